@@ -1,43 +1,141 @@
-use crate::index::{IndexEntry, NoteIndex};
-use crate::note::Note;
-use crate::shortcuts::ShortcutsRegistry;
-use chrono::Utc;
-use std::collections::HashMap;
+use crate::check::{CheckReport, Issue, NoteIssues};
+use crate::error::{Error, MetaRecordError, Result};
+use crate::events::{NoteEvent, Reporter, SilentReporter};
+use crate::export::{self, HtmlExport, ListedNote, NotePage, SearchEntry};
+use crate::fsck::{FsckIssue, FsckReport};
+use crate::index::{FolderRules, IndexEntry, NoteIndex, validate_tag};
+use crate::links::{Backlink, BacklinkSource, Link, normalize_path};
+use crate::meta::{
+    FieldChange, MetaImport, MetaRecord, MetaUpdate, NoteMetaChange, UnmatchedRecord,
+};
+use crate::note::{LinkTarget, Note, Shortcut};
+use crate::query::Query;
+use crate::reference::{self, FUZZY_THRESHOLD, NoteRef};
+use crate::scan::FolderScanner;
+use crate::search::{self, QueryTerm, SearchHit, SearchIndex};
+use crate::shortcuts::{ExpandedContent, ShortcutsRegistry, expand_aliases, validate_alias};
+use crate::storage::{self, DirLock};
+use crate::template::{self, TEMPLATES_DIR, TemplateVars};
+use crate::watch::NoteChange;
+use chrono::{DateTime, Local, Utc};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+// 마지막 목록/검색 결과 (한 번 실행하는 하위 명령어 사이에서도 #N을 쓰기 위해 저장)
+const LAST_RESULTS_FILE: &str = ".last_results.json";
+
 pub struct NoteApp {
     pub notes: HashMap<Uuid, Note>,
     pub index: NoteIndex,
     pub shortcuts: ShortcutsRegistry,
+    pub search_index: SearchIndex,
     pub notes_dir: PathBuf,
+    // 마지막으로 표시한 목록/검색 결과 (#N 참조용, .last_results.json에 저장)
+    last_results: Vec<Uuid>,
+    reporter: Box<dyn Reporter>,
+    // 앱이 살아 있는 동안 노트 디렉토리 잠금 유지
+    // None이면 다른 프로세스가 잠근 디렉토리를 읽기 전용으로 연 앱 (아무것도 저장하지 않음)
+    lock: Option<DirLock>,
 }
 
 impl NoteApp {
-    pub fn new(notes_dir: PathBuf) -> Result<Self, String> {
+    // 진행 상황을 알리지 않는 앱
+    pub fn new(notes_dir: PathBuf) -> Result<Self> {
+        Self::with_reporter(notes_dir, SilentReporter)
+    }
+
+    // 노트 발견, 삭제, UUID 추가 등의 이벤트를 reporter로 전달하는 앱
+    // 다른 md_filer가 디렉토리를 잠그고 있으면 Error::Locked
+    pub fn with_reporter(notes_dir: PathBuf, reporter: impl Reporter + 'static) -> Result<Self> {
+        Self::open(notes_dir, reporter, false)
+    }
+
+    // 읽기만 하는 명령용 (list, show, search 등)
+    // 잠글 수 있으면 with_reporter와 같고, 다른 프로세스(watch 등)가 잠그고 있으면
+    // 인덱스와 노트 파일에 아무것도 쓰지 않는 읽기 전용으로 엶
+    pub fn for_reading(notes_dir: PathBuf, reporter: impl Reporter + 'static) -> Result<Self> {
+        Self::open(notes_dir, reporter, true)
+    }
+
+    fn open(
+        notes_dir: PathBuf,
+        reporter: impl Reporter + 'static,
+        allow_read_only: bool,
+    ) -> Result<Self> {
         // 디렉토리 생성
         if !notes_dir.exists() {
-            fs::create_dir_all(&notes_dir)
-                .map_err(|e| format!("노트 디렉토리 생성 실패: {}", e))?;
+            fs::create_dir_all(&notes_dir).map_err(|source| Error::CreateDir {
+                path: notes_dir.clone(),
+                source,
+            })?;
         }
 
+        // 다른 md_filer 프로세스가 같은 디렉토리를 고치지 못하게 잠금
+        let lock = match DirLock::acquire(&notes_dir) {
+            Ok(lock) => Some(lock),
+            Err(Error::Locked(_)) if allow_read_only => {
+                reporter.report(NoteEvent::OpenedReadOnly {
+                    path: notes_dir.to_string_lossy().to_string(),
+                });
+                None
+            }
+            Err(error) => return Err(error),
+        };
+
         let index_path = notes_dir.join(".index.json");
         let shortcuts_path = notes_dir.join(".shortcuts.json");
+        let search_index_path = notes_dir.join(".search_index.json");
+
+        // 이전 실행이 저장 도중 중단되어 남긴 임시 파일 정리
+        // (읽기 전용이면 잠근 프로세스가 쓰는 중일 수 있으므로 건드리지 않음)
+        if lock.is_some() {
+            for path in [&index_path, &shortcuts_path, &search_index_path] {
+                let is_json =
+                    |bytes: &[u8]| serde_json::from_slice::<serde_json::Value>(bytes).is_ok();
+                if let Some(restored) = storage::recover_temp_file(path, is_json)? {
+                    reporter.report(NoteEvent::TempFileRecovered {
+                        path: path.to_string_lossy().to_string(),
+                        restored,
+                    });
+                }
+            }
+        }
 
-        // 인덱스와 shortcuts 로드 또는 생성
-        let mut index = if index_path.exists() {
+        // 인덱스와 shortcuts 로드 또는 생성 (이전 버전 파일은 현재 버전으로 옮김)
+        let (mut index, index_migration) = if index_path.exists() {
             NoteIndex::load(&index_path)?
         } else {
-            NoteIndex::new()
+            (NoteIndex::new(), None)
         };
 
-        let shortcuts = if shortcuts_path.exists() {
+        let (shortcuts, shortcuts_migration) = if shortcuts_path.exists() {
             ShortcutsRegistry::load(&shortcuts_path)?
         } else {
-            ShortcutsRegistry::new()
+            (ShortcutsRegistry::new(), None)
+        };
+
+        for migration in [index_migration, shortcuts_migration].into_iter().flatten() {
+            reporter.report(NoteEvent::Migrated(migration));
+        }
+
+        // 검색 인덱스는 노트에서 다시 만들 수 있으므로 읽기 실패 시 새로 생성
+        let search_index = if search_index_path.exists() {
+            SearchIndex::load(&search_index_path).unwrap_or_else(|error| {
+                reporter.report(NoteEvent::SearchIndexRebuilt { error });
+                SearchIndex::new()
+            })
+        } else {
+            SearchIndex::new()
         };
 
+        // 이전 실행의 목록/검색 결과는 없거나 깨졌으면 비워 둠
+        let last_results = fs::read(notes_dir.join(LAST_RESULTS_FILE))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+
         // 기본 폴더가 watched_folders에 없으면 추가
         let default_folder = notes_dir.to_string_lossy().to_string();
         if index.get_watched_folders().is_empty() {
@@ -48,199 +146,800 @@ impl NoteApp {
             notes: HashMap::new(),
             index,
             shortcuts,
+            search_index,
             notes_dir,
+            last_results,
+            reporter: Box::new(reporter),
+            lock,
         };
 
+        if !app.is_read_only() {
+            app.recover_note_temp_files()?;
+        }
         app.load_notes()?;
         Ok(app)
     }
 
-    pub fn load_notes(&mut self) -> Result<(), String> {
+    // 다른 프로세스가 잠근 디렉토리를 읽기 전용으로 열었는지
+    pub fn is_read_only(&self) -> bool {
+        self.lock.is_none()
+    }
+
+    // 노트 파일을 쓰다 중단되어 남은 임시 파일 (.a.md.tmp) 정리
+    // 원본이 없으면 임시 파일로 복구, 있으면 원본이 이전 내용 그대로이므로 임시 파일 삭제
+    fn recover_note_temp_files(&self) -> Result<()> {
+        let is_text = |bytes: &[u8]| std::str::from_utf8(bytes).is_ok();
+        for scanner in self.folder_scanners()? {
+            for temp in scanner.scan_temp_files()? {
+                let Some(path) = storage::temp_original(&temp) else {
+                    continue;
+                };
+                let is_valid = |bytes: &[u8]| !path.exists() && is_text(bytes);
+                if let Some(restored) = storage::recover_temp_file(&path, is_valid)? {
+                    self.reporter.report(NoteEvent::TempFileRecovered {
+                        path: path.to_string_lossy().to_string(),
+                        restored,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load_notes(&mut self) -> Result<()> {
         // 먼저 인덱스와 파일 시스템 동기화
         self.sync_with_filesystem()?;
 
-        // 기존 노트 초기화
-        self.notes.clear();
+        for folder_path in self.index.get_watched_folders() {
+            if !PathBuf::from(folder_path).exists() {
+                self.reporter.report(NoteEvent::FolderMissing {
+                    path: folder_path.clone(),
+                });
+            }
+        }
 
-        // 모든 watched_folders를 스캔
-        for folder_path in self.index.get_watched_folders().clone() {
-            let folder = PathBuf::from(&folder_path);
-            if !folder.exists() {
-                eprintln!("⚠️  폴더가 존재하지 않습니다: {}", folder_path);
-                continue;
+        // 모든 watched_folders를 재귀적으로 스캔 (바뀐 파일만 다시 읽음)
+        let mut found = HashSet::new();
+        let mut dirty = false;
+        for path in self.scan_watched_folders()? {
+            if let Some((id, changed)) = self.index_file(&path)? {
+                found.insert(id);
+                dirty |= changed;
             }
+        }
+
+        // 더 이상 없는 노트 제거
+        self.notes.retain(|id, _| found.contains(id));
+
+        // 바뀐 것이 있을 때만 인덱스 저장
+        if !self.is_read_only() && (dirty || !self.notes_dir.join(".index.json").exists()) {
+            self.save_index()?;
+        }
+
+        // 검색 인덱스 갱신 (바뀐 노트만 다시 색인)
+        self.update_search_index()?;
+        Ok(())
+    }
+
+    // 파일 하나를 읽어 노트와 인덱스 항목 갱신
+    // 반환값: (UUID, 인덱스가 바뀌었는지). 파싱에 실패하면 None
+    fn index_file(&mut self, path: &Path) -> Result<Option<(Uuid, bool)>> {
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let file_path = path.to_string_lossy().to_string();
+
+        let read_error = |source| Error::Read {
+            path: path.to_path_buf(),
+            source,
+        };
+        let (modified, size) = file_stamp(path).map_err(read_error)?;
 
-            let entries = fs::read_dir(&folder)
-                .map_err(|e| format!("디렉토리 읽기 실패 {}: {}", folder_path, e))?;
+        // 수정 시각과 크기가 같으면 다시 읽지 않음: 이미 로드된 노트는 인덱스의 태그 등만
+        // 반영하고, 시작할 때는 인덱스 항목으로 노트를 만듦 (본문은 필요할 때 읽음)
+        if let Some((id, entry)) = self.index.find_by_path(&file_path)
+            && entry.modified == Some(modified)
+            && entry.size == size
+        {
+            match self.notes.get_mut(&id) {
+                Some(note) => {
+                    note.filename = entry.filename.clone();
+                    note.tags = entry.tags.clone();
+                    note.created_at = entry.created_at;
+                }
+                None => {
+                    self.notes.insert(id, Note::from_index(id, entry));
+                }
+            }
+            return Ok(Some((id, false)));
+        }
 
-            for entry in entries {
-                let entry = entry.map_err(|e| format!("엔트리 읽기 실패: {}", e))?;
-                let path = entry.path();
+        let content = fs::read_to_string(path).map_err(read_error)?;
 
-                if path.extension().and_then(|s| s.to_str()) == Some("md") {
-                    let filename = entry.file_name().to_string_lossy().to_string();
-                    let file_path = path.to_string_lossy().to_string();
+        let (id, is_new) = self.identify_note(&content, &file_path);
 
-                    // 인덱스에서 UUID 찾기 또는 새로 생성
-                    let (id, is_new) = if let Some((id, _)) = self.index.find_by_filename(&filename)
-                    {
-                        (id, false)
-                    } else {
-                        (Uuid::new_v4(), true)
-                    };
+        // 인덱스에서 생성일과 태그 가져오기, 수정일은 파일 내용이 바뀌었을 때만 파일의 수정 시각
+        // (touch나 UUID 주입만 있었으면 인덱스의 수정일 유지, frontmatter 수정은 변경으로 봄)
+        let mut hash = Note::file_hash(&content);
+        let (tags, created_at, updated_at) = match self.index.get(&id) {
+            Some(entry) if entry.content_hash == hash => {
+                (entry.tags.clone(), entry.created_at, entry.updated_at)
+            }
+            Some(entry) => (entry.tags.clone(), entry.created_at, modified),
+            None => (Vec::new(), file_created(path).unwrap_or(modified), modified),
+        };
 
-                    let content = fs::read_to_string(&path)
-                        .map_err(|e| format!("파일 읽기 실패 {}: {}", filename, e))?;
+        let mut note = match Note::from_markdown(
+            id,
+            filename.clone(),
+            content.clone(),
+            tags.clone(),
+            created_at,
+            updated_at,
+        ) {
+            Ok(note) => note,
+            Err(error) => {
+                self.reporter.report(NoteEvent::ParseFailed {
+                    path: file_path,
+                    error,
+                });
+                return Ok(None);
+            }
+        };
 
-                    // 인덱스에서 타임스탬프와 태그 가져오기
-                    let now = Utc::now();
-                    let (tags, created_at, updated_at) =
-                        if let Some(entry) = self.index.get_entry(&id) {
-                            (entry.tags.clone(), entry.created_at, now)
-                        } else {
-                            (Vec::new(), now, now)
-                        };
+        // 중복 UUID 파일은 경로로 식별하므로 인덱스 키와 맞춤
+        note.id = id;
+        note.meta.id = Some(id);
 
-                    match Note::from_markdown(
+        // UUID가 파일에 없으면 추가 (파일이 바뀌므로 수정 시각/크기를 다시 읽음)
+        // 읽기 전용이면 파일을 고치지 않음 (잠근 프로세스가 주입함)
+        let (mut modified, mut size) = (modified, size);
+        if !self.is_read_only() && !Note::has_uuid_in_frontmatter(&content) {
+            match self.inject_uuid_to_file(path, &content, &note) {
+                Ok(injected) => {
+                    hash = Note::file_hash(&injected);
+                    note.set_file_content(&injected);
+                    self.reporter.report(NoteEvent::UuidInjected {
                         id,
-                        filename.clone(),
-                        content.clone(),
-                        tags.clone(),
-                        created_at,
-                        updated_at,
-                    ) {
-                        Ok(note) => {
-                            // UUID가 파일에 없으면 추가
-                            if !Note::has_uuid_in_frontmatter(&content) {
-                                if let Err(e) = self.inject_uuid_to_file(&path, &note) {
-                                    eprintln!("⚠️  UUID 주입 실패 {}: {}", filename, e);
-                                } else {
-                                    println!("✏️  UUID 추가됨: {} ({})", filename, note.id);
-                                }
-                            }
-
-                            // 인덱스 업데이트 (기존 태그 유지)
-                            let entry = IndexEntry {
-                                filename: filename.clone(),
-                                file_path: file_path.clone(),
-                                title: note.title.clone(),
-                                created_at: note.created_at,
-                                updated_at: note.updated_at,
-                                tags: tags,
-                            };
-
-                            if is_new {
-                                println!("📄 새 노트 발견: {}", filename);
-                            }
-
-                            self.index.add_entry(id, entry);
-                            self.notes.insert(id, note);
-                        }
-                        Err(e) => {
-                            eprintln!("노트 파싱 실패 {}: {}", filename, e);
-                        }
+                        path: file_path.clone(),
+                    });
+                    if let Ok(stamp) = file_stamp(path) {
+                        (modified, size) = stamp;
                     }
                 }
+                Err(error) => self.reporter.report(NoteEvent::UuidInjectFailed {
+                    path: file_path.clone(),
+                    error,
+                }),
             }
         }
 
-        // 인덱스 저장
-        self.save_index()?;
-        Ok(())
+        // 인덱스 업데이트 (기존 태그 유지)
+        let entry = IndexEntry {
+            filename: filename.clone(),
+            file_path,
+            title: note.title.clone(),
+            created_at: note.created_at,
+            updated_at: note.updated_at,
+            tags,
+            content_hash: hash,
+            modified: Some(modified),
+            size,
+        };
+
+        if is_new {
+            self.reporter.report(NoteEvent::NoteDiscovered {
+                id,
+                path: entry.file_path.clone(),
+            });
+        }
+
+        let changed = self.index.get(&id) != Some(&entry);
+        self.index.add_entry(id, entry);
+        self.notes.insert(id, note);
+        Ok(Some((id, changed)))
     }
 
-    // 파일 시스템과 인덱스 동기화
-    pub fn sync_with_filesystem(&mut self) -> Result<(), String> {
-        // 1. 모든 watched_folders에서 현재 파일 목록 가져오기
-        let mut existing_files = std::collections::HashSet::new();
+    // 파일의 노트 UUID 결정: frontmatter UUID → 전체 경로 순서
+    // 반환값: (UUID, 새 노트 여부)
+    fn identify_note(&mut self, content: &str, file_path: &str) -> (Uuid, bool) {
+        let path_match = self.index.find_by_path(file_path).map(|(id, _)| id);
 
-        for folder_path in self.index.get_watched_folders().clone() {
-            let folder = PathBuf::from(&folder_path);
-            if !folder.exists() {
-                continue;
+        let Some(file_id) = Note::id_in_frontmatter(content) else {
+            return match path_match {
+                Some(id) => (id, false),
+                None => (Uuid::new_v4(), true),
+            };
+        };
+
+        match self.index.get(&file_id) {
+            // 다른 파일이 이미 같은 UUID를 쓰고 있음 (복사된 파일 등)
+            Some(entry) if entry.file_path != file_path => {
+                self.reporter.report(NoteEvent::DuplicateUuid {
+                    id: file_id,
+                    path: file_path.to_string(),
+                });
+                match path_match {
+                    Some(id) if id != file_id => (id, false),
+                    _ => (Uuid::new_v4(), true),
+                }
+            }
+            Some(_) => (file_id, false),
+            None => match path_match {
+                // 인덱스에 다른 UUID로 등록되어 있으면 파일의 UUID로 옮김
+                Some(old_id) => {
+                    self.rekey_note(&old_id, file_id);
+                    (file_id, false)
+                }
+                None => (file_id, true),
+            },
+        }
+    }
+
+    // 인덱스 항목과 단축어를 새 UUID로 옮김
+    fn rekey_note(&mut self, old_id: &Uuid, new_id: Uuid) {
+        if let Some(entry) = self.index.remove_entry(old_id) {
+            self.index.add_entry(new_id, entry);
+        }
+        if let Some(shortcuts) = self.shortcuts.remove_shortcuts(old_id) {
+            self.shortcuts.shortcuts.insert(new_id, shortcuts);
+        }
+        for shortcuts in self.shortcuts.shortcuts.values_mut() {
+            for shortcut in shortcuts.values_mut() {
+                if let LinkTarget::Note { id } = &mut shortcut.target
+                    && id == old_id
+                {
+                    *id = new_id;
+                }
             }
+        }
+        if self.is_read_only() {
+            return;
+        }
+        if let Err(error) = self.save_shortcuts() {
+            self.reporter.report(NoteEvent::SaveFailed { error });
+        }
+    }
 
-            let entries = fs::read_dir(&folder)
-                .map_err(|e| format!("디렉토리 읽기 실패 {}: {}", folder_path, e))?;
+    // 파일 감시 이벤트로 들어온 경로만 반영 (생성/수정/삭제/이동)
+    // 폴더가 새로 생기면 전체를 다시 스캔
+    pub fn refresh_paths(&mut self, paths: &[PathBuf]) -> Result<Vec<NoteChange>> {
+        let before = self.file_snapshot();
+        let paths: Vec<PathBuf> = paths.iter().map(|p| self.to_index_path(p)).collect();
 
-            for entry in entries {
-                let entry = entry.map_err(|e| format!("엔트리 읽기 실패: {}", e))?;
-                let path = entry.path();
+        if paths.iter().any(|path| path.is_dir()) {
+            self.load_notes()?;
+            return Ok(self.diff_snapshot(&before));
+        }
+
+        let scanners = self.folder_scanners()?;
+        let accepted = |path: &Path| path.is_file() && scanners.iter().any(|s| s.accepts(path));
+
+        let mut missing = Vec::new();
+        let mut appeared = Vec::new();
+        let mut files = Vec::new();
+        for path in &paths {
+            if accepted(path) {
+                let path_str = path.to_string_lossy().to_string();
+                if self.index.find_by_path(&path_str).is_none() {
+                    appeared.push(path_str);
+                }
+                files.push(path.clone());
+                continue;
+            }
 
-                if path.extension().and_then(|s| s.to_str()) == Some("md") {
-                    let file_path = path.to_string_lossy().to_string();
-                    existing_files.insert(file_path);
+            // 삭제되었거나 규칙에서 빠진 파일, 또는 삭제된 폴더 아래의 파일
+            for (id, entry) in self.index.iter() {
+                let entry_path = Path::new(&entry.file_path);
+                if entry_path.starts_with(path) && !accepted(entry_path) && !missing.contains(id) {
+                    missing.push(*id);
                 }
             }
         }
 
-        // 2. 인덱스에서 삭제된 파일 제거
-        let mut to_remove = Vec::new();
-        for (id, entry) in self.index.mappings.iter() {
-            let entry_path = if entry.file_path.is_empty() {
-                // 구버전 호환: file_path가 없으면 notes_dir + filename 사용
-                self.notes_dir
-                    .join(&entry.filename)
-                    .to_string_lossy()
-                    .to_string()
-            } else {
-                entry.file_path.clone()
-            };
+        let renamed = self.match_renames(&mut missing, appeared);
 
-            if !existing_files.contains(&entry_path) {
-                println!("🗑️  삭제된 노트 감지: {}", entry.filename);
-                to_remove.push(*id);
+        let mut dirty = renamed > 0;
+        let mut search_changed = false;
+        for path in &files {
+            if let Some((id, changed)) = self.index_file(path)? {
+                dirty |= changed;
+                if let Some(note) = self.notes.get(&id)
+                    && (note.is_loaded() || !self.search_index.docs.contains_key(&id))
+                {
+                    search_changed |= self
+                        .search_index
+                        .update_document(id, &Self::searchable_text(note));
+                }
             }
         }
 
-        for id in &to_remove {
+        for id in &missing {
             self.index.remove_entry(id);
+            self.notes.remove(id);
             self.shortcuts.remove_shortcuts(id);
+            search_changed |= self.search_index.remove_document(id);
         }
 
-        if !to_remove.is_empty() {
+        if !missing.is_empty() {
+            self.save_shortcuts()?;
+        }
+        if dirty || !missing.is_empty() {
             self.save_index()?;
+        }
+        if search_changed {
+            self.save_search_index()?;
+        }
+
+        Ok(self.diff_snapshot(&before))
+    }
+
+    // 노트 하나만 다시 읽기 (편집기로 고친 뒤 등, 전체 새로고침 없이)
+    // 파일이 사라졌으면 인덱스에서 제거
+    pub fn reload_note(&mut self, id: &Uuid) -> Result<Vec<NoteChange>> {
+        let path = self
+            .index
+            .get(id)
+            .map(|entry| PathBuf::from(&entry.file_path))
+            .ok_or(Error::NoteNotFound(*id))?;
+        self.refresh_paths(&[path])
+    }
+
+    // 감시 이벤트의 경로를 인덱스에 저장된 경로 형식으로 맞춤
+    // (감시 도구가 절대 경로를 주더라도 watched folder가 상대 경로면 상대 경로로)
+    fn to_index_path(&self, path: &Path) -> PathBuf {
+        for folder in self.index.get_watched_folders() {
+            let folder = Path::new(folder);
+            if path.starts_with(folder) {
+                return path.to_path_buf();
+            }
+            if let Ok(canonical) = fs::canonicalize(folder)
+                && let Ok(relative) = path.strip_prefix(&canonical)
+            {
+                return folder.join(relative);
+            }
+        }
+        path.to_path_buf()
+    }
+
+    // 노트별 (경로, 수정 시각, 크기)
+    fn file_snapshot(&self) -> HashMap<Uuid, (String, Option<DateTime<Utc>>, u64)> {
+        self.index
+            .iter()
+            .map(|(id, e)| (*id, (e.file_path.clone(), e.modified, e.size)))
+            .collect()
+    }
+
+    fn diff_snapshot(
+        &self,
+        before: &HashMap<Uuid, (String, Option<DateTime<Utc>>, u64)>,
+    ) -> Vec<NoteChange> {
+        let after = self.file_snapshot();
+        let mut changes = Vec::new();
+
+        for (id, (path, modified, size)) in &after {
+            match before.get(id) {
+                None => changes.push(NoteChange::Added {
+                    id: *id,
+                    path: path.clone(),
+                }),
+                Some((old_path, _, _)) if old_path != path => changes.push(NoteChange::Moved {
+                    id: *id,
+                    from: old_path.clone(),
+                    to: path.clone(),
+                }),
+                Some((_, old_modified, old_size))
+                    if old_modified != modified || old_size != size =>
+                {
+                    changes.push(NoteChange::Updated {
+                        id: *id,
+                        path: path.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for (id, (path, _, _)) in before {
+            if !after.contains_key(id) {
+                changes.push(NoteChange::Removed {
+                    id: *id,
+                    path: path.clone(),
+                });
+            }
+        }
+
+        changes.sort_by(|a, b| change_path(a).cmp(change_path(b)));
+        changes
+    }
+
+    // 변경된 노트만 역색인에 반영하고, 바뀐 것이 있으면 저장
+    fn update_search_index(&mut self) -> Result<()> {
+        let mut changed = false;
+        for (id, note) in &self.notes {
+            // 파일을 다시 읽지 않은 노트는 이미 색인되어 있으면 건너뜀 (본문을 읽지 않도록)
+            if !note.is_loaded() && self.search_index.docs.contains_key(id) {
+                continue;
+            }
+            changed |= self
+                .search_index
+                .update_document(*id, &Self::searchable_text(note));
+        }
+
+        let ids: HashSet<Uuid> = self.notes.keys().copied().collect();
+        changed |= self.search_index.retain_documents(&ids) > 0;
+
+        if self.is_read_only() {
+            return Ok(());
+        }
+        if changed || !self.notes_dir.join(".search_index.json").exists() {
+            self.save_search_index()?;
+        }
+        Ok(())
+    }
+
+    // 색인 대상 텍스트: 제목 + 본문
+    fn searchable_text(note: &Note) -> String {
+        format!("{}\n{}", note.title, note.content())
+    }
+
+    // 파일 시스템과 인덱스 동기화
+    pub fn sync_with_filesystem(&mut self) -> Result<()> {
+        // 1. 모든 watched_folders에서 현재 파일 목록 가져오기
+        let existing_files: HashSet<String> = self
+            .scan_watched_folders()?
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+
+        // 2. 파일이 사라진 인덱스 항목 찾기
+        let mut indexed_files = HashSet::new();
+        let mut missing = Vec::new();
+        for (id, entry) in self.index.iter() {
+            if existing_files.contains(&entry.file_path) {
+                indexed_files.insert(entry.file_path.clone());
+            } else {
+                missing.push(*id);
+            }
+        }
+
+        // 3. 새로 나타난 파일을 사라진 항목과 짝지어 이름 변경/이동으로 처리
+        let appeared: Vec<String> = existing_files
+            .into_iter()
+            .filter(|path| !indexed_files.contains(path))
+            .collect();
+        let renamed = self.match_renames(&mut missing, appeared);
+
+        // 4. 짝이 없는 항목은 삭제된 노트로 처리
+        for id in &missing {
+            if let Some(entry) = self.index.get(id) {
+                self.reporter.report(NoteEvent::NoteDeleted {
+                    id: *id,
+                    path: entry.file_path.clone(),
+                });
+            }
+            self.index.remove_entry(id);
+            self.shortcuts.remove_shortcuts(id);
+        }
+
+        if self.is_read_only() {
+            return Ok(());
+        }
+        if !missing.is_empty() {
             self.save_shortcuts()?;
-            println!("✅ 인덱스 정리 완료: {}개 항목 제거", to_remove.len());
+            self.reporter.report(NoteEvent::IndexCleaned {
+                removed: missing.len(),
+            });
+        }
+        if !missing.is_empty() || renamed > 0 {
+            self.save_index()?;
+        }
+
+        Ok(())
+    }
+
+    // 새로 나타난 파일을 사라진 인덱스 항목과 짝지어 경로 갱신
+    // (frontmatter UUID가 같거나, UUID가 없으면 내용 해시가 같은 경우). 짝지은 개수 반환
+    fn match_renames(&mut self, missing: &mut Vec<Uuid>, mut appeared: Vec<String>) -> usize {
+        appeared.sort();
+
+        let mut renamed = 0;
+        for path in appeared {
+            if missing.is_empty() {
+                break;
+            }
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+
+            let matched = match Note::id_in_frontmatter(&content) {
+                Some(id) => missing.iter().position(|m| *m == id),
+                None => {
+                    let hash = Note::file_hash(&content);
+                    let candidates: Vec<usize> = missing
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, id)| {
+                            self.index.get(id).is_some_and(|e| e.content_hash == hash)
+                        })
+                        .map(|(i, _)| i)
+                        .collect();
+                    // 같은 본문이 여럿이면 어느 것인지 알 수 없으므로 새 노트로 취급
+                    (candidates.len() == 1).then(|| candidates[0])
+                }
+            };
+
+            let Some(position) = matched else {
+                continue;
+            };
+            let id = missing.swap_remove(position);
+            if let Some(from) = self.index.move_entry(&id, path.clone()) {
+                self.reporter
+                    .report(NoteEvent::NoteMoved { id, from, to: path });
+                renamed += 1;
+            }
+        }
+        renamed
+    }
+
+    // 모든 watched_folders를 폴더별 규칙에 따라 재귀 스캔 (중복 경로 제거)
+    fn scan_watched_folders(&self) -> Result<Vec<PathBuf>> {
+        let mut seen = HashSet::new();
+        let mut files = Vec::new();
+
+        for scanner in self.folder_scanners()? {
+            for path in scanner.scan()? {
+                if seen.insert(path.clone()) {
+                    files.push(path);
+                }
+            }
         }
 
+        Ok(files)
+    }
+
+    // 존재하는 watched_folders의 스캐너
+    fn folder_scanners(&self) -> Result<Vec<FolderScanner>> {
+        self.index
+            .get_watched_folders()
+            .iter()
+            .filter(|folder_path| Path::new(folder_path).exists())
+            .map(|folder_path| {
+                FolderScanner::new(
+                    Path::new(folder_path),
+                    &self.index.get_folder_rules(folder_path),
+                )
+            })
+            .collect()
+    }
+
+    // 읽기 전용으로 연 앱은 아무것도 저장하지 않음 (변경 명령은 Error::Locked)
+    fn ensure_writable(&self) -> Result<()> {
+        if self.is_read_only() {
+            return Err(Error::Locked(self.notes_dir.clone()));
+        }
         Ok(())
     }
 
-    pub fn save_index(&self) -> Result<(), String> {
+    pub fn save_index(&self) -> Result<()> {
+        self.ensure_writable()?;
         let index_path = self.notes_dir.join(".index.json");
         self.index.save(&index_path)
     }
 
-    pub fn save_shortcuts(&self) -> Result<(), String> {
+    pub fn save_search_index(&self) -> Result<()> {
+        self.ensure_writable()?;
+        let search_index_path = self.notes_dir.join(".search_index.json");
+        self.search_index.save(&search_index_path)
+    }
+
+    pub fn save_shortcuts(&self) -> Result<()> {
+        self.ensure_writable()?;
         let shortcuts_path = self.notes_dir.join(".shortcuts.json");
         self.shortcuts.save(&shortcuts_path)
     }
 
-    // 파일에 UUID 주입
-    fn inject_uuid_to_file(&self, path: &PathBuf, note: &Note) -> Result<(), String> {
-        let markdown = note.to_markdown();
-        fs::write(path, markdown).map_err(|e| format!("파일 쓰기 실패: {}", e))
+    // 파일에 UUID 주입 (원본 텍스트에 id 줄만 추가하고 나머지는 그대로 유지)
+    // 반환값: 새로 쓴 파일 내용
+    fn inject_uuid_to_file(&self, path: &Path, content: &str, note: &Note) -> Result<String> {
+        let markdown = Note::inject_id(content, note.id, &note.title);
+        storage::write_atomic(path, &markdown)?;
+        Ok(markdown)
     }
 
     pub fn list_notes(&self) -> Vec<(&Uuid, &Note)> {
         let mut notes: Vec<_> = self.notes.iter().collect();
         // 최신순으로 정렬
-        notes.sort_by(|a, b| b.1.updated_at.cmp(&a.1.updated_at));
+        notes.sort_by_key(|(_, note)| std::cmp::Reverse(note.updated_at));
         notes
     }
 
+    // 표시용 경로: 노트 디렉토리 기준 상대 경로, 밖에 있으면 "watched 폴더 이름/상대 경로"
+    pub fn display_path(&self, id: &Uuid) -> String {
+        let Some(entry) = self.index.get(id) else {
+            return String::new();
+        };
+        let path = Path::new(&entry.file_path);
+
+        if let Ok(relative) = path.strip_prefix(&self.notes_dir) {
+            return relative.to_string_lossy().to_string();
+        }
+
+        self.index
+            .get_watched_folders()
+            .iter()
+            .map(Path::new)
+            .filter_map(|folder| Some((folder, path.strip_prefix(folder).ok()?)))
+            .max_by_key(|(folder, _)| folder.as_os_str().len())
+            .map(|(folder, relative)| {
+                folder
+                    .file_name()
+                    .map(|name| Path::new(name).join(relative))
+                    .unwrap_or_else(|| relative.to_path_buf())
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .unwrap_or_else(|| entry.file_path.clone())
+    }
+
+    // 다른 노트와 파일명이 같은지 (목록에서 폴더를 함께 보여줄 때 사용)
+    pub fn has_duplicate_filename(&self, id: &Uuid) -> bool {
+        let Some(note) = self.notes.get(id) else {
+            return false;
+        };
+        self.notes
+            .iter()
+            .any(|(other_id, other)| other_id != id && other.filename == note.filename)
+    }
+
     pub fn get_note(&self, id: &Uuid) -> Option<&Note> {
         self.notes.get(id)
     }
 
+    // 노트 참조 해석: #N → UUID → 파일명 → 제목 → UUID 앞부분 → 비슷한 제목 순서
+    // # 없는 숫자는 결과 번호로 먼저 보고, 범위 밖이면 이름/UUID 앞부분으로 찾음
+    // 여러 노트가 맞으면 후보와 함께 오류
+    pub fn resolve_note(&self, reference: &str) -> Result<Uuid> {
+        match NoteRef::parse(reference) {
+            NoteRef::Result(number) => self.result_note(number),
+            NoteRef::Number(number) => match self.result_note(number) {
+                Ok(id) => Ok(id),
+                Err(error @ (Error::NoResultSet | Error::ResultOutOfRange { .. })) => {
+                    let prefix = reference::as_prefix(reference.trim());
+                    match self.resolve_name(reference, prefix) {
+                        Err(Error::NoteRefNotFound(_)) => Err(error),
+                        resolved => resolved,
+                    }
+                }
+                Err(error) => Err(error),
+            },
+            NoteRef::Id(id) if self.notes.contains_key(&id) => Ok(id),
+            NoteRef::Id(id) => Err(Error::NoteNotFound(id)),
+            NoteRef::Prefix(prefix) => self.resolve_name(reference, Some(prefix)),
+            NoteRef::Name(_) => self.resolve_name(reference, None),
+        }
+    }
+
+    // 마지막으로 표시한 결과의 N번째 (1부터)
+    fn result_note(&self, number: usize) -> Result<Uuid> {
+        if self.last_results.is_empty() {
+            return Err(Error::NoResultSet);
+        }
+        let id = number
+            .checked_sub(1)
+            .and_then(|index| self.last_results.get(index))
+            .ok_or(Error::ResultOutOfRange {
+                number,
+                len: self.last_results.len(),
+            })?;
+        if !self.notes.contains_key(id) {
+            return Err(Error::NoteNotFound(*id));
+        }
+        Ok(*id)
+    }
+
+    // 파일명 → 제목 → UUID 앞부분 → 비슷한 제목 순서로 찾기
+    // (cafe.md, 2024.md처럼 16진수로 된 이름은 UUID 앞부분보다 정확한 이름이 우선)
+    fn resolve_name(&self, reference: &str, prefix: Option<String>) -> Result<Uuid> {
+        let name = reference.trim();
+
+        let by_filename = self.matching_notes(|_, note| {
+            note.filename == name || note.filename.strip_suffix(".md") == Some(name)
+        });
+        if !by_filename.is_empty() {
+            return self.single_match(reference, by_filename);
+        }
+
+        let by_title =
+            self.matching_notes(|_, note| note.title.to_lowercase() == name.to_lowercase());
+        if !by_title.is_empty() {
+            return self.single_match(reference, by_title);
+        }
+
+        if let Some(prefix) = prefix {
+            let matches = self.matching_notes(|id, _| reference::matches_prefix(id, &prefix));
+            if !matches.is_empty() {
+                return self.single_match(reference, matches);
+            }
+        }
+
+        // 가장 비슷한 제목이 하나뿐일 때만 선택
+        let mut scored: Vec<(f64, Uuid)> = self
+            .notes
+            .iter()
+            .map(|(id, note)| (reference::fuzzy_score(name, &note.title), *id))
+            .filter(|(score, _)| *score >= FUZZY_THRESHOLD)
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let best = scored.first().map(|(score, _)| *score);
+        let top: Vec<Uuid> = scored
+            .into_iter()
+            .filter(|(score, _)| Some(*score) == best)
+            .map(|(_, id)| id)
+            .collect();
+        if top.is_empty() {
+            return Err(Error::NoteRefNotFound(reference.to_string()));
+        }
+        self.single_match(reference, top)
+    }
+
+    fn matching_notes(&self, predicate: impl Fn(&Uuid, &Note) -> bool) -> Vec<Uuid> {
+        self.notes
+            .iter()
+            .filter(|(id, note)| predicate(id, note))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    // 후보가 하나면 그 노트, 여럿이면 "짧은 UUID 제목" 목록과 함께 오류
+    fn single_match(&self, reference: &str, mut ids: Vec<Uuid>) -> Result<Uuid> {
+        if ids.len() == 1 {
+            return Ok(ids[0]);
+        }
+        ids.sort_by_key(|id| self.display_path(id));
+        let candidates = ids
+            .iter()
+            .filter_map(|id| {
+                let note = self.notes.get(id)?;
+                Some(format!("{} {}", reference::short_id(id), note.title))
+            })
+            .collect();
+        Err(Error::AmbiguousNoteRef {
+            reference: reference.to_string(),
+            candidates,
+        })
+    }
+
+    // 마지막으로 표시한 목록/검색 결과 (#N 참조 대상)
+    pub fn last_results(&self) -> &[Uuid] {
+        &self.last_results
+    }
+
+    // 표시한 결과를 기억하고 저장 (저장 실패는 이벤트로만 알림, 읽기 전용이면 메모리에만)
+    pub fn remember_results(&mut self, ids: Vec<Uuid>) {
+        if ids == self.last_results {
+            return;
+        }
+        self.last_results = ids;
+        if self.is_read_only() {
+            return;
+        }
+        let saved = serde_json::to_string(&self.last_results)
+            .map_err(Error::Serialize)
+            .and_then(|json| storage::write_atomic(&self.notes_dir.join(LAST_RESULTS_FILE), json));
+        if let Err(error) = saved {
+            self.reporter.report(NoteEvent::SaveFailed { error });
+        }
+    }
+
     pub fn search_notes(&self, query: &str) -> Vec<(&Uuid, &Note)> {
         let query_lower = query.to_lowercase();
         self.notes
             .iter()
             .filter(|(_, note)| {
                 note.title.to_lowercase().contains(&query_lower)
-                    || note.content.to_lowercase().contains(&query_lower)
+                    || note.content().to_lowercase().contains(&query_lower)
                     || note
                         .tags
                         .iter()
@@ -249,6 +948,61 @@ impl NoteApp {
             .collect()
     }
 
+    // 구조화된 검색 쿼리로 노트 검색
+    // 예: tag:rust folder:@work title:"meeting" updated:>2025-01-01 -draft
+    // 일반 검색어는 역색인으로 찾고 BM25로 순위를 매김 (검색어가 없으면 최신순)
+    pub fn search(&self, query: &str) -> Result<Vec<SearchHit>> {
+        let query = Query::parse(query)?;
+        let terms = query.text_terms();
+
+        // 전문 검색어별로 포함된 노트 목록 미리 조회
+        let text_matches: HashMap<QueryTerm, HashSet<Uuid>> = query
+            .referenced_terms()
+            .into_iter()
+            .map(|term| {
+                let ids = self
+                    .search_index
+                    .term_frequencies(&term)
+                    .into_keys()
+                    .collect();
+                (term, ids)
+            })
+            .collect();
+
+        let matched: HashSet<Uuid> = self
+            .notes
+            .iter()
+            .filter(|(id, note)| {
+                let text_match =
+                    |term: &QueryTerm| text_matches.get(term).is_some_and(|ids| ids.contains(*id));
+                query.matches(note, self.index.get(id), &text_match)
+            })
+            .map(|(id, _)| *id)
+            .collect();
+
+        let scores = self.search_index.score(&terms, &matched);
+        let mut hits: Vec<SearchHit> = matched
+            .iter()
+            .filter_map(|id| {
+                let note = self.notes.get(id)?;
+                Some(SearchHit {
+                    id: *id,
+                    score: scores.get(id).copied().unwrap_or(0.0),
+                    snippet: search::find_snippet(note.content(), &terms),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score.total_cmp(&a.score).then_with(|| {
+                let a_updated = self.notes.get(&a.id).map(|n| n.updated_at);
+                let b_updated = self.notes.get(&b.id).map(|n| n.updated_at);
+                b_updated.cmp(&a_updated)
+            })
+        });
+        Ok(hits)
+    }
+
     pub fn get_notes_by_folder(&self, folder: &str) -> Vec<(&Uuid, &Note)> {
         let folder_tag = if folder.starts_with('@') {
             folder.to_string()
@@ -263,7 +1017,7 @@ impl NoteApp {
     }
 
     pub fn get_all_tags(&self) -> Vec<String> {
-        let mut tags = std::collections::HashSet::new();
+        let mut tags = HashSet::new();
 
         for note in self.notes.values() {
             for tag in &note.tags {
@@ -283,18 +1037,1002 @@ impl NoteApp {
             .collect()
     }
 
-    // 새로운 폴더를 watched_folders에 추가
-    pub fn add_watched_folder(&mut self, folder_path: String) -> Result<(), String> {
-        let folder = PathBuf::from(&folder_path);
-
+    // 새 노트 파일 만들기: 제목으로 파일명을 만들고 frontmatter에 새 UUID를 기록
+    // folder는 관리 중인 폴더 (None이면 노트 디렉토리), template은 .templates/<이름>.md
+    pub fn create_note(
+        &mut self,
+        title: &str,
+        folder: Option<&str>,
+        tags: &[String],
+        template: Option<&str>,
+    ) -> Result<Uuid> {
+        let title = title.trim();
+        if title.is_empty() {
+            return Err(Error::EmptyTitle);
+        }
+        for tag in tags {
+            validate_tag(tag)?;
+        }
+        let folders: Vec<&String> = tags.iter().filter(|t| t.starts_with('@')).collect();
+        if folders.len() > 1 {
+            return Err(Error::MultipleFolders(
+                folders.into_iter().cloned().collect(),
+            ));
+        }
+
+        let dir = match folder {
+            Some(folder) => self
+                .index
+                .get_watched_folders()
+                .iter()
+                .find(|watched| Path::new(watched) == Path::new(folder))
+                .map(PathBuf::from)
+                .ok_or_else(|| Error::FolderNotWatched(folder.to_string()))?,
+            None => self.notes_dir.clone(),
+        };
+        if !dir.is_dir() {
+            return Err(Error::FolderMissing(dir));
+        }
+
+        let id = Uuid::new_v4();
+        let content = match template {
+            Some(name) => {
+                let vars = TemplateVars {
+                    title,
+                    id,
+                    now: Local::now(),
+                };
+                let rendered = template::render(&self.read_template(name)?, &vars);
+                Note::replace_id(&rendered, id, title)
+            }
+            None => Note::inject_id(&format!("\n# {}\n", title), id, title),
+        };
+
+        // 템플릿의 frontmatter가 깨져 있으면 파일을 만들기 전에 알림
+        let now = Utc::now();
+        Note::from_markdown(id, String::new(), content.clone(), Vec::new(), now, now)?;
+
+        // 같은 이름의 파일이 있으면 -2, -3 ... 을 붙임
+        let slug = template::slugify(title);
+        let mut path = dir.join(format!("{}.md", slug));
+        let mut n = 2;
+        while path.exists() {
+            path = dir.join(format!("{}-{}.md", slug, n));
+            n += 1;
+        }
+        storage::write_atomic(&path, content)?;
+
+        self.index_file(&path)?;
+        self.set_note_tags(&id, tags.to_vec())?;
+        self.save_index()?;
+        self.update_search_index()?;
+        Ok(id)
+    }
+
+    // .templates/<이름>.md 읽기 (이름에 .md를 붙여도 됨)
+    fn read_template(&self, name: &str) -> Result<String> {
+        let file_name = if name.ends_with(".md") {
+            name.to_string()
+        } else {
+            format!("{}.md", name)
+        };
+        let path = self.notes_dir.join(TEMPLATES_DIR).join(file_name);
+        if !path.is_file() {
+            return Err(Error::TemplateNotFound(name.to_string()));
+        }
+        fs::read_to_string(&path).map_err(|source| Error::Read { path, source })
+    }
+
+    // 노트에 태그 추가 (폴더 태그는 노트당 하나만 허용)
+    pub fn add_tags(&mut self, id: &Uuid, tags: &[String]) -> Result<()> {
+        for tag in tags {
+            validate_tag(tag)?;
+        }
+
+        let current = self.note_tags(id)?;
+        let mut new_tags = current.clone();
+        for tag in tags {
+            if !new_tags.contains(tag) {
+                new_tags.push(tag.clone());
+            }
+        }
+
+        let folders: Vec<&String> = new_tags.iter().filter(|t| t.starts_with('@')).collect();
+        if folders.len() > 1 {
+            return Err(Error::MultipleFolders(
+                folders.into_iter().cloned().collect(),
+            ));
+        }
+
+        self.set_note_tags(id, new_tags)?;
+        self.save_index()
+    }
+
+    // 노트에서 태그 제거
+    pub fn remove_tags(&mut self, id: &Uuid, tags: &[String]) -> Result<()> {
+        let current = self.note_tags(id)?;
+
+        if let Some(missing) = tags.iter().find(|tag| !current.contains(tag)) {
+            return Err(Error::TagNotOnNote(missing.clone()));
+        }
+
+        let new_tags = current.into_iter().filter(|t| !tags.contains(t)).collect();
+        self.set_note_tags(id, new_tags)?;
+        self.save_index()
+    }
+
+    // 노트의 폴더 지정 또는 이동 (None이면 폴더 해제)
+    pub fn set_folder(&mut self, id: &Uuid, folder: Option<&str>) -> Result<()> {
+        let folder_tag = folder.map(|f| {
+            if f.starts_with('@') {
+                f.to_string()
+            } else {
+                format!("@{}", f)
+            }
+        });
+        if let Some(tag) = &folder_tag {
+            validate_tag(tag)?;
+        }
+
+        let mut new_tags: Vec<String> = self
+            .note_tags(id)?
+            .into_iter()
+            .filter(|t| !t.starts_with('@'))
+            .collect();
+        if let Some(tag) = folder_tag {
+            new_tags.insert(0, tag);
+        }
+
+        self.set_note_tags(id, new_tags)?;
+        self.save_index()
+    }
+
+    // 모든 노트에서 태그 이름 변경. 변경된 노트 수 반환
+    pub fn rename_tag(&mut self, old: &str, new: &str) -> Result<usize> {
+        self.merge_tags(&[old.to_string()], new)
+    }
+
+    // 여러 태그를 하나로 합치기. 변경된 노트 수 반환
+    pub fn merge_tags(&mut self, sources: &[String], target: &str) -> Result<usize> {
+        validate_tag(target)?;
+
+        // 폴더 태그와 일반 태그는 서로 합칠 수 없음 (폴더 하나 규칙 유지)
+        let target_is_folder = target.starts_with('@');
+        if let Some(source) = sources
+            .iter()
+            .find(|s| s.starts_with('@') != target_is_folder)
+        {
+            return Err(Error::MixedTagKinds {
+                source: source.clone(),
+                target: target.to_string(),
+            });
+        }
+
+        let ids: Vec<Uuid> = self
+            .index
+            .iter()
+            .filter(|(_, entry)| entry.tags.iter().any(|t| sources.contains(t)))
+            .map(|(id, _)| *id)
+            .collect();
+
+        if ids.is_empty() {
+            return Err(Error::TagNotFound(sources.to_vec()));
+        }
+
+        for id in &ids {
+            let mut new_tags = Vec::new();
+            for tag in self.note_tags(id)? {
+                let tag = if sources.contains(&tag) {
+                    target.to_string()
+                } else {
+                    tag
+                };
+                if !new_tags.contains(&tag) {
+                    new_tags.push(tag);
+                }
+            }
+
+            // 대상이 폴더이면 기존 다른 폴더는 새 폴더로 대체
+            if target_is_folder {
+                new_tags.retain(|t| !t.starts_with('@') || t == target);
+            }
+
+            self.set_note_tags(id, new_tags)?;
+        }
+
+        self.save_index()?;
+        Ok(ids.len())
+    }
+
+    // 노트의 단축어 목록 (이름순)
+    pub fn list_shortcuts(&self, id: &Uuid) -> Vec<&Shortcut> {
+        let mut shortcuts: Vec<&Shortcut> = self
+            .shortcuts
+            .get_shortcuts(id)
+            .map(|s| s.values().collect())
+            .unwrap_or_default();
+        shortcuts.sort_by(|a, b| a.alias.cmp(&b.alias));
+        shortcuts
+    }
+
+    // 단축어 추가 (이미 있는 이름이면 오류)
+    pub fn add_shortcut(
+        &mut self,
+        id: &Uuid,
+        alias: &str,
+        target: LinkTarget,
+        description: Option<String>,
+    ) -> Result<()> {
+        validate_alias(alias)?;
+        self.check_shortcut_target(id, &target)?;
+
+        if self
+            .shortcuts
+            .get_shortcuts(id)
+            .is_some_and(|s| s.contains_key(alias))
+        {
+            return Err(Error::DuplicateShortcut(alias.to_string()));
+        }
+
+        self.shortcuts.add_shortcut(
+            *id,
+            alias.to_string(),
+            Shortcut {
+                alias: alias.to_string(),
+                target,
+                description,
+            },
+        );
+        self.save_shortcuts()
+    }
+
+    // 단축어 대상/설명 변경 (None이면 기존 값 유지)
+    pub fn edit_shortcut(
+        &mut self,
+        id: &Uuid,
+        alias: &str,
+        target: Option<LinkTarget>,
+        description: Option<String>,
+    ) -> Result<()> {
+        if let Some(target) = &target {
+            self.check_shortcut_target(id, target)?;
+        }
+
+        let shortcut = self
+            .shortcuts
+            .shortcuts
+            .get_mut(id)
+            .and_then(|s| s.get_mut(alias))
+            .ok_or_else(|| Error::ShortcutNotFound(alias.to_string()))?;
+
+        if let Some(target) = target {
+            shortcut.target = target;
+        }
+        if let Some(description) = description {
+            shortcut.description = Some(description).filter(|d| !d.is_empty());
+        }
+        self.save_shortcuts()
+    }
+
+    pub fn remove_shortcut(&mut self, id: &Uuid, alias: &str) -> Result<()> {
+        self.shortcuts
+            .remove_shortcut(id, alias)
+            .ok_or_else(|| Error::ShortcutNotFound(alias.to_string()))?;
+        self.save_shortcuts()
+    }
+
+    fn check_shortcut_target(&self, id: &Uuid, target: &LinkTarget) -> Result<()> {
+        if !self.notes.contains_key(id) {
+            return Err(Error::NoteNotFound(*id));
+        }
+        if let LinkTarget::Note { id: target_id } = target
+            && !self.notes.contains_key(target_id)
+        {
+            return Err(Error::TargetNoteNotFound(*target_id));
+        }
+        Ok(())
+    }
+
+    // 본문의 {alias}를 노트의 단축어로 펼친 내용 (알 수 없는 단축어의 줄 번호는 파일 기준)
+    pub fn expand_content(&self, id: &Uuid) -> Option<ExpandedContent> {
+        let note = self.notes.get(id)?;
+        let mut expanded =
+            expand_aliases(note.content(), self.shortcuts.get_shortcuts(id), |target| {
+                self.notes
+                    .get(target)
+                    .map(|n| (n.title.clone(), n.filename.clone()))
+            });
+        for unknown in &mut expanded.unknown {
+            unknown.line_number += note.frontmatter_lines();
+        }
+        Some(expanded)
+    }
+
+    // 링크 대상 문자열을 노트 UUID로 해석
+    // UUID → 경로 (.md, 링크한 노트 기준 상대 경로 우선) → 제목 → 파일명 (확장자 제외) 순서
+    // 링크 여러 개를 해석할 때는 link_resolver()를 한 번 만들어 씀
+    pub fn resolve_link(&self, from: &Uuid, target: &str) -> Option<Uuid> {
+        self.link_resolver().resolve(from, target)
+    }
+
+    // 경로/파일명/제목별 노트 조회표 (링크마다 전체 노트를 훑지 않도록)
+    fn link_resolver(&self) -> LinkResolver<'_> {
+        let mut resolver = LinkResolver {
+            app: self,
+            by_path: HashMap::new(),
+            by_filename: HashMap::new(),
+            by_title: HashMap::new(),
+            by_stem: HashMap::new(),
+        };
+        for (id, note) in &self.notes {
+            if let Some(entry) = self.index.get(id) {
+                resolver
+                    .by_path
+                    .insert(normalize_path(Path::new(&entry.file_path)), *id);
+            }
+            LinkResolver::insert_unique(&mut resolver.by_filename, note.filename.clone(), *id);
+            LinkResolver::insert_unique(
+                &mut resolver.by_title,
+                note.title.to_ascii_lowercase(),
+                *id,
+            );
+            if let Some(stem) = Path::new(&note.filename).file_stem() {
+                LinkResolver::insert_unique(
+                    &mut resolver.by_stem,
+                    stem.to_string_lossy().to_ascii_lowercase(),
+                    *id,
+                );
+            }
+        }
+        resolver
+    }
+
+    // 노트에서 나가는 링크 (해석 결과 포함)
+    pub fn outgoing_links(&self, id: &Uuid) -> Vec<Link> {
+        self.link_resolver().outgoing_links(id)
+    }
+
+    // 이 노트를 가리키는 본문 링크와 노트 단축어
+    pub fn backlinks(&self, id: &Uuid) -> Vec<Backlink> {
+        let resolver = self.link_resolver();
+        let mut backlinks = Vec::new();
+
+        for source in self.notes.keys() {
+            if source == id {
+                continue;
+            }
+            for link in resolver.outgoing_links(source) {
+                if link.resolved == Some(*id) {
+                    backlinks.push(Backlink {
+                        source: *source,
+                        via: BacklinkSource::Link {
+                            line_number: link.raw.line_number,
+                            kind: link.raw.kind,
+                        },
+                    });
+                }
+            }
+        }
+
+        for (source, alias, _) in self.shortcuts.find_references_to_note(id) {
+            if self.notes.contains_key(&source) {
+                backlinks.push(Backlink {
+                    source,
+                    via: BacklinkSource::Shortcut { alias },
+                });
+            }
+        }
+
+        backlinks.sort_by(|a, b| {
+            let title = |id: &Uuid| self.notes.get(id).map(|n| n.title.as_str());
+            title(&a.source)
+                .cmp(&title(&b.source))
+                .then_with(|| a.source.cmp(&b.source))
+        });
+        backlinks
+    }
+
+    // 전체 링크 그래프: 노트 -> 링크/단축어로 참조하는 노트들 (중복 제거)
+    pub fn link_graph(&self) -> HashMap<Uuid, HashSet<Uuid>> {
+        let resolver = self.link_resolver();
+        let mut graph: HashMap<Uuid, HashSet<Uuid>> = HashMap::new();
+
+        for id in self.notes.keys() {
+            let targets = graph.entry(*id).or_default();
+            targets.extend(
+                resolver
+                    .outgoing_links(id)
+                    .into_iter()
+                    .filter_map(|link| link.resolved)
+                    .filter(|target| target != id),
+            );
+        }
+
+        for (source, shortcuts) in &self.shortcuts.shortcuts {
+            for shortcut in shortcuts.values() {
+                if let LinkTarget::Note { id } = &shortcut.target
+                    && self.notes.contains_key(id)
+                    && let Some(targets) = graph.get_mut(source)
+                {
+                    targets.insert(*id);
+                }
+            }
+        }
+
+        graph
+    }
+
+    // 깨진 링크, 깨진 단축어, 고립된 노트 검사 (문제가 있는 노트만 제목순으로)
+    pub fn check(&self) -> CheckReport {
+        let graph = self.link_graph();
+        let linked: HashSet<Uuid> = graph.values().flatten().copied().collect();
+        let resolver = self.link_resolver();
+
+        let mut report = CheckReport::default();
+        for (id, note) in &self.notes {
+            let mut issues: Vec<Issue> = resolver
+                .outgoing_links(id)
+                .into_iter()
+                .filter(|link| link.resolved.is_none())
+                .map(|link| Issue::UnresolvedLink {
+                    line_number: link.raw.line_number,
+                    target: link.raw.target,
+                    kind: link.raw.kind,
+                })
+                .collect();
+
+            let file_path = self
+                .index
+                .get(id)
+                .map(|e| e.file_path.clone())
+                .unwrap_or_default();
+
+            for shortcut in self.list_shortcuts(id) {
+                match &shortcut.target {
+                    LinkTarget::File { path } => {
+                        let resolved = resolve_shortcut_path(Path::new(&file_path), path);
+                        if !resolved.exists() {
+                            issues.push(Issue::MissingFile {
+                                alias: shortcut.alias.clone(),
+                                path: path.clone(),
+                            });
+                        }
+                    }
+                    LinkTarget::Note { id: target } if !self.notes.contains_key(target) => {
+                        issues.push(Issue::MissingNote {
+                            alias: shortcut.alias.clone(),
+                            id: *target,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            if graph.get(id).is_none_or(|targets| targets.is_empty()) && !linked.contains(id) {
+                issues.push(Issue::Orphan);
+            }
+
+            if !issues.is_empty() {
+                report.notes.push(NoteIssues {
+                    id: *id,
+                    title: note.title.clone(),
+                    file_path,
+                    issues,
+                });
+            }
+        }
+
+        report
+            .notes
+            .sort_by(|a, b| a.title.cmp(&b.title).then_with(|| a.id.cmp(&b.id)));
+        report
+    }
+
+    // 인덱스, 단축어, 파일의 frontmatter를 서로 대조 (파일은 새로 읽음)
+    pub fn fsck(&self) -> FsckReport {
+        let mut issues = Vec::new();
+
+        for (id, entry) in self.index.iter() {
+            let path = entry.file_path.clone();
+            let content = match fs::read_to_string(&entry.file_path) {
+                Ok(content) => content,
+                Err(_) if !Path::new(&entry.file_path).exists() => {
+                    issues.push(FsckIssue::MissingFile { id: *id, path });
+                    continue;
+                }
+                Err(_) => continue,
+            };
+
+            match Note::id_in_frontmatter(&content) {
+                None => issues.push(FsckIssue::MissingId {
+                    id: *id,
+                    path: path.clone(),
+                }),
+                Some(file_id) if file_id == *id => {}
+                // 같은 UUID를 가진 다른 파일이 인덱스에 있음
+                Some(file_id) => match self.index.get(&file_id) {
+                    Some(owner) => issues.push(FsckIssue::DuplicateId {
+                        id: file_id,
+                        path: path.clone(),
+                        kept: owner.file_path.clone(),
+                        assigned: *id,
+                    }),
+                    None => issues.push(FsckIssue::IdMismatch {
+                        id: *id,
+                        file_id,
+                        path: path.clone(),
+                    }),
+                },
+            }
+
+            let Ok(note) = Note::from_markdown(
+                *id,
+                entry.filename.clone(),
+                content,
+                Vec::new(),
+                entry.created_at,
+                entry.updated_at,
+            ) else {
+                continue;
+            };
+            if note.title != entry.title {
+                issues.push(FsckIssue::StaleTitle {
+                    id: *id,
+                    path,
+                    indexed: entry.title.clone(),
+                    actual: note.title,
+                });
+            }
+        }
+
+        for (id, shortcuts) in &self.shortcuts.shortcuts {
+            if self.index.get(id).is_none() {
+                let mut aliases: Vec<String> = shortcuts.keys().cloned().collect();
+                aliases.sort();
+                issues.push(FsckIssue::OrphanShortcuts { id: *id, aliases });
+            }
+        }
+
+        FsckReport::new(issues)
+    }
+
+    // fsck에서 찾은 문제를 고치고 고친 수를 반환
+    // 중복 UUID는 인덱스가 가리키는 파일이 UUID를 유지하고, 나머지 파일에는 새 UUID를 기록
+    pub fn repair(&mut self, issues: &[FsckIssue]) -> Result<usize> {
+        let mut repaired = 0;
+        for issue in issues {
+            match issue {
+                FsckIssue::MissingFile { id, .. } => {
+                    self.index.remove_entry(id);
+                    self.notes.remove(id);
+                    self.shortcuts.remove_shortcuts(id);
+                }
+                FsckIssue::MissingId { id, path } => {
+                    self.write_note_id(Path::new(path), *id)?;
+                }
+                FsckIssue::DuplicateId { path, assigned, .. } => {
+                    self.write_note_id(Path::new(path), *assigned)?;
+                }
+                // 파일의 UUID가 맞는 것으로 보고 인덱스 항목과 단축어를 옮김
+                FsckIssue::IdMismatch { id, file_id, path } => {
+                    self.rekey_note(id, *file_id);
+                    self.notes.remove(id);
+                    self.reload_file(Path::new(path))?;
+                }
+                FsckIssue::StaleTitle { path, .. } => {
+                    self.reload_file(Path::new(path))?;
+                }
+                FsckIssue::OrphanShortcuts { id, .. } => {
+                    self.shortcuts.remove_shortcuts(id);
+                }
+            }
+            repaired += 1;
+        }
+
+        if repaired > 0 {
+            self.save_index()?;
+            self.save_shortcuts()?;
+            self.update_search_index()?;
+        }
+        Ok(repaired)
+    }
+
+    // frontmatter의 id를 주어진 UUID로 쓰고 (없으면 추가, 있으면 교체) 노트를 다시 읽음
+    fn write_note_id(&mut self, path: &Path, id: Uuid) -> Result<()> {
+        let content = fs::read_to_string(path).map_err(|source| Error::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let title = self
+            .index
+            .get(&id)
+            .map(|entry| entry.title.clone())
+            .unwrap_or_default();
+        storage::write_atomic(path, Note::replace_id(&content, id, &title))?;
+        self.reload_file(path)
+    }
+
+    // 수정 시각이 같아도 파일을 다시 읽도록 인덱스의 기록을 지우고 다시 색인
+    fn reload_file(&mut self, path: &Path) -> Result<()> {
+        let file_path = path.to_string_lossy().to_string();
+        if let Some((id, _)) = self.index.find_by_path(&file_path)
+            && let Some(entry) = self.index.get_mut(&id)
+        {
+            entry.modified = None;
+        }
+        self.index_file(path)?;
+        Ok(())
+    }
+
+    fn note_tags(&self, id: &Uuid) -> Result<Vec<String>> {
+        self.index
+            .get(id)
+            .map(|entry| entry.tags.clone())
+            .ok_or(Error::NoteNotFound(*id))
+    }
+
+    // 인덱스와 메모리의 노트 태그를 함께 변경
+    fn set_note_tags(&mut self, id: &Uuid, tags: Vec<String>) -> Result<()> {
+        let entry = self.index.get_mut(id).ok_or(Error::NoteNotFound(*id))?;
+        entry.tags = tags.clone();
+
+        if let Some(note) = self.notes.get_mut(id) {
+            note.tags = tags;
+        }
+        Ok(())
+    }
+
+    // 노트를 정적 HTML 사이트로 내보내기 (ids의 노트만)
+    // 노트 사이 링크와 노트 단축어는 내보낸 페이지 링크로 바꾸고, 폴더/태그별 목록과 검색용 JSON도 만듦
+    // 기존 파일은 덮어쓰지만 지우지는 않음
+    pub fn export_html(&self, out_dir: &Path, ids: &[Uuid]) -> Result<HtmlExport> {
+        let wanted: HashSet<&Uuid> = ids.iter().collect();
+        let notes: Vec<(&Uuid, &Note)> = self
+            .list_notes()
+            .into_iter()
+            .filter(|(id, _)| wanted.contains(id))
+            .collect();
+
+        // 페이지 이름: 파일명 기준 (겹치면 뒤에 번호)
+        let pages = export::unique_pages(notes.iter().map(|(id, note)| {
+            let stem = Path::new(&note.filename)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            (**id, template::slugify(&stem))
+        }));
+        let listed = |ids: &mut dyn Iterator<Item = Uuid>| {
+            let mut listed: Vec<ListedNote> = ids
+                .filter_map(|id| {
+                    let note = self.notes.get(&id)?;
+                    Some(ListedNote {
+                        page: pages.get(&id)?,
+                        title: &note.title,
+                        updated: note.updated_at,
+                    })
+                })
+                .collect();
+            listed.sort_by_key(|note| std::cmp::Reverse(note.updated));
+            listed
+        };
+
+        let mut all_tags: Vec<&str> = notes
+            .iter()
+            .flat_map(|(_, note)| note.tags.iter().map(String::as_str))
+            .collect();
+        all_tags.sort_unstable();
+        all_tags.dedup();
+        let (folders, tags): (Vec<&str>, Vec<&str>) =
+            all_tags.into_iter().partition(|tag| tag.starts_with('@'));
+        let folder_pages = export::unique_pages(
+            folders
+                .iter()
+                .map(|folder| (*folder, template::slugify(&folder[1..]))),
+        );
+        let tag_pages = export::unique_pages(tags.iter().map(|tag| (*tag, template::slugify(tag))));
+
+        for dir in [export::NOTES_DIR, export::TAGS_DIR, export::FOLDERS_DIR] {
+            let path = out_dir.join(dir);
+            fs::create_dir_all(&path).map_err(|source| Error::CreateDir { path, source })?;
+        }
+
+        // 백링크: 링크 그래프를 뒤집어 노트마다 참조하는 노트 목록
+        let mut referrers: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+        for (source, targets) in self.link_graph() {
+            for target in targets {
+                referrers.entry(target).or_default().push(source);
+            }
+        }
+        let resolver = self.link_resolver();
+        let mut unresolved_links = 0;
+        let mut search_entries = Vec::new();
+        for (id, note) in &notes {
+            let page = &pages[*id];
+            let content = self
+                .expand_content(id)
+                .map_or_else(|| note.content().to_string(), |expanded| expanded.content);
+            let content = export::strip_title_heading(&content, &note.title);
+            let (body, unresolved) = export::markdown_to_html(content, |target| {
+                let target = resolver.resolve(id, target)?;
+                pages.get(&target).map(|page| format!("{}.html", page))
+            });
+            unresolved_links += unresolved;
+
+            let folder = note
+                .get_folder_tag()
+                .and_then(|tag| Some((&tag[1..], folder_pages.get(tag)?.as_str())));
+            let note_tags = note
+                .get_regular_tags()
+                .into_iter()
+                .filter_map(|tag| Some((tag, tag_pages.get(tag)?.as_str())))
+                .collect();
+            let backlinks = referrers
+                .get(*id)
+                .map(|sources| listed(&mut sources.iter().copied()))
+                .unwrap_or_default();
+            let html = export::note_page(&NotePage {
+                title: &note.title,
+                created: note.created_at,
+                updated: note.updated_at,
+                folder,
+                tags: note_tags,
+                body: &body,
+                backlinks,
+            });
+            let path = out_dir
+                .join(export::NOTES_DIR)
+                .join(format!("{}.html", page));
+            storage::write_atomic(&path, html)?;
+
+            search_entries.push(SearchEntry {
+                title: note.title.clone(),
+                url: format!("{}/{}.html", export::NOTES_DIR, page),
+                folder: note.get_folder_name(),
+                tags: note
+                    .get_regular_tags()
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                updated: note.updated_at,
+                text: export::plain_text(content),
+            });
+        }
+
+        // 폴더/태그별 목록 (인덱스의 태그 기준)
+        let mut folder_counts = Vec::new();
+        for folder in &folders {
+            let notes = listed(
+                &mut self
+                    .index
+                    .find_by_folder(folder)
+                    .into_iter()
+                    .map(|(id, _)| id),
+            );
+            let page = &folder_pages[folder];
+            let path = out_dir
+                .join(export::FOLDERS_DIR)
+                .join(format!("{}.html", page));
+            storage::write_atomic(
+                &path,
+                export::list_page(&format!("📁 {}", &folder[1..]), &notes),
+            )?;
+            folder_counts.push((&folder[1..], page.as_str(), notes.len()));
+        }
+        let mut tag_counts = Vec::new();
+        for tag in &tags {
+            let notes = listed(&mut self.index.find_by_tag(tag).into_iter().map(|(id, _)| id));
+            let page = &tag_pages[tag];
+            let path = out_dir
+                .join(export::TAGS_DIR)
+                .join(format!("{}.html", page));
+            storage::write_atomic(&path, export::list_page(&format!("#{}", tag), &notes))?;
+            tag_counts.push((*tag, page.as_str(), notes.len()));
+        }
+
+        let all_notes = listed(&mut notes.iter().map(|(id, _)| **id));
+        storage::write_atomic(
+            &out_dir.join("index.html"),
+            export::index_page(&all_notes, &folder_counts, &tag_counts),
+        )?;
+        let search_json = serde_json::to_string(&search_entries).map_err(Error::Serialize)?;
+        storage::write_atomic(&out_dir.join(export::SEARCH_FILE), search_json)?;
+        storage::write_atomic(&out_dir.join(export::STYLE_FILE), export::STYLE)?;
+
+        Ok(HtmlExport {
+            out_dir: out_dir.to_path_buf(),
+            notes: notes.len(),
+            tags: tags.len(),
+            folders: folders.len(),
+            unresolved_links,
+        })
+    }
+
+    // 노트마다 인덱스 메타데이터 (경로순)
+    pub fn export_meta(&self) -> Vec<MetaRecord> {
+        let mut records: Vec<MetaRecord> = self
+            .notes
+            .iter()
+            .map(|(id, note)| MetaRecord {
+                uuid: *id,
+                path: self.display_path(id),
+                title: note.title.clone(),
+                tags: note
+                    .get_regular_tags()
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                folder: note.get_folder_name(),
+                created: note.created_at,
+                updated: note.updated_at,
+            })
+            .collect();
+        records.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.uuid.cmp(&b.uuid)));
+        records
+    }
+
+    // 가져올 메타데이터를 현재 인덱스와 비교 (바꾸지는 않음)
+    // 노트는 UUID로 찾고, 없으면 경로 (표시용 상대 경로 또는 전체 경로)로 찾음
+    pub fn plan_meta_import(&self, updates: &[MetaUpdate]) -> Result<MetaImport> {
+        let mut plan = MetaImport::default();
+        let mut seen: HashMap<Uuid, usize> = HashMap::new();
+        // 노트 디렉토리 기준 경로 -> UUID (레코드마다 모든 노트의 경로를 만들지 않도록 한 번만)
+        let by_display_path: HashMap<String, Uuid> = self
+            .notes
+            .keys()
+            .map(|id| (self.display_path(id), *id))
+            .collect();
+
+        for update in updates {
+            let id = update
+                .uuid
+                .filter(|id| self.notes.contains_key(id))
+                .or_else(|| {
+                    let path = update.path.as_deref()?;
+                    by_display_path
+                        .get(path)
+                        .copied()
+                        .or_else(|| self.index.find_by_path(path).map(|(id, _)| id))
+                });
+            let Some((id, note)) = id.and_then(|id| Some((id, self.notes.get(&id)?))) else {
+                plan.unmatched.push(UnmatchedRecord {
+                    line: update.line,
+                    uuid: update.uuid,
+                    path: update.path.clone(),
+                });
+                continue;
+            };
+            if let Some(first_line) = seen.insert(id, update.line) {
+                return Err(Error::InvalidMetaRecord {
+                    line: update.line,
+                    reason: MetaRecordError::DuplicateNote { first_line },
+                });
+            }
+
+            let mut changes = Vec::new();
+            let tags: Vec<String> = note
+                .get_regular_tags()
+                .into_iter()
+                .map(String::from)
+                .collect();
+            if let Some(new_tags) = &update.tags {
+                let sorted = |tags: &[String]| {
+                    let mut tags = tags.to_vec();
+                    tags.sort();
+                    tags
+                };
+                if sorted(new_tags) != sorted(&tags) {
+                    changes.push(FieldChange::Tags {
+                        from: tags,
+                        to: new_tags.clone(),
+                    });
+                }
+            }
+            if let Some(folder) = &update.folder
+                && *folder != note.get_folder_name()
+            {
+                changes.push(FieldChange::Folder {
+                    from: note.get_folder_name(),
+                    to: folder.clone(),
+                });
+            }
+            if let Some(created) = update.created
+                && created != note.created_at
+            {
+                changes.push(FieldChange::Created {
+                    from: note.created_at,
+                    to: created,
+                });
+            }
+            if let Some(updated) = update.updated
+                && updated != note.updated_at
+            {
+                changes.push(FieldChange::Updated {
+                    from: note.updated_at,
+                    to: updated,
+                });
+            }
+
+            if changes.is_empty() {
+                plan.unchanged += 1;
+            } else {
+                plan.changes.push(NoteMetaChange {
+                    id,
+                    path: self.display_path(&id),
+                    line: update.line,
+                    changes,
+                });
+            }
+        }
+
+        plan.changes.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(plan)
+    }
+
+    // plan_meta_import의 변경을 인덱스에 적용하고 한 번 저장. 바뀐 노트 수 반환
+    pub fn apply_meta_import(&mut self, changes: &[NoteMetaChange]) -> Result<usize> {
+        for change in changes {
+            let mut tags = self.note_tags(&change.id)?;
+            for field in &change.changes {
+                match field {
+                    FieldChange::Tags { to, .. } => {
+                        tags.retain(|tag| tag.starts_with('@'));
+                        tags.extend(to.iter().cloned());
+                    }
+                    FieldChange::Folder { to, .. } => {
+                        tags.retain(|tag| !tag.starts_with('@'));
+                        if let Some(folder) = to {
+                            tags.insert(0, format!("@{}", folder));
+                        }
+                    }
+                    FieldChange::Created { to, .. } => {
+                        self.set_timestamps(&change.id, Some(*to), None)?
+                    }
+                    FieldChange::Updated { to, .. } => {
+                        self.set_timestamps(&change.id, None, Some(*to))?
+                    }
+                }
+            }
+            self.set_note_tags(&change.id, tags)?;
+        }
+
+        if !changes.is_empty() {
+            self.save_index()?;
+        }
+        Ok(changes.len())
+    }
+
+    // 인덱스와 메모리의 노트 작성/수정 시각을 함께 변경
+    fn set_timestamps(
+        &mut self,
+        id: &Uuid,
+        created: Option<DateTime<Utc>>,
+        updated: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let entry = self.index.get_mut(id).ok_or(Error::NoteNotFound(*id))?;
+        let note = self.notes.get_mut(id).ok_or(Error::NoteNotFound(*id))?;
+        if let Some(created) = created {
+            entry.created_at = created;
+            note.created_at = created;
+        }
+        if let Some(updated) = updated {
+            entry.updated_at = updated;
+            note.updated_at = updated;
+        }
+        Ok(())
+    }
+
+    // 새로운 폴더를 watched_folders에 추가
+    pub fn add_watched_folder(&mut self, folder_path: String) -> Result<()> {
+        let folder = PathBuf::from(&folder_path);
+
         // 폴더 존재 여부 확인
         if !folder.exists() {
-            return Err(format!("폴더가 존재하지 않습니다: {}", folder_path));
+            return Err(Error::FolderMissing(folder));
         }
 
         // 이미 추가되어 있는지 확인
         if self.index.get_watched_folders().contains(&folder_path) {
-            return Err(format!("이미 추가된 폴더입니다: {}", folder_path));
+            return Err(Error::FolderAlreadyWatched(folder_path));
         }
 
         // 폴더 추가
@@ -310,23 +2048,59 @@ impl NoteApp {
     }
 
     // watched_folders에서 폴더 제거
-    pub fn remove_watched_folder(&mut self, folder_path: &str) -> Result<(), String> {
+    pub fn remove_watched_folder(&mut self, folder_path: &str) -> Result<()> {
         if !self.index.remove_watched_folder(folder_path) {
-            return Err(format!("폴더를 찾을 수 없습니다: {}", folder_path));
+            return Err(Error::FolderNotWatched(folder_path.to_string()));
         }
 
-        // 해당 폴더의 노트들을 인덱스에서 제거
+        // 해당 폴더의 노트들을 인덱스에서 제거 (경로 구성요소 단위로 비교해
+        // notes-archive 같은 이름이 비슷한 옆 폴더는 건드리지 않음)
         let mut to_remove = Vec::new();
-        for (id, entry) in self.index.mappings.iter() {
-            if entry.file_path.starts_with(folder_path) {
+        for (id, entry) in self.index.iter() {
+            if Path::new(&entry.file_path).starts_with(folder_path) {
                 to_remove.push(*id);
             }
         }
 
-        for id in to_remove {
-            self.index.remove_entry(&id);
+        // 노트의 단축어도 같이 제거 (남으면 주인 없는 단축어가 됨)
+        for id in &to_remove {
+            self.index.remove_entry(id);
+            self.shortcuts.remove_shortcuts(id);
+        }
+
+        // 인덱스와 단축어 저장
+        self.save_index()?;
+        if !to_remove.is_empty() {
+            self.save_shortcuts()?;
+        }
+
+        // 노트 다시 로드
+        self.load_notes()?;
+
+        Ok(())
+    }
+
+    // 폴더의 스캔 규칙 가져오기
+    pub fn get_folder_rules(&self, folder_path: &str) -> FolderRules {
+        self.index.get_folder_rules(folder_path)
+    }
+
+    // 폴더의 스캔 규칙 변경 (깊이, 제외/포함 패턴)
+    pub fn set_folder_rules(&mut self, folder_path: &str, rules: FolderRules) -> Result<()> {
+        if !self
+            .index
+            .get_watched_folders()
+            .iter()
+            .any(|f| f == folder_path)
+        {
+            return Err(Error::FolderNotWatched(folder_path.to_string()));
         }
 
+        // 패턴이 올바른지 미리 확인
+        FolderScanner::new(&PathBuf::from(folder_path), &rules)?;
+
+        self.index.set_folder_rules(folder_path, rules);
+
         // 인덱스 저장
         self.save_index()?;
 
@@ -336,8 +2110,111 @@ impl NoteApp {
         Ok(())
     }
 
+    // 관리 폴더에 속한 노트 개수 (index에서 직접 세기)
+    pub fn count_notes_in_folder(&self, folder_path: &str) -> usize {
+        self.index
+            .iter()
+            .filter(|(_, entry)| Path::new(&entry.file_path).starts_with(folder_path))
+            .count()
+    }
+
     // 관리 중인 폴더 목록 가져오기
     pub fn list_watched_folders(&self) -> &Vec<String> {
         self.index.get_watched_folders()
     }
 }
+
+// 링크 해석용 조회표 (값이 None이면 같은 이름의 노트가 여럿이라 모호함)
+struct LinkResolver<'a> {
+    app: &'a NoteApp,
+    by_path: HashMap<PathBuf, Uuid>,
+    by_filename: HashMap<String, Option<Uuid>>,
+    by_title: HashMap<String, Option<Uuid>>, // ASCII 소문자
+    by_stem: HashMap<String, Option<Uuid>>,  // 확장자 뺀 파일명, ASCII 소문자
+}
+
+impl LinkResolver<'_> {
+    fn insert_unique(map: &mut HashMap<String, Option<Uuid>>, key: String, id: Uuid) {
+        map.entry(key)
+            .and_modify(|existing| *existing = None)
+            .or_insert(Some(id));
+    }
+
+    fn resolve(&self, from: &Uuid, target: &str) -> Option<Uuid> {
+        if let Ok(id) = Uuid::parse_str(target) {
+            return self.app.notes.contains_key(&id).then_some(id);
+        }
+
+        if target.to_lowercase().ends_with(".md") {
+            // 링크한 노트의 폴더 기준 상대 경로
+            if let Some(entry) = self.app.index.get(from)
+                && let Some(dir) = Path::new(&entry.file_path).parent()
+                && let Some(id) = self.by_path.get(&normalize_path(&dir.join(target)))
+            {
+                return Some(*id);
+            }
+
+            // 경로가 맞지 않으면 파일명으로 찾기
+            let filename = Path::new(target)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())?;
+            return self.by_filename.get(&filename).copied().flatten();
+        }
+
+        let key = target.to_ascii_lowercase();
+        let unique = |map: &HashMap<String, Option<Uuid>>| map.get(&key).copied().flatten();
+        unique(&self.by_title).or_else(|| unique(&self.by_stem))
+    }
+
+    fn outgoing_links(&self, id: &Uuid) -> Vec<Link> {
+        let Some(note) = self.app.notes.get(id) else {
+            return Vec::new();
+        };
+
+        note.links()
+            .into_iter()
+            .map(|raw| Link {
+                resolved: self.resolve(id, &raw.target),
+                raw,
+            })
+            .collect()
+    }
+}
+
+// 단축어 파일 경로 해석: ~는 홈 디렉토리, 상대 경로는 노트가 있는 폴더 기준
+fn resolve_shortcut_path(note_path: &Path, path: &Path) -> PathBuf {
+    if let Ok(rest) = path.strip_prefix("~")
+        && let Some(home) = std::env::var_os("HOME")
+    {
+        return PathBuf::from(home).join(rest);
+    }
+
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    note_path
+        .parent()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+// 파일의 수정 시각과 크기
+fn file_stamp(path: &Path) -> std::io::Result<(DateTime<Utc>, u64)> {
+    let metadata = fs::metadata(path)?;
+    Ok((metadata.modified()?.into(), metadata.len()))
+}
+
+// 파일 생성 시각 (지원하지 않는 파일 시스템이면 None)
+fn file_created(path: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(path).ok()?.created().ok().map(Into::into)
+}
+
+fn change_path(change: &NoteChange) -> &str {
+    match change {
+        NoteChange::Added { path, .. }
+        | NoteChange::Updated { path, .. }
+        | NoteChange::Removed { path, .. } => path,
+        NoteChange::Moved { to, .. } => to,
+    }
+}
@@ -0,0 +1,64 @@
+use crate::links::LinkKind;
+use serde::Serialize;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+// 노트 하나에서 발견된 문제
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Issue {
+    // 본문 링크의 대상 노트를 찾을 수 없음
+    UnresolvedLink {
+        line_number: usize,
+        target: String,
+        kind: LinkKind,
+    },
+    // File 단축어가 없는 경로를 가리킴
+    MissingFile {
+        alias: String,
+        path: PathBuf,
+    },
+    // Note 단축어의 대상 노트가 사라짐
+    MissingNote {
+        alias: String,
+        id: Uuid,
+    },
+    // 들어오는 링크도 나가는 링크도 없는 노트
+    Orphan,
+}
+
+impl Issue {
+    // 고립된 노트는 경고로만 취급
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Issue::Orphan)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteIssues {
+    pub id: Uuid,
+    pub title: String,
+    pub file_path: String,
+    pub issues: Vec<Issue>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CheckReport {
+    pub notes: Vec<NoteIssues>,
+}
+
+impl CheckReport {
+    pub fn has_errors(&self) -> bool {
+        self.notes
+            .iter()
+            .any(|note| note.issues.iter().any(Issue::is_error))
+    }
+
+    pub fn count(&self, predicate: impl Fn(&Issue) -> bool) -> usize {
+        self.notes
+            .iter()
+            .flat_map(|note| &note.issues)
+            .filter(|issue| predicate(issue))
+            .count()
+    }
+}
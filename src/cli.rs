@@ -0,0 +1,961 @@
+use crate::{
+    ConsoleReporter, open_in_editor, print_changes, print_check_report, print_fsck_report,
+    print_html_export, print_meta_import, print_note_detail, print_search_results, show_backlinks,
+    show_folders, show_links, show_notes_list, show_shortcuts, show_tags,
+};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use md_filer::app::NoteApp;
+use md_filer::error::Error;
+use md_filer::index::FolderRules;
+use md_filer::meta::{self, MetaFormat};
+use md_filer::note::{LinkTarget, Note, Shortcut};
+use md_filer::search::Snippet;
+use md_filer::shortcuts::UnknownAlias;
+use md_filer::storage;
+use md_filer::watch::NoteWatcher;
+use serde::Serialize;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+use uuid::Uuid;
+
+// 종료 코드
+pub const EXIT_OK: u8 = 0;
+pub const EXIT_ERROR: u8 = 1; // 앱 로드 실패, 파일 I/O 오류 등
+pub const EXIT_USAGE: u8 = 2; // 잘못된 인자 (clap과 동일)
+pub const EXIT_NOT_FOUND: u8 = 3; // 노트/폴더 없음, 검색 결과 없음
+pub const EXIT_CHECK_FAILED: u8 = 4; // check에서 깨진 링크/단축어, fsck에서 불일치 발견
+
+#[derive(Parser)]
+#[command(name = "md_filer", version, about = "마크다운 노트 관리 도구")]
+pub struct Cli {
+    /// 노트 디렉토리 (기본값: $NOTES_DIR 또는 ./notes)
+    #[arg(long, global = true)]
+    pub notes_dir: Option<PathBuf>,
+
+    /// 출력 형식
+    #[arg(long, value_enum, default_value_t = Format::Table, global = true)]
+    pub format: Format,
+
+    /// 하위 명령어 (없으면 대화형 모드)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    pub fn resolve_notes_dir(&self) -> PathBuf {
+        self.notes_dir.clone().unwrap_or_else(|| {
+            env::var("NOTES_DIR")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from("./notes"))
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+// export-meta/import-meta 파일 형식
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MetaFileFormat {
+    Csv,
+    Jsonl,
+}
+
+impl MetaFileFormat {
+    // 지정하지 않으면 확장자로 정함
+    fn resolve(kind: Option<Self>, path: Option<&Path>) -> MetaFormat {
+        match kind {
+            Some(MetaFileFormat::Csv) => MetaFormat::Csv,
+            Some(MetaFileFormat::Jsonl) => MetaFormat::Jsonl,
+            None => path.map_or(MetaFormat::Jsonl, MetaFormat::from_path),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// 노트 목록 보기 (최신순)
+    List,
+    /// 노트 상세 보기
+    Show {
+        /// 노트 (UUID 앞부분, 파일명, 제목 또는 #N)
+        id: String,
+        /// 마크다운을 렌더링하지 않고 원문 그대로 출력 (페이저 없이)
+        #[arg(long)]
+        raw: bool,
+    },
+    /// $VISUAL/$EDITOR로 노트를 열고, 닫으면 그 노트만 다시 읽기
+    Edit {
+        /// 노트 (UUID 앞부분, 파일명, 제목 또는 #N)
+        id: String,
+    },
+    /// 노트 검색
+    Search {
+        /// 검색어
+        #[arg(required = true)]
+        query: Vec<String>,
+    },
+    /// 새 노트 만들기 (제목으로 파일명을 만들고 frontmatter에 UUID 기록)
+    New {
+        /// 노트 제목
+        #[arg(required = true)]
+        title: Vec<String>,
+        /// 노트를 만들 관리 폴더 (기본값: 노트 디렉토리)
+        #[arg(long)]
+        folder: Option<String>,
+        /// 태그 (여러 번 지정 가능, @로 시작하면 폴더 태그)
+        #[arg(long = "tag", short)]
+        tags: Vec<String>,
+        /// .templates/<이름>.md 템플릿 ({{title}}, {{date}}, {{time}}, {{uuid}} 치환)
+        #[arg(long)]
+        template: Option<String>,
+    },
+    /// 태그 목록 보기
+    Tags,
+    /// 관리 중인 폴더 보기/추가/제거
+    Folders {
+        #[command(subcommand)]
+        action: Option<FolderCommand>,
+    },
+    /// 노트 태그 추가/제거, 태그 이름 변경/합치기
+    Tag {
+        #[command(subcommand)]
+        action: TagCommand,
+    },
+    /// 노트의 폴더(@태그) 지정 또는 이동
+    Move {
+        /// 노트 (UUID 앞부분, 파일명, 제목 또는 #N)
+        id: String,
+        /// 폴더 이름 (생략하면 폴더 해제)
+        folder: Option<String>,
+    },
+    /// 노트의 단축어 보기/추가/수정/제거
+    Shortcut {
+        #[command(subcommand)]
+        action: ShortcutCommand,
+    },
+    /// 노트에서 나가는 링크 보기
+    Links {
+        /// 노트 (UUID 앞부분, 파일명, 제목 또는 #N)
+        id: String,
+    },
+    /// 노트를 참조하는 노트 보기 (본문 링크와 단축어)
+    Backlinks {
+        /// 노트 (UUID 앞부분, 파일명, 제목 또는 #N)
+        id: String,
+    },
+    /// 깨진 링크/단축어와 고립된 노트 검사 (문제가 있으면 종료 코드 4)
+    Check,
+    /// 인덱스, 단축어, frontmatter 대조 (문제가 남으면 종료 코드 4)
+    Fsck {
+        /// 찾은 문제 고치기 (중복 UUID 파일에는 새 UUID 기록)
+        #[arg(long)]
+        repair: bool,
+    },
+    /// 노트를 다른 형식으로 내보내기
+    Export {
+        #[command(subcommand)]
+        target: ExportCommand,
+    },
+    /// 인덱스 메타데이터 (uuid, path, title, tags, folder, created, updated) 내보내기
+    ExportMeta {
+        /// 출력 파일 (생략하거나 -면 표준 출력)
+        output: Option<PathBuf>,
+        /// 파일 형식 (기본값: 확장자가 .csv면 csv, 아니면 jsonl)
+        #[arg(long = "as", value_enum)]
+        kind: Option<MetaFileFormat>,
+    },
+    /// 메타데이터 가져오기 (UUID, 없으면 경로로 노트를 찾음). 기본은 바뀔 내용만 표시
+    ImportMeta {
+        /// 입력 파일 (-면 표준 입력)
+        input: PathBuf,
+        /// 파일 형식 (기본값: 확장자가 .csv면 csv, 아니면 jsonl)
+        #[arg(long = "as", value_enum)]
+        kind: Option<MetaFileFormat>,
+        /// 바뀔 내용을 인덱스에 적용
+        #[arg(long)]
+        apply: bool,
+    },
+    /// 파일 시스템과 인덱스 동기화
+    Refresh,
+    /// 전체 화면 모드 (노트 목록, 미리보기, 폴더/태그, 실시간 검색)
+    Tui,
+    /// 폴더를 감시하며 바뀐 파일을 인덱스에 계속 반영 (Ctrl-C로 종료)
+    Watch {
+        /// 이벤트를 모으는 시간 (밀리초)
+        #[arg(long, default_value_t = 300)]
+        debounce_ms: u64,
+    },
+}
+
+impl Command {
+    // 노트 디렉토리에 아무것도 쓰지 않는 명령 (watch가 실행 중이어도 읽기 전용으로 열 수 있음)
+    fn is_read_only(&self) -> bool {
+        match self {
+            Command::List
+            | Command::Show { .. }
+            | Command::Search { .. }
+            | Command::Tags
+            | Command::Links { .. }
+            | Command::Backlinks { .. }
+            | Command::Check
+            | Command::Export { .. }
+            | Command::ExportMeta { .. } => true,
+            Command::Folders { action } => matches!(action, None | Some(FolderCommand::List)),
+            Command::Shortcut { action } => matches!(action, ShortcutCommand::List { .. }),
+            Command::Fsck { repair } => !repair,
+            Command::ImportMeta { apply, .. } => !apply,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum FolderCommand {
+    /// 관리 중인 폴더 목록
+    List,
+    /// 관리할 폴더 추가
+    Add { path: String },
+    /// 관리 폴더 제거
+    Remove { path: String },
+}
+
+#[derive(Subcommand)]
+pub enum ExportCommand {
+    /// 정적 HTML 사이트 (노트 페이지, 폴더/태그 목록, 검색용 search.json)
+    Html {
+        /// 사이트를 만들 폴더 (없으면 생성, 같은 이름의 파일은 덮어씀)
+        out_dir: PathBuf,
+        /// 이 검색 쿼리에 맞는 노트만 (예: "tag:public folder:@blog")
+        #[arg(long)]
+        query: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TagCommand {
+    /// 노트에 태그 추가
+    Add {
+        id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// 노트에서 태그 제거
+    Remove {
+        id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// 모든 노트에서 태그 이름 변경
+    Rename { old: String, new: String },
+    /// 여러 태그를 하나로 합치기
+    Merge {
+        target: String,
+        #[arg(required = true)]
+        sources: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ShortcutCommand {
+    /// 노트의 단축어 목록
+    List { id: String },
+    /// 단축어 추가 (대상: URL, 파일 경로 또는 note:<UUID>)
+    Add {
+        id: String,
+        alias: String,
+        target: String,
+        #[arg(long, short)]
+        description: Option<String>,
+    },
+    /// 단축어 대상/설명 변경
+    Edit {
+        id: String,
+        alias: String,
+        #[arg(long, short)]
+        target: Option<String>,
+        /// 빈 문자열이면 설명 삭제
+        #[arg(long, short)]
+        description: Option<String>,
+    },
+    /// 단축어 제거
+    Remove { id: String, alias: String },
+}
+
+// 하위 명령어 실행 오류 (종료 코드 포함)
+struct CliError {
+    code: u8,
+    message: String,
+}
+
+impl CliError {
+    fn not_found(message: impl fmt::Display) -> Self {
+        CliError {
+            code: EXIT_NOT_FOUND,
+            message: message.to_string(),
+        }
+    }
+
+    // 결과는 이미 출력했고 종료 코드만 전달할 때
+    fn silent(code: u8) -> Self {
+        CliError {
+            code,
+            message: String::new(),
+        }
+    }
+}
+
+// 오류 종류에 맞는 종료 코드: 잘못된 인자나 값은 2, 없는 노트/폴더는 3, 나머지(I/O 등)는 1
+impl From<Error> for CliError {
+    fn from(error: Error) -> Self {
+        let code = match &error {
+            Error::Query(_)
+            | Error::InvalidMetaRecord { .. }
+            | Error::InvalidPattern { .. }
+            | Error::AmbiguousNoteRef { .. }
+            | Error::NoResultSet
+            | Error::EmptyTitle
+            | Error::EmptyTag(_)
+            | Error::InvalidTagChar { .. }
+            | Error::MultipleFolders(_)
+            | Error::MixedTagKinds { .. }
+            | Error::EmptyAlias
+            | Error::InvalidAlias(_)
+            | Error::DuplicateShortcut(_)
+            | Error::EmptyLinkTarget
+            | Error::InvalidNoteId(_)
+            | Error::FolderAlreadyWatched(_) => EXIT_USAGE,
+            Error::NoteNotFound(_)
+            | Error::TargetNoteNotFound(_)
+            | Error::NoteRefNotFound(_)
+            | Error::ResultOutOfRange { .. }
+            | Error::FolderMissing(_)
+            | Error::FolderNotWatched(_)
+            | Error::TemplateNotFound(_)
+            | Error::ShortcutNotFound(_)
+            | Error::TagNotFound(_)
+            | Error::TagNotOnNote(_) => EXIT_NOT_FOUND,
+            _ => EXIT_ERROR,
+        };
+        CliError {
+            code,
+            message: error.to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct NoteView<'a> {
+    id: Uuid,
+    title: &'a str,
+    filename: &'a str,
+    file_path: Option<&'a str>,
+    folder: Option<String>,
+    tags: Vec<&'a str>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl<'a> NoteView<'a> {
+    fn new(app: &'a NoteApp, id: &Uuid, note: &'a Note) -> Self {
+        NoteView {
+            id: *id,
+            title: &note.title,
+            filename: &note.filename,
+            file_path: app.index.get(id).map(|e| e.file_path.as_str()),
+            folder: note.get_folder_name(),
+            tags: note.get_regular_tags(),
+            created_at: note.created_at,
+            updated_at: note.updated_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct NoteDetailView<'a> {
+    #[serde(flatten)]
+    note: NoteView<'a>,
+    shortcuts: Vec<&'a Shortcut>,
+    content: &'a str,
+    expanded_content: String,
+    unknown_aliases: Vec<UnknownAlias>,
+}
+
+#[derive(Serialize)]
+struct SearchResultView<'a> {
+    #[serde(flatten)]
+    note: NoteView<'a>,
+    score: f64,
+    snippet: Option<Snippet>,
+}
+
+#[derive(Serialize)]
+struct TagView<'a> {
+    tag: &'a str,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct TagsView<'a> {
+    folders: Vec<TagView<'a>>,
+    tags: Vec<TagView<'a>>,
+}
+
+#[derive(Serialize)]
+struct FolderView<'a> {
+    path: &'a str,
+    notes: usize,
+    #[serde(flatten)]
+    rules: FolderRules,
+}
+
+#[derive(Serialize)]
+struct TagChangeView {
+    notes: usize,
+}
+
+#[derive(Serialize)]
+struct RefreshView {
+    notes: usize,
+}
+
+#[derive(Serialize)]
+struct MetaExportView<'a> {
+    path: &'a Path,
+    notes: usize,
+}
+
+#[derive(Serialize)]
+struct ErrorView<'a> {
+    error: &'a str,
+    code: u8,
+}
+
+// 오류를 한 줄짜리 JSON으로 (메시지와 종료 코드)
+fn error_json(error: &CliError) -> String {
+    serde_json::to_string(&ErrorView {
+        error: &error.message,
+        code: error.code,
+    })
+    .unwrap_or_default()
+}
+
+// 하위 명령어 하나를 실행하고 종료 코드 반환
+pub fn run(command: Command, notes_dir: PathBuf, format: Format) -> ExitCode {
+    // 전체 화면 모드는 진행 메시지로 화면이 깨지지 않도록 앱을 직접 만듦
+    let result = match command {
+        Command::Tui => crate::tui::run(notes_dir).map_err(|e| CliError {
+            code: EXIT_ERROR,
+            message: e.to_string(),
+        }),
+        command => execute(command, notes_dir, format),
+    };
+    match result {
+        Ok(()) => ExitCode::from(EXIT_OK),
+        Err(e) if e.message.is_empty() => ExitCode::from(e.code),
+        Err(e) => {
+            match format {
+                Format::Table => eprintln!("❌ {}", e.message),
+                Format::Json => eprintln!("{}", error_json(&e)),
+            }
+            ExitCode::from(e.code)
+        }
+    }
+}
+
+fn execute(command: Command, notes_dir: PathBuf, format: Format) -> Result<(), CliError> {
+    let mut app = if command.is_read_only() {
+        NoteApp::for_reading(notes_dir, ConsoleReporter)?
+    } else {
+        NoteApp::with_reporter(notes_dir, ConsoleReporter)?
+    };
+
+    match command {
+        Command::List => match format {
+            Format::Table => show_notes_list(&mut app),
+            Format::Json => {
+                let ids = app.list_notes().iter().map(|(id, _)| **id).collect();
+                app.remember_results(ids);
+                print_json(&note_views(&app, app.list_notes()))?;
+            }
+        },
+        Command::Show { id, raw } => {
+            let id = parse_note_id(&app, &id)?;
+            let note = app
+                .get_note(&id)
+                .ok_or_else(|| CliError::not_found(format!("노트를 찾을 수 없습니다: {}", id)))?;
+
+            match format {
+                Format::Table => print_note_detail(&app, &id, note, raw),
+                Format::Json => {
+                    let expanded = app.expand_content(&id);
+                    print_json(&NoteDetailView {
+                        note: NoteView::new(&app, &id, note),
+                        shortcuts: app.list_shortcuts(&id),
+                        content: note.content(),
+                        expanded_content: expanded
+                            .as_ref()
+                            .map(|e| e.content.clone())
+                            .unwrap_or_default(),
+                        unknown_aliases: expanded.map(|e| e.unknown).unwrap_or_default(),
+                    })?;
+                }
+            }
+        }
+        Command::Search { query } => {
+            let query = query.join(" ");
+            let results = app.search(&query)?;
+            let found = !results.is_empty();
+
+            match format {
+                Format::Table => print_search_results(&mut app, &query, &results),
+                Format::Json => {
+                    let ids = results
+                        .iter()
+                        .map(|hit| hit.id)
+                        .filter(|id| app.get_note(id).is_some())
+                        .collect();
+                    app.remember_results(ids);
+                    let views: Vec<_> = results
+                        .into_iter()
+                        .filter_map(|hit| {
+                            let note = app.get_note(&hit.id)?;
+                            Some(SearchResultView {
+                                note: NoteView::new(&app, &hit.id, note),
+                                score: hit.score,
+                                snippet: hit.snippet,
+                            })
+                        })
+                        .collect();
+                    print_json(&views)?;
+                }
+            }
+
+            if !found {
+                return Err(CliError::silent(EXIT_NOT_FOUND));
+            }
+        }
+        Command::Tags => match format {
+            Format::Table => show_tags(&app),
+            Format::Json => {
+                let folders = app.get_folders();
+                let all_tags = app.get_all_tags();
+                print_json(&TagsView {
+                    folders: folders
+                        .iter()
+                        .map(|tag| TagView {
+                            tag,
+                            count: app.get_notes_by_folder(tag).len(),
+                        })
+                        .collect(),
+                    tags: all_tags
+                        .iter()
+                        .filter(|tag| !tag.starts_with('@'))
+                        .map(|tag| TagView {
+                            tag,
+                            count: app.index.find_by_tag(tag).len(),
+                        })
+                        .collect(),
+                })?;
+            }
+        },
+        Command::Folders { action } => {
+            match action.unwrap_or(FolderCommand::List) {
+                FolderCommand::List => {}
+                FolderCommand::Add { path } => {
+                    app.add_watched_folder(path.clone())?;
+                    if format == Format::Table {
+                        println!("✅ 폴더가 추가되었습니다: {}", path);
+                    }
+                }
+                FolderCommand::Remove { path } => {
+                    app.remove_watched_folder(&path)?;
+                    if format == Format::Table {
+                        println!("✅ 폴더가 제거되었습니다: {}", path);
+                    }
+                }
+            }
+
+            match format {
+                Format::Table => show_folders(&app),
+                Format::Json => {
+                    let folders: Vec<_> = app
+                        .list_watched_folders()
+                        .iter()
+                        .map(|path| FolderView {
+                            path,
+                            notes: app.count_notes_in_folder(path),
+                            rules: app.get_folder_rules(path),
+                        })
+                        .collect();
+                    print_json(&folders)?;
+                }
+            }
+        }
+        Command::Tag { action } => match action {
+            TagCommand::Add { id, tags } => {
+                let id = parse_note_id(&app, &id)?;
+                app.add_tags(&id, &tags)?;
+                print_note_change(&app, &id, format)?;
+            }
+            TagCommand::Remove { id, tags } => {
+                let id = parse_note_id(&app, &id)?;
+                app.remove_tags(&id, &tags)?;
+                print_note_change(&app, &id, format)?;
+            }
+            TagCommand::Rename { old, new } => {
+                let count = app.rename_tag(&old, &new)?;
+                match format {
+                    Format::Table => println!(
+                        "✅ 태그 이름을 바꿨습니다: {} → {} ({} 개 노트)",
+                        old, new, count
+                    ),
+                    Format::Json => print_json(&TagChangeView { notes: count })?,
+                }
+            }
+            TagCommand::Merge { target, sources } => {
+                let count = app.merge_tags(&sources, &target)?;
+                match format {
+                    Format::Table => println!(
+                        "✅ 태그를 합쳤습니다: {} → {} ({} 개 노트)",
+                        sources.join(", "),
+                        target,
+                        count
+                    ),
+                    Format::Json => print_json(&TagChangeView { notes: count })?,
+                }
+            }
+        },
+        Command::Edit { id } => {
+            let id = parse_note_id(&app, &id)?;
+            let file_path = app
+                .index
+                .get(&id)
+                .map(|entry| PathBuf::from(&entry.file_path))
+                .ok_or(Error::NoteNotFound(id))?;
+            open_in_editor(&file_path)?;
+
+            let changes = app.reload_note(&id)?;
+            match format {
+                Format::Table if changes.is_empty() => {
+                    println!("✅ 변경 없음: {}", app.display_path(&id))
+                }
+                Format::Table => print_changes(&changes),
+                Format::Json => print_json(&changes)?,
+            }
+        }
+        Command::New {
+            title,
+            folder,
+            tags,
+            template,
+        } => {
+            let id = app.create_note(
+                &title.join(" "),
+                folder.as_deref(),
+                &tags,
+                template.as_deref(),
+            )?;
+            let note = app.get_note(&id).ok_or(Error::NoteNotFound(id))?;
+            match format {
+                Format::Table => {
+                    println!("✅ 노트를 만들었습니다: {}", note.title);
+                    println!("📄 {}", app.display_path(&id));
+                    println!("🆔 {}", id);
+                }
+                Format::Json => print_json(&NoteView::new(&app, &id, note))?,
+            }
+        }
+        Command::Move { id, folder } => {
+            let id = parse_note_id(&app, &id)?;
+            app.set_folder(&id, folder.as_deref())?;
+            print_note_change(&app, &id, format)?;
+        }
+        Command::Shortcut { action } => {
+            let id = match &action {
+                ShortcutCommand::List { id }
+                | ShortcutCommand::Add { id, .. }
+                | ShortcutCommand::Edit { id, .. }
+                | ShortcutCommand::Remove { id, .. } => parse_note_id(&app, id)?,
+            };
+
+            match action {
+                ShortcutCommand::List { .. } => {}
+                ShortcutCommand::Add {
+                    alias,
+                    target,
+                    description,
+                    ..
+                } => {
+                    let target = LinkTarget::parse(&target)?;
+                    app.add_shortcut(&id, &alias, target, description)?;
+                    if format == Format::Table {
+                        println!("✅ 단축어가 추가되었습니다: {}", alias);
+                    }
+                }
+                ShortcutCommand::Edit {
+                    alias,
+                    target,
+                    description,
+                    ..
+                } => {
+                    let target = target.map(|t| LinkTarget::parse(&t)).transpose()?;
+                    app.edit_shortcut(&id, &alias, target, description)?;
+                    if format == Format::Table {
+                        println!("✅ 단축어가 수정되었습니다: {}", alias);
+                    }
+                }
+                ShortcutCommand::Remove { alias, .. } => {
+                    app.remove_shortcut(&id, &alias)?;
+                    if format == Format::Table {
+                        println!("✅ 단축어가 제거되었습니다: {}", alias);
+                    }
+                }
+            }
+
+            match format {
+                Format::Table => show_shortcuts(&app, &id),
+                Format::Json => print_json(&app.list_shortcuts(&id))?,
+            }
+        }
+        Command::Links { id } => {
+            let id = parse_note_id(&app, &id)?;
+            match format {
+                Format::Table => show_links(&app, &id),
+                Format::Json => print_json(&app.outgoing_links(&id))?,
+            }
+        }
+        Command::Backlinks { id } => {
+            let id = parse_note_id(&app, &id)?;
+            match format {
+                Format::Table => show_backlinks(&app, &id),
+                Format::Json => print_json(&app.backlinks(&id))?,
+            }
+        }
+        Command::Check => {
+            let report = app.check();
+            match format {
+                Format::Table => print_check_report(&report),
+                Format::Json => print_json(&report)?,
+            }
+            // 고립된 노트만 있으면 성공으로 처리
+            if report.has_errors() {
+                return Err(CliError::silent(EXIT_CHECK_FAILED));
+            }
+        }
+        Command::Fsck { repair } => {
+            let mut report = app.fsck();
+            if repair {
+                report.repaired = app.repair(&report.issues)?;
+            }
+            match format {
+                Format::Table => print_fsck_report(&report),
+                Format::Json => print_json(&report)?,
+            }
+            if !report.is_clean() && !repair {
+                return Err(CliError::silent(EXIT_CHECK_FAILED));
+            }
+        }
+        Command::Export { target } => match target {
+            ExportCommand::Html { out_dir, query } => {
+                let ids: Vec<Uuid> = match query {
+                    Some(query) => {
+                        let hits = app.search(&query)?;
+                        if hits.is_empty() {
+                            return Err(CliError::not_found(format!(
+                                "내보낼 노트가 없습니다: {}",
+                                query
+                            )));
+                        }
+                        hits.into_iter().map(|hit| hit.id).collect()
+                    }
+                    None => app.notes.keys().copied().collect(),
+                };
+                let report = app.export_html(&out_dir, &ids)?;
+                match format {
+                    Format::Table => print_html_export(&report),
+                    Format::Json => print_json(&report)?,
+                }
+            }
+        },
+        Command::Watch { debounce_ms } => {
+            let watcher = NoteWatcher::new(app.list_watched_folders())?;
+            if format == Format::Table {
+                println!(
+                    "👀 감시 중: {} (Ctrl-C로 종료)",
+                    app.list_watched_folders().join(", ")
+                );
+            }
+
+            loop {
+                let paths = watcher.next_batch(Duration::from_millis(debounce_ms))?;
+                let changes = match app.refresh_paths(&paths) {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        let e = CliError::from(e);
+                        match format {
+                            Format::Table => eprintln!("❌ {}", e.message),
+                            // 변경 이벤트와 같은 JSON Lines로 출력해 소비하는 쪽이 한 형식만 읽게 함
+                            Format::Json => println!("{}", error_json(&e)),
+                        }
+                        continue;
+                    }
+                };
+
+                match format {
+                    Format::Table => print_changes(&changes),
+                    // 한 줄에 변경 하나 (JSON Lines)
+                    Format::Json => {
+                        for change in &changes {
+                            println!("{}", serde_json::to_string(change).unwrap_or_default());
+                        }
+                    }
+                }
+            }
+        }
+        Command::Tui => unreachable!("run에서 처리"),
+        Command::ExportMeta { output, kind } => {
+            // -는 표준 출력
+            let output = output.filter(|path| path.as_os_str() != "-");
+            let records = app.export_meta();
+            let text = meta::to_string(&records, MetaFileFormat::resolve(kind, output.as_deref()))?;
+            match &output {
+                None => print!("{}", text),
+                Some(path) => {
+                    storage::write_atomic(path, text)?;
+                    match format {
+                        Format::Table => println!(
+                            "✅ {} 개 노트의 메타데이터를 내보냈습니다: {}",
+                            records.len(),
+                            path.display()
+                        ),
+                        Format::Json => print_json(&MetaExportView {
+                            path,
+                            notes: records.len(),
+                        })?,
+                    }
+                }
+            }
+        }
+        Command::ImportMeta { input, kind, apply } => {
+            let text = if input.as_os_str() == "-" {
+                io::read_to_string(io::stdin())
+            } else {
+                fs::read_to_string(&input)
+            }
+            .map_err(|source| Error::Read {
+                path: input.clone(),
+                source,
+            })?;
+            let updates = meta::parse(&text, MetaFileFormat::resolve(kind, Some(&input)))?;
+            let mut import = app.plan_meta_import(&updates)?;
+            if apply {
+                import.applied = app.apply_meta_import(&import.changes)?;
+            }
+            match format {
+                Format::Table => print_meta_import(&import, apply),
+                Format::Json => print_json(&import)?,
+            }
+        }
+        Command::Refresh => match format {
+            // NoteApp::new가 이미 파일 시스템과 동기화함
+            Format::Table => println!("✅ 새로고침 완료! ({} 개 노트)", app.notes.len()),
+            Format::Json => print_json(&RefreshView {
+                notes: app.notes.len(),
+            })?,
+        },
+    }
+
+    Ok(())
+}
+
+fn note_views<'a>(app: &'a NoteApp, notes: Vec<(&Uuid, &'a Note)>) -> Vec<NoteView<'a>> {
+    notes
+        .into_iter()
+        .map(|(id, note)| NoteView::new(app, id, note))
+        .collect()
+}
+
+// 노트 참조 (UUID 앞부분, 파일명, 제목, #N)를 노트 UUID로 변환
+fn parse_note_id(app: &NoteApp, reference: &str) -> Result<Uuid, CliError> {
+    Ok(app.resolve_note(reference)?)
+}
+
+// 태그/폴더가 바뀐 노트 출력
+fn print_note_change(app: &NoteApp, id: &Uuid, format: Format) -> Result<(), CliError> {
+    let Some(note) = app.get_note(id) else {
+        return Ok(());
+    };
+    match format {
+        Format::Table => {
+            println!("✅ 노트가 변경되었습니다: {}", note.title);
+            println!("📁 폴더: {}", note.get_folder_name().unwrap_or_default());
+            println!("🏷️ 태그: {}", note.get_regular_tags().join(", "));
+        }
+        Format::Json => print_json(&NoteView::new(app, id, note))?,
+    }
+    Ok(())
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), CliError> {
+    let json = serde_json::to_string_pretty(value).map_err(Error::Serialize)?;
+    println!("{}", json);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_follows_error_kind() {
+        let code = |error: Error| CliError::from(error).code;
+
+        // 잘못된 인자나 값
+        assert_eq!(code(Error::EmptyTag(String::new())), EXIT_USAGE);
+        assert_eq!(
+            code(Error::Query(md_filer::error::QueryError::UnclosedQuote)),
+            EXIT_USAGE
+        );
+        assert_eq!(code(Error::NoResultSet), EXIT_USAGE);
+        // 없는 노트/폴더
+        assert_eq!(
+            code(Error::NoteRefNotFound("x".to_string())),
+            EXIT_NOT_FOUND
+        );
+        assert_eq!(
+            code(Error::FolderNotWatched("x".to_string())),
+            EXIT_NOT_FOUND
+        );
+        assert_eq!(
+            code(Error::ShortcutNotFound("x".to_string())),
+            EXIT_NOT_FOUND
+        );
+        // 저장 실패 등은 인자 문제가 아님
+        let write = Error::Write {
+            path: PathBuf::from(".index.json"),
+            source: io::Error::other("disk full"),
+        };
+        assert_eq!(code(write), EXIT_ERROR);
+        assert_eq!(code(Error::Locked(PathBuf::from("notes"))), EXIT_ERROR);
+    }
+
+    #[test]
+    fn test_error_json_is_a_single_line() {
+        let error = CliError::from(Error::NoteRefNotFound("a\nb".to_string()));
+        let line = error_json(&error);
+        assert!(!line.contains('\n'));
+        let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(value["code"], EXIT_NOT_FOUND);
+        assert_eq!(value["error"], error.message);
+    }
+}
@@ -0,0 +1,643 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitStatus;
+use std::sync::atomic::{AtomicU8, Ordering};
+use uuid::Uuid;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// 라이브러리 전체에서 쓰는 오류 타입
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    // 파일 시스템
+    CreateDir {
+        path: PathBuf,
+        source: io::Error,
+    },
+    ReadDir {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Write {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // .index.json, .shortcuts.json, .search_index.json 파싱 실패
+    Corrupt {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    Serialize(serde_json::Error),
+    SerializeCsv(csv::Error),
+    // 더 새로운 md_filer가 쓴 데이터 파일
+    UnsupportedVersion {
+        path: PathBuf,
+        version: u64,
+        supported: u32,
+    },
+    // 다른 프로세스가 노트 디렉토리를 잠금
+    Locked(PathBuf),
+    Lock {
+        path: PathBuf,
+        source: io::Error,
+    },
+
+    // frontmatter
+    Yaml(serde_yaml::Error),
+    FrontmatterNotMapping,
+
+    // 관리 폴더와 스캔 규칙
+    FolderMissing(PathBuf),
+    FolderAlreadyWatched(String),
+    FolderNotWatched(String),
+    InvalidPattern {
+        pattern: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    // 노트와 태그
+    NoteNotFound(Uuid),
+    TargetNoteNotFound(Uuid),
+    EmptyTag(String),
+    InvalidTagChar {
+        tag: String,
+        ch: char,
+    },
+    TagNotOnNote(String),
+    TagNotFound(Vec<String>),
+    MultipleFolders(Vec<String>),
+    MixedTagKinds {
+        source: String,
+        target: String,
+    },
+
+    // 노트 참조 (UUID 앞부분, 파일명, 제목, #N)
+    NoteRefNotFound(String),
+    AmbiguousNoteRef {
+        reference: String,
+        candidates: Vec<String>,
+    },
+    NoResultSet,
+    ResultOutOfRange {
+        number: usize,
+        len: usize,
+    },
+
+    // 새 노트
+    EmptyTitle,
+    TemplateNotFound(String),
+
+    // 단축어
+    EmptyAlias,
+    InvalidAlias(String),
+    DuplicateShortcut(String),
+    ShortcutNotFound(String),
+    EmptyLinkTarget,
+    InvalidNoteId(String),
+
+    // 검색어
+    Query(QueryError),
+
+    // 메타데이터 가져오기 (line은 파일 기준 1부터)
+    InvalidMetaRecord {
+        line: usize,
+        reason: MetaRecordError,
+    },
+
+    // $VISUAL/$EDITOR 편집기
+    EditorLaunch {
+        program: String,
+        source: io::Error,
+    },
+    EditorFailed(ExitStatus),
+
+    // 파일 감시
+    Watch(notify::Error),
+    WatchFolder {
+        path: PathBuf,
+        source: notify::Error,
+    },
+    WatchStopped,
+}
+
+// 검색어 파싱 오류
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum QueryError {
+    Unexpected(String),
+    UnclosedQuote,
+    UnclosedParen,
+    UnexpectedEnd,
+    EmptyValue(String),
+    InvalidDate(String),
+}
+
+// 메타데이터 파일의 잘못된 줄
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum MetaRecordError {
+    Malformed(String),
+    MissingKey,
+    InvalidUuid(String),
+    InvalidDate(String),
+    InvalidTag(Box<Error>),
+    // 같은 노트를 가리키는 줄이 앞에 있음
+    DuplicateNote { first_line: usize },
+}
+
+// 오류 메시지 언어 (기본값: 한국어)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    Korean,
+    English,
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+impl Language {
+    // "ko", "en", "en_US.UTF-8" 같은 값에서 언어 찾기
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.to_ascii_lowercase();
+        if code.starts_with("ko") {
+            Some(Language::Korean)
+        } else if code.starts_with("en") {
+            Some(Language::English)
+        } else {
+            None
+        }
+    }
+}
+
+// Display에 쓰일 언어 설정 (프로세스 전체에 적용)
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::English,
+        _ => Language::Korean,
+    }
+}
+
+// 언어에 맞는 형식 문자열 고르기
+macro_rules! localized {
+    ($language:expr, $ko:literal, $en:literal $(, $arg:expr)* $(,)?) => {
+        match $language {
+            Language::Korean => format!($ko $(, $arg)*),
+            Language::English => format!($en $(, $arg)*),
+        }
+    };
+}
+
+impl Error {
+    // 지정한 언어로 된 오류 메시지
+    pub fn message(&self, language: Language) -> String {
+        match self {
+            Error::CreateDir { path, source } => localized!(
+                language,
+                "디렉토리 생성 실패 {}: {}",
+                "failed to create directory {}: {}",
+                path.display(),
+                source
+            ),
+            Error::ReadDir { path, source } => localized!(
+                language,
+                "디렉토리 읽기 실패 {}: {}",
+                "failed to read directory {}: {}",
+                path.display(),
+                source
+            ),
+            Error::Read { path, source } => localized!(
+                language,
+                "파일 읽기 실패 {}: {}",
+                "failed to read {}: {}",
+                path.display(),
+                source
+            ),
+            Error::Write { path, source } => localized!(
+                language,
+                "파일 쓰기 실패 {}: {}",
+                "failed to write {}: {}",
+                path.display(),
+                source
+            ),
+            Error::Corrupt { path, source } => localized!(
+                language,
+                "파일 파싱 실패 {}: {}",
+                "corrupt data file {}: {}",
+                path.display(),
+                source
+            ),
+            Error::Serialize(source) => localized!(
+                language,
+                "JSON 직렬화 실패: {}",
+                "failed to serialize JSON: {}",
+                source
+            ),
+            Error::SerializeCsv(source) => localized!(
+                language,
+                "CSV 직렬화 실패: {}",
+                "failed to serialize CSV: {}",
+                source
+            ),
+            Error::UnsupportedVersion {
+                path,
+                version,
+                supported,
+            } => localized!(
+                language,
+                "더 새로운 md_filer가 만든 파일입니다: {} (버전 {}, 지원하는 버전: {}). md_filer를 업데이트하세요",
+                "file was written by a newer md_filer: {} (version {}, supported: {}). Please upgrade md_filer",
+                path.display(),
+                version,
+                supported
+            ),
+            Error::Locked(path) => localized!(
+                language,
+                "다른 md_filer가 이 노트 디렉토리를 사용 중입니다: {} (watch나 대화형 모드를 종료하세요)",
+                "another md_filer process is using this notes directory: {} (stop watch or the interactive session)",
+                path.display()
+            ),
+            Error::Lock { path, source } => localized!(
+                language,
+                "잠금 파일 열기 실패 {}: {}",
+                "failed to lock {}: {}",
+                path.display(),
+                source
+            ),
+            Error::Yaml(source) => localized!(
+                language,
+                "YAML 파싱 오류: {}",
+                "invalid YAML frontmatter: {}",
+                source
+            ),
+            Error::FrontmatterNotMapping => localized!(
+                language,
+                "YAML 파싱 오류: frontmatter가 키-값 매핑이 아닙니다",
+                "invalid YAML frontmatter: not a key-value mapping"
+            ),
+            Error::FolderMissing(path) => localized!(
+                language,
+                "폴더가 존재하지 않습니다: {}",
+                "folder does not exist: {}",
+                path.display()
+            ),
+            Error::FolderAlreadyWatched(folder) => localized!(
+                language,
+                "이미 추가된 폴더입니다: {}",
+                "folder is already watched: {}",
+                folder
+            ),
+            Error::FolderNotWatched(folder) => localized!(
+                language,
+                "폴더를 찾을 수 없습니다: {}",
+                "folder is not watched: {}",
+                folder
+            ),
+            Error::InvalidPattern { pattern, source } => localized!(
+                language,
+                "패턴 오류 '{}': {}",
+                "invalid pattern '{}': {}",
+                pattern,
+                source
+            ),
+            Error::NoteNotFound(id) => localized!(
+                language,
+                "노트를 찾을 수 없습니다: {}",
+                "note not found: {}",
+                id
+            ),
+            Error::TargetNoteNotFound(id) => localized!(
+                language,
+                "대상 노트를 찾을 수 없습니다: {}",
+                "target note not found: {}",
+                id
+            ),
+            Error::EmptyTag(tag) => localized!(
+                language,
+                "태그가 비어 있습니다: '{}'",
+                "tag is empty: '{}'",
+                tag
+            ),
+            Error::InvalidTagChar { tag, ch } => localized!(
+                language,
+                "태그에 사용할 수 없는 문자 '{}': {}",
+                "invalid character '{}' in tag: {}",
+                ch,
+                tag
+            ),
+            Error::TagNotOnNote(tag) => localized!(
+                language,
+                "노트에 없는 태그입니다: {}",
+                "note does not have tag: {}",
+                tag
+            ),
+            Error::TagNotFound(tags) => localized!(
+                language,
+                "태그를 찾을 수 없습니다: {}",
+                "tag not found: {}",
+                tags.join(", ")
+            ),
+            Error::MultipleFolders(folders) => localized!(
+                language,
+                "하나의 노트는 하나의 폴더만 가질 수 있습니다: {} (move 명령으로 폴더를 바꾸세요)",
+                "a note can belong to only one folder: {} (use the move command to change it)",
+                folders.join(", ")
+            ),
+            Error::MixedTagKinds { source, target } => localized!(
+                language,
+                "폴더 태그와 일반 태그는 합칠 수 없습니다: {} → {}",
+                "cannot merge folder and regular tags: {} → {}",
+                source,
+                target
+            ),
+            Error::NoteRefNotFound(reference) => localized!(
+                language,
+                "노트를 찾을 수 없습니다: {}",
+                "no note matches: {}",
+                reference
+            ),
+            Error::AmbiguousNoteRef {
+                reference,
+                candidates,
+            } => localized!(
+                language,
+                "'{}'에 해당하는 노트가 여럿입니다: {}",
+                "'{}' matches several notes: {}",
+                reference,
+                candidates.join(", ")
+            ),
+            Error::NoResultSet => localized!(
+                language,
+                "#번호는 목록이나 검색 결과를 표시한 뒤에 쓸 수 있습니다.",
+                "#N refers to the last list or search results, but none were shown yet."
+            ),
+            Error::ResultOutOfRange { number, len } => localized!(
+                language,
+                "#{}번 노트가 없습니다 (마지막 결과 {} 개).",
+                "there is no #{} (the last results had {} notes).",
+                number,
+                len
+            ),
+            Error::EmptyTitle => localized!(
+                language,
+                "노트 제목이 비어 있습니다.",
+                "note title is empty."
+            ),
+            Error::TemplateNotFound(name) => localized!(
+                language,
+                "템플릿을 찾을 수 없습니다: {}",
+                "template not found: {}",
+                name
+            ),
+            Error::EmptyAlias => localized!(
+                language,
+                "단축어 이름이 비어 있습니다.",
+                "shortcut alias is empty."
+            ),
+            Error::InvalidAlias(alias) => localized!(
+                language,
+                "단축어 이름에는 문자, 숫자, '-', '_', '.'만 사용할 수 있습니다: {}",
+                "shortcut aliases may only contain letters, digits, '-', '_' and '.': {}",
+                alias
+            ),
+            Error::DuplicateShortcut(alias) => localized!(
+                language,
+                "이미 있는 단축어입니다: {}",
+                "shortcut already exists: {}",
+                alias
+            ),
+            Error::ShortcutNotFound(alias) => localized!(
+                language,
+                "단축어를 찾을 수 없습니다: {}",
+                "shortcut not found: {}",
+                alias
+            ),
+            Error::EmptyLinkTarget => localized!(
+                language,
+                "단축어 대상이 비어 있습니다.",
+                "shortcut target is empty."
+            ),
+            Error::InvalidNoteId(id) => localized!(
+                language,
+                "올바른 노트 UUID가 아닙니다: {}",
+                "not a valid note UUID: {}",
+                id
+            ),
+            Error::Query(error) => error.message(language),
+            Error::InvalidMetaRecord { line, reason } => localized!(
+                language,
+                "메타데이터 {}번째 줄: {}",
+                "metadata line {}: {}",
+                line,
+                reason.message(language)
+            ),
+            Error::Watch(source) => localized!(
+                language,
+                "파일 감시 시작 실패: {}",
+                "failed to start file watcher: {}",
+                source
+            ),
+            Error::WatchFolder { path, source } => localized!(
+                language,
+                "폴더 감시 실패 {}: {}",
+                "failed to watch folder {}: {}",
+                path.display(),
+                source
+            ),
+            Error::EditorLaunch { program, source } => localized!(
+                language,
+                "편집기 실행 실패 '{}': {}",
+                "failed to launch editor '{}': {}",
+                program,
+                source
+            ),
+            Error::EditorFailed(status) => localized!(
+                language,
+                "편집기가 오류로 종료되었습니다 ({})",
+                "editor exited with an error ({})",
+                status
+            ),
+            Error::WatchStopped => localized!(
+                language,
+                "파일 감시가 중단되었습니다.",
+                "file watcher stopped."
+            ),
+        }
+    }
+}
+
+impl QueryError {
+    pub fn message(&self, language: Language) -> String {
+        let detail = match self {
+            QueryError::Unexpected(token) => {
+                localized!(language, "예상하지 못한 '{}'", "unexpected '{}'", token)
+            }
+            QueryError::UnclosedQuote => {
+                localized!(language, "닫는 따옴표가 없습니다", "missing closing quote")
+            }
+            QueryError::UnclosedParen => localized!(
+                language,
+                "닫는 괄호가 없습니다",
+                "missing closing parenthesis"
+            ),
+            QueryError::UnexpectedEnd => {
+                localized!(language, "검색어가 끝났습니다", "unexpected end of query")
+            }
+            QueryError::EmptyValue(field) => localized!(
+                language,
+                "'{}' 값이 비어 있습니다",
+                "'{}' has no value",
+                field
+            ),
+            QueryError::InvalidDate(value) => localized!(
+                language,
+                "날짜 형식은 YYYY-MM-DD 입니다: {}",
+                "dates must be YYYY-MM-DD: {}",
+                value
+            ),
+        };
+
+        localized!(
+            language,
+            "검색어 파싱 오류: {}",
+            "invalid query: {}",
+            detail
+        )
+    }
+}
+
+impl MetaRecordError {
+    pub fn message(&self, language: Language) -> String {
+        match self {
+            MetaRecordError::Malformed(detail) => {
+                localized!(language, "형식 오류: {}", "malformed record: {}", detail)
+            }
+            MetaRecordError::MissingKey => localized!(
+                language,
+                "uuid나 path 중 하나가 필요합니다",
+                "needs a uuid or a path"
+            ),
+            MetaRecordError::InvalidUuid(value) => localized!(
+                language,
+                "올바른 UUID가 아닙니다: {}",
+                "not a valid UUID: {}",
+                value
+            ),
+            MetaRecordError::InvalidDate(value) => localized!(
+                language,
+                "날짜 형식은 RFC 3339 입니다 (예: 2024-05-03T09:30:00Z): {}",
+                "dates must be RFC 3339 (e.g. 2024-05-03T09:30:00Z): {}",
+                value
+            ),
+            MetaRecordError::InvalidTag(error) => error.message(language),
+            MetaRecordError::DuplicateNote { first_line } => localized!(
+                language,
+                "{}번째 줄과 같은 노트입니다",
+                "same note as line {}",
+                first_line
+            ),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(language()))
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message(language()))
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::CreateDir { source, .. }
+            | Error::ReadDir { source, .. }
+            | Error::Read { source, .. }
+            | Error::Write { source, .. }
+            | Error::Lock { source, .. }
+            | Error::EditorLaunch { source, .. } => Some(source),
+            Error::Corrupt { source, .. } | Error::Serialize(source) => Some(source),
+            Error::Yaml(source) => Some(source),
+            Error::SerializeCsv(source) => Some(source),
+            Error::InvalidPattern { source, .. } => Some(source.as_ref()),
+            Error::Query(source) => Some(source),
+            Error::InvalidMetaRecord {
+                reason: MetaRecordError::InvalidTag(source),
+                ..
+            } => Some(source.as_ref()),
+            Error::Watch(source) | Error::WatchFolder { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl From<QueryError> for Error {
+    fn from(error: QueryError) -> Self {
+        Error::Query(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_localized_messages() {
+        let error = Error::TagNotOnNote("rust".to_string());
+        assert_eq!(
+            error.message(Language::Korean),
+            "노트에 없는 태그입니다: rust"
+        );
+        assert_eq!(
+            error.message(Language::English),
+            "note does not have tag: rust"
+        );
+
+        let error = Error::from(QueryError::UnclosedQuote);
+        assert_eq!(
+            error.message(Language::English),
+            "invalid query: missing closing quote"
+        );
+        assert!(error.source().is_some());
+
+        let error = Error::EditorLaunch {
+            program: "nvim".to_string(),
+            source: io::Error::from(io::ErrorKind::NotFound),
+        };
+        assert!(
+            error
+                .message(Language::Korean)
+                .starts_with("편집기 실행 실패 'nvim': ")
+        );
+        assert!(
+            error
+                .message(Language::English)
+                .starts_with("failed to launch editor 'nvim': ")
+        );
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn test_language_from_code() {
+        assert_eq!(Language::from_code("en_US.UTF-8"), Some(Language::English));
+        assert_eq!(Language::from_code("ko"), Some(Language::Korean));
+        assert_eq!(Language::from_code("fr"), None);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::error::Error;
+use crate::migrate::Migration;
+use uuid::Uuid;
+
+// NoteApp이 노트를 읽고 동기화하면서 알리는 진행 상황
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum NoteEvent {
+    // 인덱스에 없던 노트 파일을 찾음
+    NoteDiscovered { id: Uuid, path: String },
+    // 파일이 사라져 인덱스에서 제거함
+    NoteDeleted { id: Uuid, path: String },
+    // 이름이 바뀌거나 이동한 노트의 경로를 갱신함
+    NoteMoved { id: Uuid, from: String, to: String },
+    // frontmatter에 UUID를 추가함
+    UuidInjected { id: Uuid, path: String },
+    UuidInjectFailed { path: String, error: Error },
+    // 다른 파일과 같은 UUID를 쓰고 있어 경로로 구분함
+    DuplicateUuid { id: Uuid, path: String },
+    // frontmatter를 읽지 못해 노트를 건너뜀
+    ParseFailed { path: String, error: Error },
+    // watched_folders에 있지만 존재하지 않는 폴더
+    FolderMissing { path: String },
+    // 사라진 노트를 정리한 뒤 제거한 항목 수
+    IndexCleaned { removed: usize },
+    // 이전 버전 .index.json/.shortcuts.json을 백업하고 현재 버전으로 옮김
+    Migrated(Migration),
+    // 저장 도중 중단되어 남은 임시 파일 (restored면 임시 파일로 복구, 아니면 삭제)
+    TempFileRecovered { path: String, restored: bool },
+    // 검색 인덱스를 읽지 못해 새로 만듦
+    SearchIndexRebuilt { error: Error },
+    // 진행을 멈출 정도는 아닌 저장 실패
+    SaveFailed { error: Error },
+    // 다른 md_filer가 잠근 디렉토리를 읽기 전용으로 엶
+    OpenedReadOnly { path: String },
+}
+
+// 이벤트를 받는 쪽 (기본값은 아무것도 하지 않음)
+pub trait Reporter {
+    fn report(&self, event: NoteEvent);
+}
+
+// 이벤트를 모두 무시
+pub struct SilentReporter;
+
+impl Reporter for SilentReporter {
+    fn report(&self, _event: NoteEvent) {}
+}
+
+// 클로저로 이벤트 받기: NoteApp::with_reporter(dir, |event| ...)
+impl<F: Fn(NoteEvent)> Reporter for F {
+    fn report(&self, event: NoteEvent) {
+        self(event)
+    }
+}
@@ -0,0 +1,407 @@
+use crate::links;
+use chrono::{DateTime, Utc};
+use pulldown_cmark::{CowStr, Event, LinkType, Options, Parser, Tag, TagEnd, html};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::hash::Hash;
+use std::path::PathBuf;
+
+// 내보낸 사이트 구조: index.html, notes/, tags/, folders/, search.json, style.css
+pub const NOTES_DIR: &str = "notes";
+pub const TAGS_DIR: &str = "tags";
+pub const FOLDERS_DIR: &str = "folders";
+pub const SEARCH_FILE: &str = "search.json";
+pub const STYLE_FILE: &str = "style.css";
+
+// 내보내기 결과 요약
+#[derive(Debug, Clone, Serialize)]
+pub struct HtmlExport {
+    pub out_dir: PathBuf,
+    pub notes: usize,
+    pub tags: usize,
+    pub folders: usize,
+    // 없는 노트나 내보내지 않은 노트를 가리켜 링크 없이 남긴 본문 링크 수
+    pub unresolved_links: usize,
+}
+
+// search.json 항목 (url은 사이트 루트 기준)
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchEntry {
+    pub title: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folder: Option<String>,
+    pub tags: Vec<String>,
+    pub updated: DateTime<Utc>,
+    pub text: String,
+}
+
+// 목록 페이지의 노트 한 줄
+pub struct ListedNote<'a> {
+    pub page: &'a str,
+    pub title: &'a str,
+    pub updated: DateTime<Utc>,
+}
+
+// 노트 페이지에 들어갈 내용 (body는 이미 HTML로 변환된 본문)
+pub struct NotePage<'a> {
+    pub title: &'a str,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+    // (이름, 페이지)
+    pub folder: Option<(&'a str, &'a str)>,
+    pub tags: Vec<(&'a str, &'a str)>,
+    pub body: &'a str,
+    pub backlinks: Vec<ListedNote<'a>>,
+}
+
+// 이름마다 겹치지 않는 페이지 이름 (같은 이름이면 뒤에 -2, -3 ...)
+// 순서가 같으면 결과도 같도록 입력 순서대로 배정
+pub fn unique_pages<K: Hash + Eq>(
+    items: impl IntoIterator<Item = (K, String)>,
+) -> HashMap<K, String> {
+    let mut used = HashSet::new();
+    let mut pages = HashMap::new();
+    for (key, base) in items {
+        let mut page = base.clone();
+        let mut n = 2;
+        while !used.insert(page.clone()) {
+            page = format!("{}-{}", base, n);
+            n += 1;
+        }
+        pages.insert(key, page);
+    }
+    pages
+}
+
+// 본문이 제목과 같은 # 제목으로 시작하면 페이지 제목과 겹치지 않게 제거
+pub fn strip_title_heading<'a>(content: &'a str, title: &str) -> &'a str {
+    let trimmed = content.trim_start();
+    match trimmed.split_once('\n') {
+        Some((first, rest)) if first.trim_end() == format!("# {}", title) => rest,
+        None if trimmed.trim_end() == format!("# {}", title) => "",
+        _ => content,
+    }
+}
+
+// 마크다운 본문을 HTML로 변환하면서 노트 링크를 내보낸 페이지 링크로 바꿈
+// resolve는 링크 대상(위키 링크 이름 또는 .md 경로)을 노트 페이지 주소로 바꾸며,
+// 찾지 못한 노트 링크는 <span class="broken-link">으로 남기고 그 수를 함께 반환
+pub fn markdown_to_html(
+    content: &str,
+    resolve: impl Fn(&str) -> Option<String>,
+) -> (String, usize) {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_WIKILINKS;
+
+    let mut unresolved = 0;
+    // 열린 링크마다 span으로 바꿨는지
+    let mut broken = Vec::new();
+    let events = Parser::new_ext(content, options).map(|event| match event {
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => {
+            let (target, anchor) = match link_type {
+                LinkType::WikiLink { .. } => match dest_url.split_once('#') {
+                    Some((target, anchor)) => (Some(target.trim().to_string()), Some(anchor)),
+                    None => (Some(dest_url.trim().to_string()), None),
+                },
+                _ => (
+                    links::markdown_note_target(&dest_url),
+                    dest_url.split_once('#').map(|(_, anchor)| anchor),
+                ),
+            };
+            let Some(target) = target.filter(|target| !target.is_empty()) else {
+                broken.push(false);
+                return Event::Start(Tag::Link {
+                    link_type,
+                    dest_url,
+                    title,
+                    id,
+                });
+            };
+
+            match resolve(&target) {
+                Some(href) => {
+                    broken.push(false);
+                    let href = match anchor {
+                        Some(anchor) => format!("{}#{}", href, anchor),
+                        None => href,
+                    };
+                    Event::Start(Tag::Link {
+                        link_type: LinkType::Inline,
+                        dest_url: href.into(),
+                        title,
+                        id,
+                    })
+                }
+                None => {
+                    broken.push(true);
+                    unresolved += 1;
+                    Event::Html(
+                        format!(
+                            "<span class=\"broken-link\" title=\"{}\">",
+                            escape_html(&target)
+                        )
+                        .into(),
+                    )
+                }
+            }
+        }
+        Event::End(TagEnd::Link) if broken.pop() == Some(true) => {
+            Event::Html(CowStr::Borrowed("</span>"))
+        }
+        event => event,
+    });
+
+    let mut output = String::new();
+    html::push_html(&mut output, events);
+    (output, unresolved)
+}
+
+// 검색용 본문 텍스트 (마크다운 기호 없이, 공백은 하나로)
+pub fn plain_text(content: &str) -> String {
+    let mut text = String::new();
+    for event in Parser::new_ext(content, Options::ENABLE_TABLES | Options::ENABLE_WIKILINKS) {
+        match event {
+            Event::Text(part) | Event::Code(part) => text.push_str(&part),
+            Event::SoftBreak | Event::HardBreak => text.push(' '),
+            Event::End(
+                TagEnd::Paragraph
+                | TagEnd::Heading(_)
+                | TagEnd::Item
+                | TagEnd::CodeBlock
+                | TagEnd::TableCell,
+            ) => text.push(' '),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+// 공통 페이지 틀 (root는 사이트 루트까지의 상대 경로, 예: "../")
+fn layout(title: &str, root: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ko\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<link rel=\"stylesheet\" href=\"{root}{STYLE_FILE}\">\n</head>\n\
+         <body>\n<nav><a href=\"{root}index.html\">모든 노트</a></nav>\n<main>\n{body}</main>\n</body>\n</html>\n",
+        title = escape_html(title),
+    )
+}
+
+fn note_list(notes: &[ListedNote], root: &str) -> String {
+    let mut html = String::from("<ul class=\"notes\">\n");
+    for note in notes {
+        let _ = writeln!(
+            html,
+            "<li><a href=\"{}{}/{}.html\">{}</a> <time>{}</time></li>",
+            root,
+            NOTES_DIR,
+            escape_html(note.page),
+            escape_html(note.title),
+            note.updated.format("%Y-%m-%d")
+        );
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+// notes/<페이지>.html
+pub fn note_page(note: &NotePage) -> String {
+    let root = "../";
+    let mut body = format!("<h1>{}</h1>\n<p class=\"meta\">", escape_html(note.title));
+    let _ = write!(
+        body,
+        "<time>{}</time> 작성, <time>{}</time> 수정",
+        note.created.format("%Y-%m-%d"),
+        note.updated.format("%Y-%m-%d")
+    );
+    if let Some((name, page)) = note.folder {
+        let _ = write!(
+            body,
+            " · 📁 <a href=\"{}{}/{}.html\">{}</a>",
+            root,
+            FOLDERS_DIR,
+            escape_html(page),
+            escape_html(name)
+        );
+    }
+    for (tag, page) in &note.tags {
+        let _ = write!(
+            body,
+            " <a class=\"tag\" href=\"{}{}/{}.html\">#{}</a>",
+            root,
+            TAGS_DIR,
+            escape_html(page),
+            escape_html(tag)
+        );
+    }
+    body.push_str("</p>\n<article>\n");
+    body.push_str(note.body);
+    body.push_str("</article>\n");
+
+    if !note.backlinks.is_empty() {
+        body.push_str("<section class=\"backlinks\">\n<h2>이 노트를 참조하는 노트</h2>\n");
+        body.push_str(&note_list(&note.backlinks, root));
+        body.push_str("</section>\n");
+    }
+    layout(note.title, root, &body)
+}
+
+// tags/<페이지>.html, folders/<페이지>.html
+pub fn list_page(heading: &str, notes: &[ListedNote]) -> String {
+    let root = "../";
+    let body = format!(
+        "<h1>{}</h1>\n<p class=\"meta\">{} 개 노트</p>\n{}",
+        escape_html(heading),
+        notes.len(),
+        note_list(notes, root)
+    );
+    layout(heading, root, &body)
+}
+
+// index.html: 검색창, 폴더/태그 목록, 전체 노트 목록
+// folders와 tags는 (이름, 페이지, 노트 수)
+pub fn index_page(
+    notes: &[ListedNote],
+    folders: &[(&str, &str, usize)],
+    tags: &[(&str, &str, usize)],
+) -> String {
+    let mut body = String::from(
+        "<h1>노트</h1>\n<input id=\"search\" type=\"search\" placeholder=\"검색\" autocomplete=\"off\">\n\
+         <ul id=\"results\" class=\"notes\"></ul>\n<div id=\"browse\">\n",
+    );
+    for (heading, dir, items) in [("폴더", FOLDERS_DIR, folders), ("태그", TAGS_DIR, tags)] {
+        if items.is_empty() {
+            continue;
+        }
+        let _ = writeln!(body, "<h2>{}</h2>\n<p class=\"tags\">", heading);
+        for (name, page, count) in items {
+            let _ = writeln!(
+                body,
+                "<a class=\"tag\" href=\"{}/{}.html\">{}</a> <small>{}</small>",
+                dir,
+                escape_html(page),
+                escape_html(name),
+                count
+            );
+        }
+        body.push_str("</p>\n");
+    }
+    let _ = writeln!(body, "<h2>모든 노트 ({})</h2>", notes.len());
+    body.push_str(&note_list(notes, ""));
+    body.push_str("</div>\n");
+    let _ = writeln!(
+        body,
+        "<script>\n{}</script>",
+        SEARCH_SCRIPT.replace("{SEARCH_FILE}", SEARCH_FILE)
+    );
+    layout("노트", "", &body)
+}
+
+// 검색어의 모든 단어가 제목/태그/본문에 들어 있는 노트 표시 (search.json은 처음 입력할 때 읽음)
+const SEARCH_SCRIPT: &str = r#"const input = document.getElementById('search');
+const results = document.getElementById('results');
+const browse = document.getElementById('browse');
+let entries = null;
+input.addEventListener('input', async () => {
+  entries ??= await fetch('{SEARCH_FILE}').then((r) => r.json());
+  const terms = input.value.toLowerCase().split(/\s+/).filter(Boolean);
+  results.replaceChildren();
+  browse.hidden = terms.length > 0;
+  for (const entry of entries) {
+    const haystack = [entry.title, entry.folder ?? '', ...entry.tags, entry.text].join(' ').toLowerCase();
+    if (terms.length === 0 || !terms.every((term) => haystack.includes(term))) continue;
+    const item = document.createElement('li');
+    const link = document.createElement('a');
+    link.href = entry.url;
+    link.textContent = entry.title;
+    item.append(link);
+    results.append(item);
+  }
+});
+"#;
+
+pub const STYLE: &str =
+    "body { margin: 0; font-family: system-ui, sans-serif; line-height: 1.6; color: #222; }
+nav { padding: 0.5rem 1rem; border-bottom: 1px solid #ddd; }
+main { max-width: 48rem; margin: 0 auto; padding: 1rem; }
+a { color: #0b61a4; }
+.meta, time, small { color: #777; }
+.tag { margin-right: 0.4rem; }
+.broken-link { color: #b00; text-decoration: underline dotted; }
+pre { background: #f5f5f5; padding: 0.75rem; overflow-x: auto; }
+code { font-family: ui-monospace, monospace; }
+blockquote { margin-left: 0; padding-left: 1rem; border-left: 3px solid #ddd; color: #555; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ddd; padding: 0.25rem 0.5rem; }
+#search { width: 100%; padding: 0.5rem; font-size: 1rem; box-sizing: border-box; }
+ul.notes { padding-left: 1.2rem; }
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_html_rewrites_note_links() {
+        let content = "[[Rust]] [[Rust#ownership|소유권]] [[Nowhere]]\n\n\
+                       [웹](web.md) [외부](https://example.com)\n";
+        let (html, unresolved) = markdown_to_html(content, |target| match target {
+            "Rust" => Some("rust.html".to_string()),
+            "web.md" => Some("web.html".to_string()),
+            _ => None,
+        });
+
+        assert!(html.contains("<a href=\"rust.html\">Rust</a>"));
+        assert!(html.contains("<a href=\"rust.html#ownership\">소유권</a>"));
+        assert!(html.contains("<span class=\"broken-link\" title=\"Nowhere\">Nowhere</span>"));
+        assert!(html.contains("<a href=\"web.html\">웹</a>"));
+        assert!(html.contains("<a href=\"https://example.com\">외부</a>"));
+        assert_eq!(unresolved, 1);
+    }
+
+    #[test]
+    fn test_unique_pages_and_title_heading() {
+        let pages = unique_pages([
+            (1, "a".to_string()),
+            (2, "a".to_string()),
+            (3, "b".to_string()),
+        ]);
+        assert_eq!(pages[&1], "a");
+        assert_eq!(pages[&2], "a-2");
+        assert_eq!(pages[&3], "b");
+
+        assert_eq!(strip_title_heading("\n# Rust\n\nbody", "Rust"), "\nbody");
+        assert_eq!(
+            strip_title_heading("# Other\nbody", "Rust"),
+            "# Other\nbody"
+        );
+        assert_eq!(
+            plain_text("# Rust\n\nSome **bold** `code`"),
+            "Rust Some bold code"
+        );
+    }
+}
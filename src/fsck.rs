@@ -0,0 +1,151 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+// 인덱스, 단축어, frontmatter 사이의 불일치
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FsckIssue {
+    // 인덱스에 있지만 파일이 사라짐
+    MissingFile {
+        id: Uuid,
+        path: String,
+    },
+    // frontmatter에 id가 없음
+    MissingId {
+        id: Uuid,
+        path: String,
+    },
+    // frontmatter의 id가 인덱스 키와 다름
+    IdMismatch {
+        id: Uuid,
+        file_id: Uuid,
+        path: String,
+    },
+    // 다른 파일(kept)이 이미 쓰고 있는 id를 가진 파일 (인덱스에는 assigned로 등록됨)
+    DuplicateId {
+        id: Uuid,
+        path: String,
+        kept: String,
+        assigned: Uuid,
+    },
+    // 인덱스의 제목이 frontmatter/본문의 제목과 다름
+    StaleTitle {
+        id: Uuid,
+        path: String,
+        indexed: String,
+        actual: String,
+    },
+    // 인덱스에 없는 노트의 단축어
+    OrphanShortcuts {
+        id: Uuid,
+        aliases: Vec<String>,
+    },
+}
+
+impl FsckIssue {
+    // 표시용 분류 (사라진 파일 → UUID 문제 → 제목 → 단축어 순)
+    pub fn category(&self) -> FsckCategory {
+        match self {
+            FsckIssue::MissingFile { .. } => FsckCategory::File,
+            FsckIssue::MissingId { .. }
+            | FsckIssue::IdMismatch { .. }
+            | FsckIssue::DuplicateId { .. } => FsckCategory::Id,
+            FsckIssue::StaleTitle { .. } => FsckCategory::Title,
+            FsckIssue::OrphanShortcuts { .. } => FsckCategory::Shortcut,
+        }
+    }
+
+    fn path(&self) -> &str {
+        match self {
+            FsckIssue::MissingFile { path, .. }
+            | FsckIssue::MissingId { path, .. }
+            | FsckIssue::IdMismatch { path, .. }
+            | FsckIssue::DuplicateId { path, .. }
+            | FsckIssue::StaleTitle { path, .. } => path,
+            FsckIssue::OrphanShortcuts { .. } => "",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FsckCategory {
+    File,
+    Id,
+    Title,
+    Shortcut,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FsckReport {
+    pub issues: Vec<FsckIssue>,
+    // --repair로 고친 문제 수
+    pub repaired: usize,
+}
+
+impl FsckReport {
+    pub fn new(mut issues: Vec<FsckIssue>) -> Self {
+        issues.sort_by(|a, b| {
+            a.category()
+                .cmp(&b.category())
+                .then_with(|| a.path().cmp(b.path()))
+        });
+        FsckReport {
+            issues,
+            repaired: 0,
+        }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    pub fn in_category(&self, category: FsckCategory) -> impl Iterator<Item = &FsckIssue> {
+        self.issues
+            .iter()
+            .filter(move |issue| issue.category() == category)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_sorted_by_category_then_path() {
+        let id = Uuid::new_v4();
+        let report = FsckReport::new(vec![
+            FsckIssue::OrphanShortcuts {
+                id,
+                aliases: vec!["a".to_string()],
+            },
+            FsckIssue::MissingId {
+                id,
+                path: "b.md".to_string(),
+            },
+            FsckIssue::MissingFile {
+                id,
+                path: "z.md".to_string(),
+            },
+            FsckIssue::MissingId {
+                id,
+                path: "a.md".to_string(),
+            },
+        ]);
+
+        let order: Vec<_> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.category(), issue.path()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (FsckCategory::File, "z.md"),
+                (FsckCategory::Id, "a.md"),
+                (FsckCategory::Id, "b.md"),
+                (FsckCategory::Shortcut, ""),
+            ]
+        );
+    }
+}
@@ -1,33 +1,106 @@
+use crate::error::{Error, Result};
+use crate::migrate::{self, INDEX_VERSION, Migration};
+use crate::storage;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::fs;
 use std::path::Path;
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct IndexEntry {
     pub filename: String,
-    #[serde(default)]
     pub file_path: String, // 파일의 전체 경로 (폴더 포함)
     pub title: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub tags: Vec<String>,
+    // frontmatter(id 제외)와 본문의 해시 (수정일 갱신 판단, UUID가 없는 파일의 이름 변경 감지용)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub content_hash: String,
+    // 마지막으로 읽었을 때 파일의 수정 시각과 크기 (바뀌지 않은 파일은 다시 읽지 않음)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub size: u64,
+}
+
+// 내용 변경 감지용 해시 (SHA-256 16진수 문자열)
+pub fn content_hash(content: &str) -> String {
+    Sha256::digest(content.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+// 태그 문법 검사: 공백, 쉼표, #, 따옴표, 괄호는 사용할 수 없고
+// @는 폴더 태그의 첫 글자로만 사용 가능
+pub fn validate_tag(tag: &str) -> Result<()> {
+    let name = tag.strip_prefix('@').unwrap_or(tag);
+
+    if name.is_empty() {
+        return Err(Error::EmptyTag(tag.to_string()));
+    }
+
+    if let Some(c) = name
+        .chars()
+        .find(|c| c.is_whitespace() || matches!(c, ',' | '#' | '@' | '"' | '(' | ')'))
+    {
+        return Err(Error::InvalidTagChar {
+            tag: tag.to_string(),
+            ch: c,
+        });
+    }
+
+    Ok(())
+}
+
+// watched folder별 스캔 규칙
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FolderRules {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>, // 하위 폴더 탐색 깊이 (없으면 무제한, 0이면 최상위만)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude: Vec<String>, // .gitignore 형식 제외 패턴 (예: node_modules, archive/)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>, // 포함할 파일 glob (비어 있으면 모든 .md 파일)
+}
+
+impl FolderRules {
+    pub fn is_default(&self) -> bool {
+        *self == FolderRules::default()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NoteIndex {
-    pub mappings: HashMap<Uuid, IndexEntry>,
+    pub version: u32, // 파일 형식 버전 (migrate.rs 참고)
+    mappings: HashMap<Uuid, IndexEntry>,
     #[serde(default)]
     pub watched_folders: Vec<String>, // 관리 중인 폴더 목록
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub folder_rules: HashMap<String, FolderRules>, // 폴더 경로 -> 스캔 규칙
+    // 파일 경로 -> UUID (저장하지 않고 로드할 때 만듦)
+    // 항목의 file_path는 add_entry/move_entry로만 바꿔야 이 표가 맞게 유지됨
+    #[serde(skip)]
+    paths: HashMap<String, Uuid>,
+}
+
+impl Default for NoteIndex {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl NoteIndex {
     pub fn new() -> Self {
         NoteIndex {
+            version: INDEX_VERSION,
             mappings: HashMap::new(),
             watched_folders: Vec::new(),
+            folder_rules: HashMap::new(),
+            paths: HashMap::new(),
         }
     }
 
@@ -40,6 +113,7 @@ impl NoteIndex {
     pub fn remove_watched_folder(&mut self, folder: &str) -> bool {
         if let Some(pos) = self.watched_folders.iter().position(|f| f == folder) {
             self.watched_folders.remove(pos);
+            self.folder_rules.remove(folder);
             true
         } else {
             false
@@ -50,45 +124,100 @@ impl NoteIndex {
         &self.watched_folders
     }
 
-    pub fn load(path: &Path) -> Result<Self, String> {
-        let content =
-            fs::read_to_string(path).map_err(|e| format!("인덱스 파일 읽기 실패: {}", e))?;
+    pub fn get_folder_rules(&self, folder: &str) -> FolderRules {
+        self.folder_rules.get(folder).cloned().unwrap_or_default()
+    }
+
+    pub fn set_folder_rules(&mut self, folder: &str, rules: FolderRules) {
+        if rules.is_default() {
+            self.folder_rules.remove(folder);
+        } else {
+            self.folder_rules.insert(folder.to_string(), rules);
+        }
+    }
 
-        serde_json::from_str(&content).map_err(|e| format!("인덱스 파일 파싱 실패: {}", e))
+    // 이전 버전 파일이면 백업 후 현재 버전으로 옮겨 저장하고 그 기록을 함께 반환
+    pub fn load(path: &Path) -> Result<(Self, Option<Migration>)> {
+        let (mut index, migration): (Self, _) = migrate::load_index(path)?;
+        index.paths = index
+            .mappings
+            .iter()
+            .map(|(id, entry)| (entry.file_path.clone(), *id))
+            .collect();
+        Ok((index, migration))
     }
 
-    pub fn save(&self, path: &Path) -> Result<(), String> {
-        let content =
-            serde_json::to_string_pretty(&self).map_err(|e| format!("JSON 직렬화 실패: {}", e))?;
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(&self).map_err(Error::Serialize)?;
 
-        fs::write(path, content).map_err(|e| format!("인덱스 파일 저장 실패: {}", e))
+        storage::write_atomic(path, content)
     }
 
     pub fn add_entry(&mut self, id: Uuid, entry: IndexEntry) {
-        self.mappings.insert(id, entry);
+        self.paths.insert(entry.file_path.clone(), id);
+        if let Some(old) = self.mappings.insert(id, entry) {
+            self.forget_path(&old.file_path, &id);
+        }
     }
 
     pub fn remove_entry(&mut self, id: &Uuid) -> Option<IndexEntry> {
-        self.mappings.remove(id)
+        let entry = self.mappings.remove(id)?;
+        self.forget_path(&entry.file_path, id);
+        Some(entry)
+    }
+
+    // 이름이 바뀌거나 옮겨진 파일의 경로 갱신. 이전 경로를 반환
+    pub fn move_entry(&mut self, id: &Uuid, file_path: String) -> Option<String> {
+        let entry = self.mappings.get_mut(id)?;
+        entry.filename = Path::new(&file_path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let old = std::mem::replace(&mut entry.file_path, file_path.clone());
+        self.paths.insert(file_path, *id);
+        self.forget_path(&old, id);
+        Some(old)
     }
 
-    pub fn get_entry(&self, id: &Uuid) -> Option<&IndexEntry> {
+    // 경로가 아직 이 노트를 가리키고 있으면 표에서 제거
+    fn forget_path(&mut self, file_path: &str, id: &Uuid) {
+        let still_used = self
+            .mappings
+            .get(id)
+            .is_some_and(|entry| entry.file_path == file_path);
+        if !still_used && self.paths.get(file_path) == Some(id) {
+            self.paths.remove(file_path);
+        }
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<&IndexEntry> {
         self.mappings.get(id)
     }
 
-    pub fn find_by_filename(&self, filename: &str) -> Option<(Uuid, &IndexEntry)> {
-        self.mappings
-            .iter()
-            .find(|(_, entry)| entry.filename == filename)
-            .map(|(id, entry)| (*id, entry))
+    // 제목/태그/시각 등 수정용 (file_path는 move_entry로만 바꿀 것)
+    pub fn get_mut(&mut self, id: &Uuid) -> Option<&mut IndexEntry> {
+        self.mappings.get_mut(id)
     }
 
-    #[allow(dead_code)]
-    pub fn update_filename(&mut self, id: &Uuid, new_filename: String) {
-        if let Some(entry) = self.mappings.get_mut(id) {
-            entry.filename = new_filename;
-            entry.updated_at = Utc::now();
-        }
+    pub fn iter(&self) -> impl Iterator<Item = (&Uuid, &IndexEntry)> {
+        self.mappings.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.mappings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    // 전체 경로로 찾기 (다른 폴더의 같은 파일명과 구분)
+    pub fn find_by_path(&self, file_path: &str) -> Option<(Uuid, &IndexEntry)> {
+        let id = self.paths.get(file_path)?;
+        self.mappings
+            .get(id)
+            .filter(|entry| entry.file_path == file_path)
+            .map(|entry| (*id, entry))
     }
 
     // 태그별로 노트 찾기
@@ -115,3 +244,75 @@ impl NoteIndex {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(file_path: &str) -> IndexEntry {
+        let now = Utc::now();
+        IndexEntry {
+            filename: Path::new(file_path)
+                .file_name()
+                .unwrap()
+                .to_string_lossy()
+                .to_string(),
+            file_path: file_path.to_string(),
+            title: String::new(),
+            created_at: now,
+            updated_at: now,
+            tags: Vec::new(),
+            content_hash: String::new(),
+            modified: None,
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn test_find_by_path_follows_add_move_and_remove() {
+        let mut index = NoteIndex::new();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        index.add_entry(a, entry("notes/a.md"));
+        index.add_entry(b, entry("notes/sub/a.md"));
+        assert_eq!(index.find_by_path("notes/a.md").map(|(id, _)| id), Some(a));
+        assert_eq!(
+            index.find_by_path("notes/sub/a.md").map(|(id, _)| id),
+            Some(b)
+        );
+
+        // 같은 UUID로 다시 추가하면 이전 경로는 지워짐
+        index.add_entry(a, entry("notes/renamed.md"));
+        assert!(index.find_by_path("notes/a.md").is_none());
+        assert_eq!(
+            index.find_by_path("notes/renamed.md").map(|(id, _)| id),
+            Some(a)
+        );
+
+        assert_eq!(
+            index.move_entry(&b, "notes/moved.md".to_string()),
+            Some("notes/sub/a.md".to_string())
+        );
+        assert!(index.find_by_path("notes/sub/a.md").is_none());
+        let (id, moved) = index.find_by_path("notes/moved.md").unwrap();
+        assert_eq!((id, moved.filename.as_str()), (b, "moved.md"));
+
+        index.remove_entry(&a);
+        assert!(index.find_by_path("notes/renamed.md").is_none());
+    }
+
+    #[test]
+    fn test_load_rebuilds_path_lookup() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join(".index.json");
+        let mut index = NoteIndex::new();
+        let id = Uuid::new_v4();
+        index.add_entry(id, entry("notes/a.md"));
+        index.save(&path).unwrap();
+
+        let (loaded, _) = NoteIndex::load(&path).unwrap();
+        assert_eq!(
+            loaded.find_by_path("notes/a.md").map(|(id, _)| id),
+            Some(id)
+        );
+    }
+}
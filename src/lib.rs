@@ -1,4 +1,19 @@
 pub mod app;
+pub mod check;
+pub mod error;
+pub mod events;
+pub mod export;
+pub mod fsck;
 pub mod index;
+pub mod links;
+pub mod meta;
+pub mod migrate;
 pub mod note;
+pub mod query;
+pub mod reference;
+pub mod scan;
+pub mod search;
 pub mod shortcuts;
+pub mod storage;
+pub mod template;
+pub mod watch;
@@ -0,0 +1,259 @@
+use serde::Serialize;
+use std::path::{Component, Path, PathBuf};
+use uuid::Uuid;
+
+// 링크 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Wiki,     // [[Title]], [[uuid]], [[file.md|label]]
+    Markdown, // [text](other.md)
+}
+
+// 본문에서 찾은 링크 (아직 UUID로 해석하지 않은 상태)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RawLink {
+    pub target: String,
+    pub label: Option<String>,
+    pub line_number: usize, // 1부터 시작 (parse_links는 받은 텍스트 기준, Note::links는 파일 기준)
+    pub kind: LinkKind,
+}
+
+// 해석된 링크 (대상 노트를 찾지 못하면 resolved는 None)
+#[derive(Debug, Clone, Serialize)]
+pub struct Link {
+    #[serde(flatten)]
+    pub raw: RawLink,
+    pub resolved: Option<Uuid>,
+}
+
+// 다른 노트가 이 노트를 참조하는 방식
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BacklinkSource {
+    Link { line_number: usize, kind: LinkKind },
+    Shortcut { alias: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Backlink {
+    pub source: Uuid,
+    #[serde(flatten)]
+    pub via: BacklinkSource,
+}
+
+// 본문에서 위키 링크와 마크다운 노트 링크 찾기 (코드 블록과 인라인 코드는 제외)
+pub fn parse_links(content: &str) -> Vec<RawLink> {
+    let mut links = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m));
+        match (fence, marker) {
+            (None, Some(m)) => fence = Some(m),
+            (Some(open), Some(m)) if open == m => fence = None,
+            _ => {}
+        }
+        if fence.is_some() || marker.is_some() {
+            continue;
+        }
+
+        // 인라인 코드 (`...`)는 짝수 번째 조각만 검사
+        for (i, part) in line.split('`').enumerate() {
+            if i % 2 == 0 {
+                parse_line(part, idx + 1, &mut links);
+            }
+        }
+    }
+
+    links
+}
+
+fn parse_line(line: &str, line_number: usize, links: &mut Vec<RawLink>) {
+    let mut rest = line;
+
+    while let Some(pos) = rest.find('[') {
+        let is_image = rest[..pos].ends_with('!');
+        rest = &rest[pos..];
+
+        // [[target|label]]
+        if let Some(inner) = rest.strip_prefix("[[")
+            && let Some(end) = inner.find("]]")
+        {
+            let body = &inner[..end];
+            let (target, label) = match body.split_once('|') {
+                Some((target, label)) => (target, Some(label.trim().to_string())),
+                None => (body, None),
+            };
+            let target = strip_anchor(target.trim());
+            if !target.is_empty() {
+                links.push(RawLink {
+                    target: target.to_string(),
+                    label: label.filter(|l| !l.is_empty()),
+                    line_number,
+                    kind: LinkKind::Wiki,
+                });
+            }
+            rest = &inner[end + 2..];
+            continue;
+        }
+
+        // [text](target)
+        if let Some(close) = rest.find("](")
+            && !rest[1..close].contains('[')
+            && let Some(end) = rest[close + 2..].find(')')
+        {
+            let text = &rest[1..close];
+            let target = rest[close + 2..close + 2 + end].trim();
+            // 제목이 붙은 링크: [text](file.md "title")
+            let target = target.split_whitespace().next().unwrap_or_default();
+            let target = target.trim_start_matches('<').trim_end_matches('>');
+
+            if !is_image && let Some(target) = markdown_note_target(target) {
+                links.push(RawLink {
+                    target,
+                    label: Some(text.to_string()).filter(|t| !t.is_empty()),
+                    line_number,
+                    kind: LinkKind::Markdown,
+                });
+            }
+            rest = &rest[close + 2 + end + 1..];
+            continue;
+        }
+
+        rest = &rest[1..];
+    }
+}
+
+// 마크다운 링크 중 로컬 .md 파일을 가리키는 것만 노트 링크로 취급
+pub fn markdown_note_target(target: &str) -> Option<String> {
+    if target.contains("://") || target.starts_with("mailto:") || target.starts_with('#') {
+        return None;
+    }
+
+    let path = percent_decode(strip_anchor(target));
+    path.to_lowercase().ends_with(".md").then_some(path)
+}
+
+fn strip_anchor(target: &str) -> &str {
+    target.split('#').next().unwrap_or_default()
+}
+
+// %20 같은 퍼센트 인코딩 해제 (잘못된 인코딩은 그대로 둠)
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(hex) = text.get(i + 1..i + 3)
+            && let Ok(byte) = u8::from_str_radix(hex, 16)
+        {
+            decoded.push(byte);
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    String::from_utf8(decoded).unwrap_or_else(|_| text.to_string())
+}
+
+// 파일 시스템을 보지 않고 ., .. 정리
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(content: &str) -> Vec<(String, Option<String>, LinkKind)> {
+        parse_links(content)
+            .into_iter()
+            .map(|link| (link.target, link.label, link.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_wiki_links() {
+        let id = Uuid::new_v4();
+        let content = format!(
+            "See [[Rust Notes]] and [[{}]].\nAlso [[daily/2024-01-01.md|yesterday]] and [[Topic#Section]].\n[[ ]] is empty",
+            id
+        );
+
+        assert_eq!(
+            targets(&content),
+            vec![
+                ("Rust Notes".to_string(), None, LinkKind::Wiki),
+                (id.to_string(), None, LinkKind::Wiki),
+                (
+                    "daily/2024-01-01.md".to_string(),
+                    Some("yesterday".to_string()),
+                    LinkKind::Wiki
+                ),
+                ("Topic".to_string(), None, LinkKind::Wiki),
+            ]
+        );
+        assert_eq!(parse_links(&content)[2].line_number, 2);
+    }
+
+    #[test]
+    fn test_parse_markdown_links_only_local_notes() {
+        let content = "[one](one.md) [web](https://a.com/x.md) ![img](pic.md)\n\
+                       [two](../dir/My%20Note.md#part \"Title\") [pdf](spec.pdf) [top](#top)";
+
+        assert_eq!(
+            targets(content),
+            vec![
+                (
+                    "one.md".to_string(),
+                    Some("one".to_string()),
+                    LinkKind::Markdown
+                ),
+                (
+                    "../dir/My Note.md".to_string(),
+                    Some("two".to_string()),
+                    LinkKind::Markdown
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_links_in_code_are_ignored() {
+        let content = "`[[Inline]]` [[Real]]\n```\n[[Fenced]]\n[x](x.md)\n```\n";
+        assert_eq!(
+            targets(content),
+            vec![("Real".to_string(), None, LinkKind::Wiki)]
+        );
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path(Path::new("notes/a/./../b/c.md")),
+            PathBuf::from("notes/b/c.md")
+        );
+        assert_eq!(
+            normalize_path(Path::new("../x.md")),
+            PathBuf::from("../x.md")
+        );
+    }
+}
@@ -1,39 +1,98 @@
-mod app;
-mod index;
-mod note;
-mod shortcuts;
+mod cli;
+mod markdown;
+mod pager;
+mod tui;
 
-use app::NoteApp;
-use std::env;
+use chrono::{DateTime, Utc};
+use clap::Parser;
+use cli::Cli;
+use md_filer::app::NoteApp;
+use md_filer::check::{CheckReport, Issue};
+use md_filer::error::{self, Error, Language};
+use md_filer::events::{NoteEvent, Reporter};
+use md_filer::export::HtmlExport;
+use md_filer::fsck::{FsckCategory, FsckIssue, FsckReport};
+use md_filer::links::BacklinkSource;
+use md_filer::meta::{FieldChange, MetaImport};
+use md_filer::note::{LinkTarget, Note, Shortcut};
+use md_filer::reference;
+use md_filer::search::SearchHit;
+use md_filer::watch::{NoteChange, NoteWatcher};
+use std::fmt::Write as _;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use uuid::Uuid;
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // 노트 디렉토리 설정
-    let notes_dir = env::var("NOTES_DIR")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("./notes"));
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
 
+    // 노트 디렉토리 설정 (--notes-dir > NOTES_DIR > ./notes)
+    let notes_dir = cli.resolve_notes_dir();
+
+    // 오류 메시지 언어 (MD_FILER_LANG=en이면 영어, 기본값은 한국어)
+    if let Some(language) = std::env::var("MD_FILER_LANG")
+        .ok()
+        .and_then(|code| Language::from_code(&code))
+    {
+        error::set_language(language);
+    }
+
+    // 하위 명령어가 있으면 한 번만 실행하고 종료
+    if let Some(command) = cli.command {
+        return Ok(cli::run(command, notes_dir, cli.format));
+    }
+
+    run_repl(notes_dir)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+// 대화형 모드
+fn run_repl(notes_dir: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
     println!("🎉 노트앱에 오신 것을 환영합니다!");
     println!("📂 노트 디렉토리: {}", notes_dir.display());
 
     // 앱 초기화
-    let mut app = NoteApp::new(notes_dir.clone())?;
+    let mut app = NoteApp::with_reporter(notes_dir.clone(), ConsoleReporter)?;
 
     // 시작 시 목록 표시
-    show_notes_list(&app);
+    show_notes_list(&mut app);
+
+    // 명령 사이에 바뀐 파일을 반영하기 위한 감시 (실패하면 수동 refresh만 사용)
+    let watcher = match NoteWatcher::new(app.list_watched_folders()) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            eprintln!("⚠️  {} - refresh 명령으로 직접 동기화하세요", e);
+            None
+        }
+    };
 
     loop {
         println!(
-            "\n명령어: [l]ist, <번호>, [s]how <번호>, [se]arch <검색어>, [t]ags, [f]olders, [a]dd-folder <경로>, [r]efresh, [q]uit"
+            "\n명령어: [l]ist, <노트>, [s]how [--raw] <노트>, [e]dit <노트>, [se]arch <검색어>, new [--template <이름>] <제목>, [t]ags, [f]olders, [a]dd-folder <경로>, depth <N|none> <경로>, exclude <패턴> <경로>, include <glob> <경로>, clear-rules <경로>, tag-add <노트> <태그..>, tag-remove <노트> <태그..>, tag-rename <이전> <새 태그>, tag-merge <대상> <태그..>, move <노트> <@폴더|none>, shortcuts <노트>, shortcut-add <노트> <별칭> <대상> [설명], shortcut-edit <노트> <별칭> <대상> [설명], shortcut-remove <노트> <별칭>, links <노트>, backlinks <노트>, check, fsck [--repair], [r]efresh, [q]uit\n<노트>: 목록/검색 결과 번호 (#N), UUID 앞부분, 파일명 또는 제목"
         );
         print!("> ");
         io::stdout().flush()?;
 
         let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        // 입력이 끝나면 (EOF) 종료
+        if io::stdin().read_line(&mut input)? == 0 {
+            println!();
+            break;
+        }
         let input = input.trim();
 
+        // 입력을 기다리는 동안 바뀐 파일 반영
+        if let Some(watcher) = &watcher {
+            let paths = watcher.pending();
+            if !paths.is_empty() {
+                match app.refresh_paths(&paths) {
+                    Ok(changes) => print_changes(&changes),
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+        }
+
         let parts: Vec<&str> = input.split_whitespace().collect();
         if parts.is_empty() {
             continue;
@@ -41,18 +100,24 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         match parts[0] {
             "l" | "list" => {
-                show_notes_list(&app);
+                show_notes_list(&mut app);
             }
             "s" | "show" => {
-                if parts.len() < 2 {
-                    println!("❌ 사용법: show <번호>");
+                let raw = parts.contains(&"--raw");
+                let words: Vec<&str> = parts[1..]
+                    .iter()
+                    .copied()
+                    .filter(|word| *word != "--raw")
+                    .collect();
+                if words.is_empty() {
+                    println!("❌ 사용법: show [--raw] <노트>");
                     continue;
                 }
-                show_note_detail(&app, parts[1]);
+                show_note_detail(&app, &words.join(" "), raw);
             }
             // 숫자만 입력한 경우 show 명령으로 처리
             num if num.parse::<usize>().is_ok() => {
-                show_note_detail(&app, num);
+                show_note_detail(&app, num, false);
             }
             "se" | "search" => {
                 if parts.len() < 2 {
@@ -60,7 +125,25 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     continue;
                 }
                 let query = parts[1..].join(" ");
-                search_notes(&app, &query);
+                search_notes(&mut app, &query);
+            }
+            "new" => {
+                let (template, title) = match parts.get(1) {
+                    Some(&"--template") if parts.len() > 3 => (Some(parts[2]), &parts[3..]),
+                    Some(&"--template") => {
+                        println!("❌ 사용법: new --template <이름> <제목>");
+                        continue;
+                    }
+                    _ => (None, &parts[1..]),
+                };
+                if title.is_empty() {
+                    println!("❌ 사용법: new [--template <이름>] <제목>");
+                    continue;
+                }
+                match app.create_note(&title.join(" "), None, &[], template) {
+                    Ok(id) => println!("✅ 노트를 만들었습니다: {}", app.display_path(&id)),
+                    Err(e) => println!("❌ {}", e),
+                }
             }
             "t" | "tags" => {
                 show_tags(&app);
@@ -77,7 +160,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 match app.add_watched_folder(folder_path.clone()) {
                     Ok(_) => {
                         println!("✅ 폴더가 추가되었습니다: {}", folder_path);
-                        show_notes_list(&app);
+                        show_notes_list(&mut app);
                     }
                     Err(e) => println!("❌ {}", e),
                 }
@@ -91,16 +174,237 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 match app.remove_watched_folder(&folder_path) {
                     Ok(_) => {
                         println!("✅ 폴더가 제거되었습니다: {}", folder_path);
-                        show_notes_list(&app);
+                        show_notes_list(&mut app);
+                    }
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "depth" | "exclude" | "include" => {
+                if parts.len() < 3 {
+                    println!("❌ 사용법: {} <값> <경로>", parts[0]);
+                    continue;
+                }
+                let folder_path = parts[2..].join(" ");
+                let mut rules = app.get_folder_rules(&folder_path);
+                match parts[0] {
+                    "depth" => {
+                        rules.max_depth = match parts[1] {
+                            "none" => None,
+                            n => match n.parse::<usize>() {
+                                Ok(depth) => Some(depth),
+                                Err(_) => {
+                                    println!("❌ 깊이는 숫자 또는 none 이어야 합니다.");
+                                    continue;
+                                }
+                            },
+                        };
+                    }
+                    "exclude" => rules.exclude.push(parts[1].to_string()),
+                    _ => rules.include.push(parts[1].to_string()),
+                }
+                match app.set_folder_rules(&folder_path, rules) {
+                    Ok(_) => {
+                        println!("✅ 폴더 규칙이 변경되었습니다: {}", folder_path);
+                        show_folders(&app);
+                    }
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "clear-rules" => {
+                if parts.len() < 2 {
+                    println!("❌ 사용법: clear-rules <경로>");
+                    continue;
+                }
+                let folder_path = parts[1..].join(" ");
+                match app.set_folder_rules(&folder_path, Default::default()) {
+                    Ok(_) => {
+                        println!("✅ 폴더 규칙이 초기화되었습니다: {}", folder_path);
+                        show_folders(&app);
                     }
                     Err(e) => println!("❌ {}", e),
                 }
             }
+            "tag-add" | "tag-remove" => {
+                if parts.len() < 3 {
+                    println!("❌ 사용법: {} <노트> <태그...>", parts[0]);
+                    continue;
+                }
+                let tags: Vec<String> = parts[2..].iter().map(|t| t.to_string()).collect();
+                let result = note_ref(&app, parts[1]).and_then(|id| {
+                    if parts[0] == "tag-add" {
+                        app.add_tags(&id, &tags).map_err(|e| e.to_string())
+                    } else {
+                        app.remove_tags(&id, &tags).map_err(|e| e.to_string())
+                    }
+                });
+                match result {
+                    Ok(_) => {
+                        println!("✅ 태그가 변경되었습니다: {}", tags.join(", "));
+                        show_notes_list(&mut app);
+                    }
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "tag-rename" => {
+                if parts.len() != 3 {
+                    println!("❌ 사용법: tag-rename <이전 태그> <새 태그>");
+                    continue;
+                }
+                match app.rename_tag(parts[1], parts[2]) {
+                    Ok(count) => {
+                        println!(
+                            "✅ 태그 이름을 바꿨습니다: {} → {} ({} 개 노트)",
+                            parts[1], parts[2], count
+                        );
+                    }
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "tag-merge" => {
+                if parts.len() < 3 {
+                    println!("❌ 사용법: tag-merge <대상 태그> <합칠 태그...>");
+                    continue;
+                }
+                let sources: Vec<String> = parts[2..].iter().map(|t| t.to_string()).collect();
+                match app.merge_tags(&sources, parts[1]) {
+                    Ok(count) => {
+                        println!(
+                            "✅ 태그를 합쳤습니다: {} → {} ({} 개 노트)",
+                            sources.join(", "),
+                            parts[1],
+                            count
+                        );
+                    }
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "move" => {
+                if parts.len() != 3 {
+                    println!("❌ 사용법: move <노트> <@폴더|none>");
+                    continue;
+                }
+                let folder = match parts[2] {
+                    "none" => None,
+                    folder => Some(folder),
+                };
+                match note_ref(&app, parts[1])
+                    .and_then(|id| app.set_folder(&id, folder).map_err(|e| e.to_string()))
+                {
+                    Ok(_) => {
+                        println!("✅ 폴더가 변경되었습니다.");
+                        show_notes_list(&mut app);
+                    }
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "shortcuts" => {
+                if parts.len() != 2 {
+                    println!("❌ 사용법: shortcuts <노트>");
+                    continue;
+                }
+                match note_ref(&app, parts[1]) {
+                    Ok(id) => show_shortcuts(&app, &id),
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "shortcut-add" | "shortcut-edit" => {
+                if parts.len() < 4 {
+                    println!("❌ 사용법: {} <노트> <별칭> <대상> [설명]", parts[0]);
+                    continue;
+                }
+                let description = (parts.len() > 4).then(|| parts[4..].join(" "));
+                let result = note_ref(&app, parts[1]).and_then(|id| {
+                    let target = LinkTarget::parse(parts[3]).map_err(|e| e.to_string())?;
+                    let saved = if parts[0] == "shortcut-add" {
+                        app.add_shortcut(&id, parts[2], target, description)
+                    } else {
+                        app.edit_shortcut(&id, parts[2], Some(target), description)
+                    };
+                    saved.map(|_| id).map_err(|e| e.to_string())
+                });
+                match result {
+                    Ok(id) => {
+                        println!("✅ 단축어가 저장되었습니다: {}", parts[2]);
+                        show_shortcuts(&app, &id);
+                    }
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "shortcut-remove" => {
+                if parts.len() != 3 {
+                    println!("❌ 사용법: shortcut-remove <노트> <별칭>");
+                    continue;
+                }
+                match note_ref(&app, parts[1]).and_then(|id| {
+                    app.remove_shortcut(&id, parts[2])
+                        .map_err(|e| e.to_string())
+                }) {
+                    Ok(_) => println!("✅ 단축어가 제거되었습니다: {}", parts[2]),
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "links" | "backlinks" => {
+                if parts.len() != 2 {
+                    println!("❌ 사용법: {} <노트>", parts[0]);
+                    continue;
+                }
+                match note_ref(&app, parts[1]) {
+                    Ok(id) if parts[0] == "links" => show_links(&app, &id),
+                    Ok(id) => show_backlinks(&app, &id),
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "e" | "edit" => {
+                if parts.len() < 2 {
+                    println!("❌ 사용법: edit <노트>");
+                    continue;
+                }
+                let id = match note_ref(&app, &parts[1..].join(" ")) {
+                    Ok(id) => id,
+                    Err(e) => {
+                        println!("❌ {}", e);
+                        continue;
+                    }
+                };
+                let path = app.display_path(&id);
+                let file_path = app
+                    .index
+                    .get(&id)
+                    .map(|entry| PathBuf::from(&entry.file_path))
+                    .unwrap_or_default();
+                if let Err(e) = open_in_editor(&file_path) {
+                    println!("❌ {}", e);
+                    continue;
+                }
+                match app.reload_note(&id) {
+                    Ok(changes) if changes.is_empty() => println!("✅ 변경 없음: {}", path),
+                    Ok(changes) => print_changes(&changes),
+                    Err(e) => println!("❌ {}", e),
+                }
+            }
+            "check" => {
+                print_check_report(&app.check());
+            }
+            "fsck" => {
+                let mut report = app.fsck();
+                if parts.get(1) == Some(&"--repair") {
+                    match app.repair(&report.issues) {
+                        Ok(repaired) => report.repaired = repaired,
+                        Err(e) => println!("❌ {}", e),
+                    }
+                }
+                print_fsck_report(&report);
+            }
             "r" | "refresh" => {
                 println!("🔄 노트 목록 새로고침 중...");
-                app = NoteApp::new(notes_dir.clone())?;
-                println!("✅ 새로고침 완료!");
-                show_notes_list(&app);
+                // 바뀐 파일만 다시 읽음
+                match app.load_notes() {
+                    Ok(_) => {
+                        println!("✅ 새로고침 완료!");
+                        show_notes_list(&mut app);
+                    }
+                    Err(e) => println!("❌ {}", e),
+                }
             }
             "q" | "quit" => {
                 println!("👋 안녕히 가세요!");
@@ -115,7 +419,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn show_notes_list(app: &NoteApp) {
+// 노트 목록 출력 (#N으로 다시 가리킬 수 있도록 순서를 기억)
+fn show_notes_list(app: &mut NoteApp) {
+    let ids: Vec<Uuid> = app.list_notes().iter().map(|(id, _)| **id).collect();
+    app.remember_results(ids);
+    let app = &*app;
     let notes = app.list_notes();
 
     if notes.is_empty() {
@@ -150,6 +458,10 @@ fn show_notes_list(app: &NoteApp) {
 
         // 정보 조합
         let mut info_parts = Vec::new();
+        // 같은 파일명이 여러 폴더에 있으면 경로로 구분
+        if app.has_duplicate_filename(id) {
+            info_parts.push(format!("📄{}", app.display_path(id)));
+        }
         if !folder_str.is_empty() {
             info_parts.push(folder_str);
         }
@@ -166,8 +478,9 @@ fn show_notes_list(app: &NoteApp) {
         };
 
         println!(
-            "{:3}. {} {}{}",
-            idx + 1,
+            "{:>4} {} {} {}{}",
+            format!("#{}", idx + 1),
+            reference::short_id(id),
             note.title,
             note.updated_at.format("%Y-%m-%d"),
             info_str
@@ -176,60 +489,459 @@ fn show_notes_list(app: &NoteApp) {
     println!("{:-<60}", "");
 }
 
-fn show_note_detail(app: &NoteApp, number_str: &str) {
-    let index = match number_str.parse::<usize>() {
-        Ok(n) if n > 0 => n - 1,
-        _ => {
-            println!("❌ 올바른 번호를 입력하세요.");
-            return;
+fn show_note_detail(app: &NoteApp, reference: &str, raw: bool) {
+    match note_ref(app, reference) {
+        Ok(id) => {
+            if let Some(note) = app.get_note(&id) {
+                print_note_detail(app, &id, note, raw);
+            }
         }
-    };
+        Err(e) => println!("❌ {}", e),
+    }
+}
 
-    let notes = app.list_notes();
-    if let Some((id, note)) = notes.get(index) {
-        println!("\n📝 노트 상세");
-        println!("{:-<60}", "");
-        println!("제목: {}", note.title);
-        println!("파일: {}", note.filename);
-        println!("생성: {}", note.created_at.format("%Y-%m-%d %H:%M"));
-        println!("수정: {}", note.updated_at.format("%Y-%m-%d %H:%M"));
-
-        if let Some(folder_name) = note.get_folder_name() {
-            println!("📁 폴더: {}", folder_name);
+// 노트 참조 (#N, UUID 앞부분, 파일명, 제목)를 노트 UUID로 변환
+// 숫자만 입력하면 마지막으로 표시한 목록/검색 결과의 번호로 보고, 범위 밖이면 이름으로 찾음
+fn note_ref(app: &NoteApp, reference: &str) -> Result<Uuid, String> {
+    app.resolve_note(reference).map_err(|e| e.to_string())
+}
+
+// 노트 상세: 본문은 마크다운을 렌더링해서 표시하고, 길면 $PAGER로 넘김
+// raw면 본문을 원문 그대로 출력 (색/페이저 없이)
+fn print_note_detail(app: &NoteApp, id: &Uuid, note: &Note, raw: bool) {
+    let mut out = String::new();
+    out.push_str("\n📝 노트 상세\n");
+    let _ = writeln!(out, "{:-<60}", "");
+    let _ = writeln!(out, "제목: {}", note.title);
+    let _ = writeln!(out, "파일: {}", app.display_path(id));
+    let _ = writeln!(out, "생성: {}", note.created_at.format("%Y-%m-%d %H:%M"));
+    let _ = writeln!(out, "수정: {}", note.updated_at.format("%Y-%m-%d %H:%M"));
+
+    if let Some(folder_name) = note.get_folder_name() {
+        let _ = writeln!(out, "📁 폴더: {}", folder_name);
+    }
+
+    let tags = note.get_regular_tags();
+    if !tags.is_empty() {
+        let _ = writeln!(out, "🏷️  태그: {}", tags.join(", "));
+    }
+
+    // Shortcuts 표시
+    let shortcuts = app.list_shortcuts(id);
+    if !shortcuts.is_empty() {
+        out.push_str("🔗 단축어:\n");
+        for line in shortcut_lines(app, &shortcuts) {
+            let _ = writeln!(out, "{}", line);
         }
+    }
 
-        let tags = note.get_regular_tags();
-        if !tags.is_empty() {
-            println!("🏷️  태그: {}", tags.join(", "));
+    let _ = writeln!(out, "{:-<60}", "");
+
+    // 본문의 {단축어}를 펼쳐서 표시
+    let expanded = app.expand_content(id);
+    let content = expanded
+        .as_ref()
+        .map_or(note.content(), |expanded| expanded.content.as_str());
+    if raw {
+        let _ = writeln!(out, "\n{}", content);
+    } else {
+        let _ = write!(
+            out,
+            "\n{}",
+            markdown::to_ansi(&markdown::render(content), pager::use_color())
+        );
+    }
+    for unknown in expanded.iter().flat_map(|expanded| &expanded.unknown) {
+        let _ = writeln!(
+            out,
+            "⚠️  알 수 없는 단축어: {{{}}} ({}번째 줄)",
+            unknown.alias, unknown.line_number
+        );
+    }
+
+    if raw {
+        print!("{}", out);
+    } else {
+        pager::page(&out);
+    }
+}
+
+fn shortcut_lines(app: &NoteApp, shortcuts: &[&Shortcut]) -> Vec<String> {
+    shortcuts
+        .iter()
+        .map(|shortcut| {
+            let target_str = match &shortcut.target {
+                LinkTarget::Url { url } => url.clone(),
+                LinkTarget::File { path } => path.display().to_string(),
+                LinkTarget::Note { id } => app
+                    .get_note(id)
+                    .map(|n| n.title.clone())
+                    .unwrap_or_else(|| format!("(노트 {})", id)),
+            };
+            match &shortcut.description {
+                Some(description) => {
+                    format!("   {} → {} ({})", shortcut.alias, target_str, description)
+                }
+                None => format!("   {} → {}", shortcut.alias, target_str),
+            }
+        })
+        .collect()
+}
+
+fn show_shortcuts(app: &NoteApp, id: &Uuid) {
+    let shortcuts = app.list_shortcuts(id);
+    if shortcuts.is_empty() {
+        println!("\n📭 단축어가 없습니다.");
+        return;
+    }
+
+    println!("\n🔗 단축어 ({} 개)", shortcuts.len());
+    println!("{:-<60}", "");
+    for line in shortcut_lines(app, &shortcuts) {
+        println!("{}", line);
+    }
+    println!("{:-<60}", "");
+}
+
+fn show_links(app: &NoteApp, id: &Uuid) {
+    let links = app.outgoing_links(id);
+    if links.is_empty() {
+        println!("\n📭 링크가 없습니다.");
+        return;
+    }
+
+    println!("\n🔗 나가는 링크 ({} 개)", links.len());
+    println!("{:-<60}", "");
+    for link in &links {
+        let target = match link.resolved.and_then(|target| app.get_note(&target)) {
+            Some(note) => note.title.clone(),
+            None => "❓ 찾을 수 없음".to_string(),
+        };
+        println!(
+            "{:4}: {} → {}",
+            link.raw.line_number, link.raw.target, target
+        );
+    }
+    println!("{:-<60}", "");
+}
+
+fn show_backlinks(app: &NoteApp, id: &Uuid) {
+    let backlinks = app.backlinks(id);
+    if backlinks.is_empty() {
+        println!("\n📭 이 노트를 참조하는 노트가 없습니다.");
+        return;
+    }
+
+    println!("\n↩️  백링크 ({} 개)", backlinks.len());
+    println!("{:-<60}", "");
+    for backlink in &backlinks {
+        let title = app
+            .get_note(&backlink.source)
+            .map(|n| n.title.as_str())
+            .unwrap_or_default();
+        match &backlink.via {
+            BacklinkSource::Link { line_number, .. } => {
+                println!("   {} ({}번째 줄)", title, line_number)
+            }
+            BacklinkSource::Shortcut { alias } => println!("   {} (단축어 {{{}}})", title, alias),
         }
+    }
+    println!("{:-<60}", "");
+}
 
-        // Shortcuts 표시
-        if let Some(shortcuts) = app.shortcuts.get_shortcuts(id) {
-            if !shortcuts.is_empty() {
-                println!("🔗 단축어:");
-                for (alias, shortcut) in shortcuts {
-                    let target_str = match &shortcut.target {
-                        crate::note::LinkTarget::Url { url } => url.clone(),
-                        crate::note::LinkTarget::File { path } => path.display().to_string(),
-                        crate::note::LinkTarget::Note { id } => app
-                            .get_note(id)
-                            .map(|n| n.title.clone())
-                            .unwrap_or_else(|| format!("(노트 {})", id)),
-                    };
-                    println!("   {} → {}", alias, target_str);
+fn print_check_report(report: &CheckReport) {
+    if report.notes.is_empty() {
+        println!("\n✅ 문제가 없습니다.");
+        return;
+    }
+
+    println!("\n🩺 검사 결과 ({} 개 노트)", report.notes.len());
+    println!("{:-<60}", "");
+    for note in &report.notes {
+        println!("📄 {} ({})", note.title, note.file_path);
+        for issue in &note.issues {
+            match issue {
+                Issue::UnresolvedLink {
+                    line_number,
+                    target,
+                    ..
+                } => println!("   {:4}: ❌ 연결되지 않은 링크: {}", line_number, target),
+                Issue::MissingFile { alias, path } => {
+                    println!(
+                        "         ❌ 단축어 {{{}}}: 파일 없음 {}",
+                        alias,
+                        path.display()
+                    )
                 }
+                Issue::MissingNote { alias, id } => {
+                    println!("         ❌ 단축어 {{{}}}: 노트 없음 {}", alias, id)
+                }
+                Issue::Orphan => println!("         ⚠️  고립된 노트 (링크 없음)"),
             }
         }
+    }
+    println!("{:-<60}", "");
+    println!(
+        "링크 {} 개, 단축어 {} 개, 고립된 노트 {} 개",
+        report.count(|i| matches!(i, Issue::UnresolvedLink { .. })),
+        report.count(|i| matches!(i, Issue::MissingFile { .. } | Issue::MissingNote { .. })),
+        report.count(|i| matches!(i, Issue::Orphan)),
+    );
+}
+
+fn print_fsck_report(report: &FsckReport) {
+    if report.is_clean() {
+        println!("\n✅ 인덱스, 단축어, frontmatter가 일치합니다.");
+        return;
+    }
 
-        println!("{:-<60}", "");
-        println!("\n{}", note.content);
+    println!("\n🔍 fsck 결과 ({} 개 문제)", report.issues.len());
+    println!("{:-<60}", "");
+    for (category, heading) in [
+        (FsckCategory::File, "🗑️  사라진 파일"),
+        (FsckCategory::Id, "🆔 UUID 문제"),
+        (FsckCategory::Title, "📝 오래된 제목"),
+        (FsckCategory::Shortcut, "🔗 주인 없는 단축어"),
+    ] {
+        let issues: Vec<_> = report.in_category(category).collect();
+        if issues.is_empty() {
+            continue;
+        }
+        println!("{} ({} 개)", heading, issues.len());
+        for issue in issues {
+            match issue {
+                FsckIssue::MissingFile { path, .. } => println!("   {}", path),
+                FsckIssue::MissingId { path, .. } => {
+                    println!("   {}: frontmatter에 id 없음", path)
+                }
+                FsckIssue::IdMismatch {
+                    id, file_id, path, ..
+                } => println!("   {}: 파일 {} ≠ 인덱스 {}", path, file_id, id),
+                FsckIssue::DuplicateId { id, path, kept, .. } => {
+                    println!("   {}: {}와 같은 UUID {}", path, kept, id)
+                }
+                FsckIssue::StaleTitle {
+                    path,
+                    indexed,
+                    actual,
+                    ..
+                } => println!("   {}: \"{}\" → \"{}\"", path, indexed, actual),
+                FsckIssue::OrphanShortcuts { id, aliases } => {
+                    println!("   {}: {{{}}}", id, aliases.join("}, {"))
+                }
+            }
+        }
+    }
+    println!("{:-<60}", "");
+    if report.repaired > 0 {
+        println!("🔧 {} 개 문제를 고쳤습니다.", report.repaired);
     } else {
-        println!("❌ 해당 번호의 노트가 없습니다.");
+        println!("💡 fsck --repair로 고칠 수 있습니다.");
+    }
+}
+
+fn print_html_export(report: &HtmlExport) {
+    println!(
+        "\n✅ HTML로 내보냈습니다: {} (노트 {} 개, 폴더 {} 개, 태그 {} 개)",
+        report.out_dir.display(),
+        report.notes,
+        report.folders,
+        report.tags
+    );
+    if report.unresolved_links > 0 {
+        println!(
+            "⚠️  내보내지 않았거나 없는 노트를 가리키는 링크 {} 개는 링크 없이 남겼습니다.",
+            report.unresolved_links
+        );
     }
+    println!("   {}", report.out_dir.join("index.html").display());
 }
 
-fn search_notes(app: &NoteApp, query: &str) {
-    let results = app.search_notes(query);
+fn print_meta_import(import: &MetaImport, apply: bool) {
+    let title = if apply { "적용" } else { "미리보기" };
+    println!(
+        "\n📋 메타데이터 가져오기 ({}): 바뀌는 노트 {} 개, 같음 {} 개, 못 찾음 {} 개",
+        title,
+        import.changes.len(),
+        import.unchanged,
+        import.unmatched.len()
+    );
+    println!("{:-<60}", "");
+
+    let time = |time: &DateTime<Utc>| time.format("%Y-%m-%d %H:%M:%S").to_string();
+    let folder = |folder: &Option<String>| folder.clone().unwrap_or_else(|| "(없음)".to_string());
+    let tags = |tags: &[String]| {
+        if tags.is_empty() {
+            "(없음)".to_string()
+        } else {
+            tags.join(", ")
+        }
+    };
+    for change in &import.changes {
+        println!("{} ({})", change.path, reference::short_id(&change.id));
+        for field in &change.changes {
+            match field {
+                FieldChange::Tags { from, to } => {
+                    println!("   태그: {} → {}", tags(from), tags(to))
+                }
+                FieldChange::Folder { from, to } => {
+                    println!("   폴더: {} → {}", folder(from), folder(to))
+                }
+                FieldChange::Created { from, to } => {
+                    println!("   생성: {} → {}", time(from), time(to))
+                }
+                FieldChange::Updated { from, to } => {
+                    println!("   수정: {} → {}", time(from), time(to))
+                }
+            }
+        }
+    }
+    for record in &import.unmatched {
+        let key = record
+            .uuid
+            .map(|id| id.to_string())
+            .or_else(|| record.path.clone())
+            .unwrap_or_default();
+        println!("⚠️  {}번째 줄: 노트를 찾을 수 없음 ({})", record.line, key);
+    }
+    println!("{:-<60}", "");
+
+    if apply {
+        println!("✅ {} 개 노트에 적용했습니다.", import.applied);
+    } else if !import.changes.is_empty() {
+        println!("💡 적용하려면 --apply 옵션을 주세요.");
+    }
+}
+
+fn print_changes(changes: &[NoteChange]) {
+    let time = chrono::Local::now().format("%H:%M:%S");
+    for change in changes {
+        match change {
+            NoteChange::Added { path, .. } => println!("[{}] 📄 추가: {}", time, path),
+            NoteChange::Updated { path, .. } => println!("[{}] ✏️  수정: {}", time, path),
+            NoteChange::Moved { from, to, .. } => println!("[{}] 🚚 이동: {} → {}", time, from, to),
+            NoteChange::Removed { path, .. } => println!("[{}] 🗑️  삭제: {}", time, path),
+        }
+    }
+}
+
+// 앱 이벤트를 사람이 읽는 진행 메시지로 출력 (stdout의 JSON과 섞이지 않도록 stderr)
+struct ConsoleReporter;
+
+impl Reporter for ConsoleReporter {
+    fn report(&self, event: NoteEvent) {
+        match event {
+            NoteEvent::NoteDiscovered { path, .. } => {
+                eprintln!("📄 새 노트 발견: {}", file_name(&path))
+            }
+            NoteEvent::NoteDeleted { path, .. } => {
+                eprintln!("🗑️  삭제된 노트 감지: {}", file_name(&path))
+            }
+            NoteEvent::NoteMoved { from, to, .. } => {
+                eprintln!("🚚 이동된 노트 감지: {} → {}", from, to)
+            }
+            NoteEvent::UuidInjected { id, path } => {
+                eprintln!("✏️  UUID 추가됨: {} ({})", file_name(&path), id)
+            }
+            NoteEvent::UuidInjectFailed { path, error } => {
+                eprintln!("⚠️  UUID 주입 실패 {}: {}", file_name(&path), error)
+            }
+            NoteEvent::DuplicateUuid { id, path } => {
+                eprintln!("⚠️  중복 UUID {}: {} (경로로 구분합니다)", id, path)
+            }
+            NoteEvent::ParseFailed { path, error } => {
+                eprintln!("노트 파싱 실패 {}: {}", file_name(&path), error)
+            }
+            NoteEvent::FolderMissing { path } => {
+                eprintln!("⚠️  폴더가 존재하지 않습니다: {}", path)
+            }
+            NoteEvent::IndexCleaned { removed } => {
+                eprintln!("✅ 인덱스 정리 완료: {}개 항목 제거", removed)
+            }
+            NoteEvent::Migrated(migration) => eprintln!(
+                "⬆️  {} 파일을 버전 {}에서 {}로 옮겼습니다 (백업: {})",
+                migration.path.display(),
+                migration.from,
+                migration.to,
+                migration.backup.display()
+            ),
+            NoteEvent::TempFileRecovered {
+                path,
+                restored: true,
+            } => {
+                eprintln!("♻️  임시 파일에서 복구했습니다: {}", path)
+            }
+            NoteEvent::TempFileRecovered { path, .. } => {
+                eprintln!("🧹 남은 임시 파일을 삭제했습니다: {}", path)
+            }
+            NoteEvent::SearchIndexRebuilt { error } => {
+                eprintln!("⚠️  {} - 검색 인덱스를 다시 만듭니다", error)
+            }
+            NoteEvent::SaveFailed { error } => eprintln!("⚠️  {}", error),
+            NoteEvent::OpenedReadOnly { path } => {
+                eprintln!(
+                    "🔒 다른 md_filer가 사용 중이라 읽기 전용으로 엽니다: {}",
+                    path
+                )
+            }
+            _ => {}
+        }
+    }
+}
+
+// $VISUAL, $EDITOR 순서로 편집기를 찾아 파일을 열고 끝날 때까지 기다림
+// "code --wait"처럼 인자가 있는 값도 허용
+fn open_in_editor(path: &Path) -> error::Result<()> {
+    let editor = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|key| std::env::var(key).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| if cfg!(windows) { "notepad" } else { "vi" }.to_string());
+
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .map_err(|source| Error::EditorLaunch {
+            program: program.to_string(),
+            source,
+        })?;
+
+    if !status.success() {
+        return Err(Error::EditorFailed(status));
+    }
+    Ok(())
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn search_notes(app: &mut NoteApp, query: &str) {
+    let results = match app.search(query) {
+        Ok(results) => results,
+        Err(e) => {
+            println!("❌ {}", e);
+            return;
+        }
+    };
+    print_search_results(app, query, &results);
+}
+
+// 검색 결과 출력 (#N으로 다시 가리킬 수 있도록 순서를 기억)
+fn print_search_results(app: &mut NoteApp, query: &str, results: &[SearchHit]) {
+    let ids: Vec<Uuid> = results
+        .iter()
+        .map(|hit| hit.id)
+        .filter(|id| app.get_note(id).is_some())
+        .collect();
+    app.remember_results(ids);
+    let app = &*app;
 
     if results.is_empty() {
         println!("🔍 '{}' 검색 결과가 없습니다.", query);
@@ -239,13 +951,32 @@ fn search_notes(app: &NoteApp, query: &str) {
     println!("\n🔍 '{}' 검색 결과 ({} 개)", query, results.len());
     println!("{:-<60}", "");
 
-    for (_id, note) in results {
-        println!("📝 {} - {}", note.title, note.updated_at.format("%Y-%m-%d"));
+    let notes = results
+        .iter()
+        .filter_map(|hit| Some((hit, app.get_note(&hit.id)?)));
+    for (number, (hit, note)) in notes.enumerate() {
+        // 전문 검색어가 없는 쿼리 (tag:rust 등)는 점수를 표시하지 않음
+        let score_str = if hit.score > 0.0 {
+            format!(" (점수 {:.2})", hit.score)
+        } else {
+            String::new()
+        };
+        println!(
+            "{:>4} 📝 {} - {}{}",
+            format!("#{}", number + 1),
+            note.title,
+            note.updated_at.format("%Y-%m-%d"),
+            score_str
+        );
 
-        // 내용 미리보기 (첫 50자)
-        let preview: String = note.content.chars().take(50).collect();
-        if !preview.is_empty() {
-            println!("   {}", preview.replace('\n', " "));
+        // 매칭된 줄 표시 (본문에 없으면 내용 미리보기 첫 50자)
+        if let Some(snippet) = &hit.snippet {
+            println!("   {}: {}", snippet.line_number, snippet.text);
+        } else {
+            let preview: String = note.content().chars().take(50).collect();
+            if !preview.is_empty() {
+                println!("   {}", preview.replace('\n', " "));
+            }
         }
     }
 }
@@ -294,22 +1025,21 @@ fn show_folders(app: &NoteApp) {
     }
 
     for (idx, folder_path) in watched_folders.iter().enumerate() {
-        // 해당 폴더의 노트 개수 세기 - index에서 직접 세기
-        let count = app
-            .index
-            .mappings
-            .values()
-            .filter(|entry| {
-                if !entry.file_path.is_empty() {
-                    entry.file_path.starts_with(folder_path)
-                } else {
-                    // 구버전 호환: file_path가 없으면 기본 notes 폴더로 간주
-                    folder_path.contains("notes")
-                }
-            })
-            .count();
+        let count = app.count_notes_in_folder(folder_path);
 
         println!("{}. {} ({} 개 노트)", idx + 1, folder_path, count);
+
+        // 스캔 규칙 표시
+        let rules = app.get_folder_rules(folder_path);
+        if let Some(depth) = rules.max_depth {
+            println!("   ↳ 깊이: {}", depth);
+        }
+        if !rules.exclude.is_empty() {
+            println!("   ↳ 제외: {}", rules.exclude.join(", "));
+        }
+        if !rules.include.is_empty() {
+            println!("   ↳ 포함: {}", rules.include.join(", "));
+        }
     }
     println!("{:-<60}", "");
 }
@@ -0,0 +1,633 @@
+use pulldown_cmark::{
+    Alignment, CodeBlockKind, Event, HeadingLevel, LinkType, Options, Parser, Tag, TagEnd,
+};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use std::fmt::Write;
+use std::sync::LazyLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{FontStyle, Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+// 코드 하이라이트용 문법/테마 (처음 쓸 때 한 번만 읽음)
+static SYNTAXES: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+static THEME: LazyLock<Theme> = LazyLock::new(|| {
+    ThemeSet::load_defaults()
+        .themes
+        .remove("base16-ocean.dark")
+        .unwrap_or_default()
+});
+
+// 마크다운 본문을 터미널 미리보기용 스타일 텍스트로 변환
+pub fn render(markdown: &str) -> Text<'static> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_WIKILINKS;
+
+    let mut renderer = Renderer::default();
+    for event in Parser::new_ext(markdown, options) {
+        renderer.event(event);
+    }
+    renderer.finish()
+}
+
+// syntect 색을 터미널 스타일로 (배경색은 터미널 것을 그대로 씀)
+fn highlight_style(style: syntect::highlighting::Style) -> Style {
+    let fg = style.foreground;
+    let mut result = Style::new().fg(Color::Rgb(fg.r, fg.g, fg.b));
+    if style.font_style.contains(FontStyle::BOLD) {
+        result = result.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        result = result.add_modifier(Modifier::ITALIC);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        result = result.add_modifier(Modifier::UNDERLINED);
+    }
+    result
+}
+
+// 스타일 텍스트를 ANSI 이스케이프가 들어간 문자열로 (color가 false면 글자만)
+pub fn to_ansi(text: &Text, color: bool) -> String {
+    let mut out = String::new();
+    for line in &text.lines {
+        for span in &line.spans {
+            let codes = if color {
+                sgr_codes(line.style.patch(span.style))
+            } else {
+                Vec::new()
+            };
+            if codes.is_empty() {
+                out.push_str(&span.content);
+            } else {
+                let _ = write!(out, "\x1b[{}m{}\x1b[0m", codes.join(";"), span.content);
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn sgr_codes(style: Style) -> Vec<String> {
+    let modifiers = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    let mut codes: Vec<String> = modifiers
+        .into_iter()
+        .filter(|(modifier, _)| style.add_modifier.contains(*modifier))
+        .map(|(_, code)| code.to_string())
+        .collect();
+    if let Some(fg) = style.fg.and_then(color_code) {
+        codes.push(fg);
+    }
+    codes
+}
+
+fn color_code(color: Color) -> Option<String> {
+    let code = match color {
+        Color::Reset => return None,
+        Color::Black => 30,
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Blue => 34,
+        Color::Magenta => 35,
+        Color::Cyan => 36,
+        Color::Gray => 37,
+        Color::DarkGray => 90,
+        Color::LightRed => 91,
+        Color::LightGreen => 92,
+        Color::LightYellow => 93,
+        Color::LightBlue => 94,
+        Color::LightMagenta => 95,
+        Color::LightCyan => 96,
+        Color::White => 97,
+        Color::Indexed(index) => return Some(format!("38;5;{}", index)),
+        Color::Rgb(r, g, b) => return Some(format!("38;2;{};{};{}", r, g, b)),
+    };
+    Some(code.to_string())
+}
+
+fn heading_style(level: HeadingLevel) -> Style {
+    let style = Style::new().add_modifier(Modifier::BOLD);
+    match level {
+        HeadingLevel::H1 => style.fg(Color::Magenta).add_modifier(Modifier::UNDERLINED),
+        HeadingLevel::H2 => style.fg(Color::Cyan),
+        _ => style.fg(Color::Blue),
+    }
+}
+
+const DIM: Style = Style::new().fg(Color::DarkGray);
+const CODE: Style = Style::new().fg(Color::Green);
+const INLINE_CODE: Style = Style::new().fg(Color::Yellow);
+const LINK: Style = Style::new()
+    .fg(Color::LightBlue)
+    .add_modifier(Modifier::UNDERLINED);
+
+#[derive(Default)]
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    spans: Vec<Span<'static>>,
+    // 중첩된 강조/링크 스타일
+    styles: Vec<Style>,
+    // 목록 중첩 (순서 있는 목록이면 다음 번호)
+    lists: Vec<Option<u64>>,
+    quote_depth: usize,
+    in_code_block: bool,
+    // 코드 블록 언어와 내용 (블록이 끝나면 한꺼번에 하이라이트)
+    code_lang: String,
+    code: String,
+    // 표는 열 너비를 맞추기 위해 끝날 때 한꺼번에 출력 (첫 행은 머리글)
+    table_align: Vec<Alignment>,
+    table_rows: Vec<Vec<Vec<Span<'static>>>>,
+    table_row: Vec<Vec<Span<'static>>>,
+    // 닫힐 때 뒤에 붙일 링크 주소 (위키 링크는 빈 문자열)
+    link_urls: Vec<String>,
+}
+
+impl Renderer {
+    fn event(&mut self, event: Event) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text(&text),
+            Event::Code(code) => self.spans.push(Span::styled(code.to_string(), INLINE_CODE)),
+            Event::Html(html) | Event::InlineHtml(html) => self.push(html.trim_end(), DIM),
+            Event::InlineMath(math) | Event::DisplayMath(math) => {
+                self.spans.push(Span::styled(math.to_string(), INLINE_CODE))
+            }
+            Event::FootnoteReference(label) => self.push(&format!("[^{}]", label), DIM),
+            Event::SoftBreak => self.push(" ", self.style()),
+            Event::HardBreak => self.flush(false),
+            Event::Rule => {
+                self.flush(false);
+                self.lines.push(Line::styled("─".repeat(40), DIM));
+                self.blank();
+            }
+            Event::TaskListMarker(checked) => {
+                self.push(if checked { "[x] " } else { "[ ] " }, DIM);
+            }
+        }
+    }
+
+    fn start(&mut self, tag: Tag) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush(false);
+                self.push(&format!("{} ", "#".repeat(level as usize)), DIM);
+                self.styles.push(heading_style(level));
+            }
+            Tag::BlockQuote(_) => {
+                self.flush(false);
+                self.quote_depth += 1;
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush(false);
+                self.in_code_block = true;
+                self.code_lang = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+            }
+            Tag::List(start) => {
+                self.flush(false);
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush(false);
+                let indent = "  ".repeat(self.lists.len().saturating_sub(1));
+                let marker = match self.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}{}. ", indent, *number - 1)
+                    }
+                    _ => format!("{}• ", indent),
+                };
+                self.push(&marker, DIM);
+            }
+            Tag::Emphasis => self
+                .styles
+                .push(self.style().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.styles.push(self.style().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => self
+                .styles
+                .push(self.style().add_modifier(Modifier::CROSSED_OUT)),
+            Tag::Link {
+                link_type,
+                dest_url,
+                ..
+            } => {
+                self.styles.push(self.style().patch(LINK));
+                let url = match link_type {
+                    LinkType::WikiLink { .. } | LinkType::Autolink | LinkType::Email => {
+                        String::new()
+                    }
+                    _ => dest_url.to_string(),
+                };
+                self.link_urls.push(url);
+            }
+            Tag::Image { .. } => self.push("🖼 ", DIM),
+            Tag::Table(alignments) => {
+                self.flush(false);
+                self.table_align = alignments;
+            }
+            Tag::TableHead => self.styles.push(self.style().add_modifier(Modifier::BOLD)),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => {
+                self.flush(false);
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.flush(false);
+                self.blank();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush(false);
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                self.blank();
+            }
+            TagEnd::CodeBlock => {
+                self.code_block();
+                self.in_code_block = false;
+                self.blank();
+            }
+            TagEnd::List(_) => {
+                self.flush(false);
+                self.lists.pop();
+                if self.lists.is_empty() {
+                    self.blank();
+                }
+            }
+            TagEnd::Item => self.flush(false),
+            TagEnd::TableCell => {
+                let cell = std::mem::take(&mut self.spans);
+                self.table_row.push(cell);
+            }
+            TagEnd::TableRow => {
+                let row = std::mem::take(&mut self.table_row);
+                self.table_rows.push(row);
+            }
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link => {
+                self.styles.pop();
+                if let Some(url) = self.link_urls.pop()
+                    && !url.is_empty()
+                {
+                    self.push(&format!(" ({})", url), DIM);
+                }
+            }
+            TagEnd::TableHead => {
+                self.styles.pop();
+                let row = std::mem::take(&mut self.table_row);
+                self.table_rows.push(row);
+            }
+            TagEnd::Table => {
+                self.table();
+                self.blank();
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.in_code_block {
+            self.code.push_str(text);
+        } else {
+            self.push(text, self.style());
+        }
+    }
+
+    // 코드 블록은 줄 단위로 (빈 줄도 유지), 아는 언어면 문법 하이라이트
+    fn code_block(&mut self) {
+        let code = std::mem::take(&mut self.code);
+        let syntax = Some(self.code_lang.as_str())
+            .filter(|lang| !lang.is_empty())
+            .and_then(|lang| SYNTAXES.find_syntax_by_token(lang));
+        let mut highlighter = syntax.map(|syntax| HighlightLines::new(syntax, &THEME));
+
+        for line in LinesWithEndings::from(&code) {
+            let regions = highlighter
+                .as_mut()
+                .and_then(|h| h.highlight_line(line, &SYNTAXES).ok());
+            match regions {
+                Some(regions) => {
+                    for (style, text) in regions {
+                        let text = text.trim_end_matches(['\r', '\n']);
+                        if !text.is_empty() {
+                            self.push(text, highlight_style(style));
+                        }
+                    }
+                }
+                None => {
+                    let text = line.trim_end_matches(['\r', '\n']);
+                    if !text.is_empty() {
+                        self.push(text, CODE);
+                    }
+                }
+            }
+            self.flush(true);
+        }
+    }
+
+    // 열마다 가장 넓은 칸에 맞춰 정렬하고 머리글 아래 구분선
+    fn table(&mut self) {
+        let rows = std::mem::take(&mut self.table_rows);
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut widths = vec![0; columns];
+        for row in &rows {
+            for (column, cell) in row.iter().enumerate() {
+                let width: usize = cell.iter().map(Span::width).sum();
+                widths[column] = widths[column].max(width);
+            }
+        }
+
+        for (index, row) in rows.into_iter().enumerate() {
+            let cells = row.into_iter().map(Some).chain(std::iter::repeat(None));
+            for (column, cell) in cells.take(columns).enumerate() {
+                if column > 0 {
+                    self.push(" │ ", DIM);
+                }
+                let cell = cell.unwrap_or_default();
+                let padding = widths[column] - cell.iter().map(Span::width).sum::<usize>();
+                let left = match self.table_align.get(column) {
+                    Some(Alignment::Right) => padding,
+                    Some(Alignment::Center) => padding / 2,
+                    _ => 0,
+                };
+                self.spans.push(Span::raw(" ".repeat(left)));
+                self.spans.extend(cell);
+                self.spans.push(Span::raw(" ".repeat(padding - left)));
+            }
+            self.flush(false);
+
+            if index == 0 {
+                let rule: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+                self.push(&rule.join("─┼─"), DIM);
+                self.flush(false);
+            }
+        }
+    }
+
+    fn style(&self) -> Style {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    fn push(&mut self, text: &str, style: Style) {
+        self.spans.push(Span::styled(text.to_string(), style));
+    }
+
+    // 모은 조각을 한 줄로 (인용문과 코드 블록은 앞에 표시를 붙임)
+    fn flush(&mut self, keep_empty: bool) {
+        if self.spans.is_empty() && !keep_empty {
+            return;
+        }
+        let mut spans = Vec::new();
+        if self.quote_depth > 0 {
+            spans.push(Span::styled("│ ".repeat(self.quote_depth), DIM));
+        }
+        if self.in_code_block {
+            spans.push(Span::styled("  ", DIM));
+        }
+        spans.append(&mut self.spans);
+        self.lines.push(Line::from(spans));
+    }
+
+    // 블록 사이 빈 줄 (연속으로 넣지 않음)
+    fn blank(&mut self) {
+        if self.lines.last().is_some_and(|line| line.width() > 0) {
+            self.lines.push(Line::default());
+        }
+    }
+
+    fn finish(mut self) -> Text<'static> {
+        self.flush(false);
+        while self.lines.last().is_some_and(|line| line.width() == 0) {
+            self.lines.pop();
+        }
+        Text::from(self.lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 줄마다 스타일 없이 글자만
+    fn plain(markdown: &str) -> Vec<String> {
+        render(markdown)
+            .lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    // 글자가 text인 첫 조각의 스타일
+    fn style_of(text: &Text, content: &str) -> Style {
+        text.lines
+            .iter()
+            .flat_map(|line| &line.spans)
+            .find(|span| span.content == content)
+            .map(|span| span.style)
+            .unwrap_or_else(|| panic!("no span {:?}", content))
+    }
+
+    #[test]
+    fn test_render_headings() {
+        assert_eq!(
+            plain("# Title\nIntro\n## Section\n### Deep"),
+            vec!["# Title", "", "Intro", "", "## Section", "", "### Deep"]
+        );
+
+        let text = render("# Title\n## Section\n#### Deep");
+        assert_eq!(style_of(&text, "# "), DIM);
+        assert_eq!(style_of(&text, "Title"), heading_style(HeadingLevel::H1));
+        assert_eq!(style_of(&text, "Section"), heading_style(HeadingLevel::H2));
+        assert_eq!(style_of(&text, "Deep"), heading_style(HeadingLevel::H4));
+        assert!(
+            style_of(&text, "Title")
+                .add_modifier
+                .contains(Modifier::BOLD)
+        );
+    }
+
+    #[test]
+    fn test_render_lists() {
+        assert_eq!(
+            plain("- one\n- two\n  - nested\n- [x] done\n\n3. third\n4. fourth\n\nAfter"),
+            vec![
+                "• one",
+                "• two",
+                "  • nested",
+                "• [x] done",
+                "",
+                "3. third",
+                "4. fourth",
+                "",
+                "After",
+            ]
+        );
+        // 느슨한 목록도 항목 사이에 빈 줄을 넣지 않음
+        assert_eq!(plain("- a\n\n- b"), vec!["• a", "• b"]);
+    }
+
+    #[test]
+    fn test_render_quotes() {
+        assert_eq!(
+            plain("> quoted\n> more\n>\n> > nested\n\nplain"),
+            vec!["│ quoted more", "", "│ │ nested", "", "plain"]
+        );
+        assert_eq!(style_of(&render("> quoted"), "│ "), DIM);
+    }
+
+    #[test]
+    fn test_render_links() {
+        let text = render("See [site](https://example.com), [[Wiki Page]] and <https://a.io>.");
+        assert_eq!(
+            plain("See [site](https://example.com), [[Wiki Page]] and <https://a.io>."),
+            vec!["See site (https://example.com), Wiki Page and https://a.io."]
+        );
+        assert_eq!(style_of(&text, "site"), LINK);
+        assert_eq!(style_of(&text, "Wiki Page"), LINK);
+        assert_eq!(style_of(&text, " (https://example.com)"), DIM);
+
+        // 강조 안의 링크는 두 스타일을 합침
+        let text = render("**[bold](x.md)**");
+        let style = style_of(&text, "bold");
+        assert!(style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(style.fg, LINK.fg);
+    }
+
+    #[test]
+    fn test_render_table_widths_and_alignment() {
+        // 열 너비는 가장 넓은 칸, 정렬은 왼쪽/가운데/오른쪽, 칸이 모자란 행은 빈 칸으로 채움
+        let markdown = "| a | bb | c |\n|:--|:--:|--:|\n| long | x | 1 |\n| y |\n\nafter";
+        assert_eq!(
+            plain(markdown),
+            vec![
+                "a    │ bb │ c",
+                "─────┼────┼──",
+                "long │ x  │ 1",
+                "y    │    │  ",
+                "",
+                "after",
+            ]
+        );
+
+        // 가운데 정렬은 남는 칸을 반씩 (홀수면 오른쪽에 하나 더)
+        assert_eq!(
+            plain("| h | c | r |\n|---|:-:|--:|\n| x | mid | 7 |\n| x | m | 1000 |"),
+            vec![
+                "h │  c  │    r",
+                "──┼─────┼─────",
+                "x │ mid │    7",
+                "x │  m  │ 1000",
+            ]
+        );
+
+        // 너비는 글자 수가 아니라 화면 폭 (한글은 두 칸)
+        assert_eq!(
+            plain("| 이름 | n |\n|---|---|\n| ab | 1 |"),
+            vec!["이름 │ n", "─────┼──", "ab   │ 1"]
+        );
+
+        // 머리글은 굵게
+        let text = render("| head |\n|---|\n| body |");
+        assert!(
+            style_of(&text, "head")
+                .add_modifier
+                .contains(Modifier::BOLD)
+        );
+        assert!(
+            !style_of(&text, "body")
+                .add_modifier
+                .contains(Modifier::BOLD)
+        );
+    }
+
+    #[test]
+    fn test_render_indented_and_fenced_code() {
+        // 들여쓴 코드는 언어가 없으므로 하이라이트 없이 CODE 스타일, 빈 줄도 유지
+        let text = render("Text\n\n    let x = 1;\n\n    let y = 2;\n\nAfter");
+        assert_eq!(
+            plain("Text\n\n    let x = 1;\n\n    let y = 2;\n\nAfter"),
+            vec![
+                "Text",
+                "",
+                "  let x = 1;",
+                "  ",
+                "  let y = 2;",
+                "",
+                "After"
+            ]
+        );
+        assert_eq!(style_of(&text, "let x = 1;"), CODE);
+
+        // 아는 언어의 펜스 코드는 문법 하이라이트 (RGB 색)
+        let markdown = "```rust\nfn main() {}\n\nlet z = 3;\n```";
+        assert_eq!(
+            plain(markdown),
+            vec!["  fn main() {}", "  ", "  let z = 3;"]
+        );
+        let text = render(markdown);
+        let code_line = &text.lines[0];
+        assert!(code_line.spans.len() > 2);
+        assert!(
+            code_line.spans[1..]
+                .iter()
+                .all(|span| matches!(span.style.fg, Some(Color::Rgb(..))))
+        );
+
+        // 모르는 언어는 CODE 스타일, 마크다운 문법은 해석하지 않음
+        let text = render("```nosuchlang\n**raw** [x](y)\n```");
+        assert_eq!(style_of(&text, "**raw** [x](y)"), CODE);
+        assert_eq!(text.lines.len(), 1);
+    }
+
+    #[test]
+    fn test_to_ansi_escape_sequences() {
+        let text = render("# Hi\n\n**b** `c` [l](u) ~~s~~ *i*");
+        assert_eq!(
+            to_ansi(&text, true),
+            "\x1b[90m# \x1b[0m\x1b[1;4;35mHi\x1b[0m\n\
+             \n\
+             \x1b[1mb\x1b[0m \x1b[33mc\x1b[0m \x1b[4;94ml\x1b[0m\x1b[90m (u)\x1b[0m \
+             \x1b[9ms\x1b[0m \x1b[3mi\x1b[0m\n"
+        );
+        // color가 false면 글자만
+        assert_eq!(to_ansi(&text, false), "# Hi\n\nb c l (u) s i\n");
+
+        // 줄 스타일은 조각 스타일과 합침, 256색/RGB, 스타일 없는 조각은 그대로
+        let line = Line::from(vec![
+            Span::raw("plain "),
+            Span::styled("idx", Style::new().fg(Color::Indexed(208))),
+            Span::styled("rgb", Style::new().fg(Color::Rgb(1, 2, 3))),
+            Span::styled("reset", Style::new().fg(Color::Reset)),
+        ])
+        .style(Style::new().add_modifier(Modifier::DIM | Modifier::REVERSED));
+        assert_eq!(
+            to_ansi(&Text::from(line), true),
+            "\x1b[2;7mplain \x1b[0m\x1b[2;7;38;5;208midx\x1b[0m\
+             \x1b[2;7;38;2;1;2;3mrgb\x1b[0m\x1b[2;7mreset\x1b[0m\n"
+        );
+        assert_eq!(to_ansi(&Text::from("a\nb"), true), "a\nb\n");
+    }
+}
@@ -0,0 +1,384 @@
+use crate::error::{Error, MetaRecordError, Result};
+use crate::index::validate_tag;
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use uuid::Uuid;
+
+// 내보내는 열 (CSV 머리글 순서)
+pub const COLUMNS: [&str; 7] = [
+    "uuid", "path", "title", "tags", "folder", "created", "updated",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetaFormat {
+    Csv,
+    Jsonl,
+}
+
+impl MetaFormat {
+    // 확장자로 형식 추측 (.csv면 CSV, 나머지는 JSON Lines)
+    pub fn from_path(path: &Path) -> Self {
+        let is_csv = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+        if is_csv {
+            MetaFormat::Csv
+        } else {
+            MetaFormat::Jsonl
+        }
+    }
+}
+
+// 노트 하나의 인덱스 메타데이터 (path는 표시용 상대 경로, folder는 @ 없이)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetaRecord {
+    pub uuid: Uuid,
+    pub path: String,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub folder: Option<String>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+}
+
+// CSV에서는 태그를 공백으로 구분 (태그에는 공백을 쓸 수 없음)
+pub fn to_string(records: &[MetaRecord], format: MetaFormat) -> Result<String> {
+    match format {
+        MetaFormat::Jsonl => {
+            let mut output = String::new();
+            for record in records {
+                output.push_str(&serde_json::to_string(record).map_err(Error::Serialize)?);
+                output.push('\n');
+            }
+            Ok(output)
+        }
+        MetaFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            let timestamp =
+                |time: &DateTime<Utc>| time.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+            let rows =
+                std::iter::once(COLUMNS.map(String::from)).chain(records.iter().map(|record| {
+                    [
+                        record.uuid.to_string(),
+                        record.path.clone(),
+                        record.title.clone(),
+                        record.tags.join(" "),
+                        record.folder.clone().unwrap_or_default(),
+                        timestamp(&record.created),
+                        timestamp(&record.updated),
+                    ]
+                }));
+            for row in rows {
+                writer.write_record(&row).map_err(Error::SerializeCsv)?;
+            }
+            let bytes = writer
+                .into_inner()
+                .map_err(|error| Error::SerializeCsv(error.into_error().into()))?;
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        }
+    }
+}
+
+// 가져올 값 한 줄 (파일에 없는 열은 None이라 바꾸지 않음)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetaUpdate {
+    pub line: usize, // 파일 기준 1부터 시작
+    pub uuid: Option<Uuid>,
+    pub path: Option<String>,
+    pub tags: Option<Vec<String>>,
+    // Some(None)이면 폴더 해제
+    pub folder: Option<Option<String>>,
+    pub created: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+}
+
+// CSV 또는 JSON Lines 파싱 (title 열은 확인용이라 읽지 않음)
+pub fn parse(input: &str, format: MetaFormat) -> Result<Vec<MetaUpdate>> {
+    match format {
+        MetaFormat::Csv => parse_csv(input),
+        MetaFormat::Jsonl => parse_jsonl(input),
+    }
+}
+
+fn parse_csv(input: &str) -> Result<Vec<MetaUpdate>> {
+    let malformed = |error: csv::Error| Error::InvalidMetaRecord {
+        line: error
+            .position()
+            .map_or(1, |position| position.line() as usize),
+        reason: MetaRecordError::Malformed(error.to_string()),
+    };
+
+    let mut reader = csv::Reader::from_reader(input.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .map_err(malformed)?
+        .iter()
+        .map(|header| header.trim().to_lowercase())
+        .collect();
+
+    let mut updates = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(malformed)?;
+        let line = record
+            .position()
+            .map_or(0, |position| position.line() as usize);
+        let fields = headers
+            .iter()
+            .map(String::as_str)
+            .zip(record.iter().map(String::from))
+            .collect();
+        updates.push(update_from_fields(line, fields)?);
+    }
+    Ok(updates)
+}
+
+fn parse_jsonl(input: &str) -> Result<Vec<MetaUpdate>> {
+    let mut updates = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let object: serde_json::Map<String, Value> =
+            serde_json::from_str(line).map_err(|error| Error::InvalidMetaRecord {
+                line: idx + 1,
+                reason: MetaRecordError::Malformed(error.to_string()),
+            })?;
+
+        // null은 빈 값, 배열(태그)은 공백으로 이어서 CSV와 같이 처리
+        let fields = object
+            .iter()
+            .map(|(key, value)| {
+                let text = match value {
+                    Value::Null => String::new(),
+                    Value::String(text) => text.clone(),
+                    Value::Array(items) => items
+                        .iter()
+                        .map(|item| item.as_str().map_or_else(|| item.to_string(), String::from))
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                    other => other.to_string(),
+                };
+                (key.as_str(), text)
+            })
+            .collect();
+        updates.push(update_from_fields(idx + 1, fields)?);
+    }
+    Ok(updates)
+}
+
+fn update_from_fields(line: usize, fields: HashMap<&str, String>) -> Result<MetaUpdate> {
+    let invalid = |reason| Error::InvalidMetaRecord { line, reason };
+    let field = |name: &str| fields.get(name).map(|value| value.trim());
+    let non_empty = |name: &str| field(name).filter(|value| !value.is_empty());
+
+    let uuid = non_empty("uuid")
+        .map(|value| {
+            Uuid::parse_str(value)
+                .map_err(|_| invalid(MetaRecordError::InvalidUuid(value.to_string())))
+        })
+        .transpose()?;
+    let path = non_empty("path").map(String::from);
+    if uuid.is_none() && path.is_none() {
+        return Err(invalid(MetaRecordError::MissingKey));
+    }
+
+    let tags = field("tags")
+        .map(|value| {
+            parse_tags(value).map_err(|e| invalid(MetaRecordError::InvalidTag(Box::new(e))))
+        })
+        .transpose()?;
+
+    let folder = field("folder")
+        .map(|value| {
+            let name = value.trim_start_matches('@');
+            if name.is_empty() {
+                return Ok(None);
+            }
+            validate_tag(name)
+                .map(|_| Some(name.to_string()))
+                .map_err(|e| invalid(MetaRecordError::InvalidTag(Box::new(e))))
+        })
+        .transpose()?;
+
+    let timestamp = |name: &str| {
+        non_empty(name)
+            .map(|value| {
+                DateTime::parse_from_rfc3339(value)
+                    .map(|time| time.with_timezone(&Utc))
+                    .map_err(|_| invalid(MetaRecordError::InvalidDate(value.to_string())))
+            })
+            .transpose()
+    };
+
+    Ok(MetaUpdate {
+        line,
+        uuid,
+        path,
+        tags,
+        folder,
+        created: timestamp("created")?,
+        updated: timestamp("updated")?,
+    })
+}
+
+// 공백으로 구분한 태그 (폴더 태그는 folder 열에만, 중복 제거)
+fn parse_tags(value: &str) -> Result<Vec<String>> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in value.split_whitespace() {
+        if tag.starts_with('@') {
+            return Err(Error::InvalidTagChar {
+                tag: tag.to_string(),
+                ch: '@',
+            });
+        }
+        validate_tag(tag)?;
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    Ok(tags)
+}
+
+// 노트 하나에서 바뀌는 값
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum FieldChange {
+    Tags {
+        from: Vec<String>,
+        to: Vec<String>,
+    },
+    Folder {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Created {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+    Updated {
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteMetaChange {
+    pub id: Uuid,
+    pub path: String,
+    pub line: usize,
+    pub changes: Vec<FieldChange>,
+}
+
+// UUID로도 경로로도 노트를 찾지 못한 줄
+#[derive(Debug, Clone, Serialize)]
+pub struct UnmatchedRecord {
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+// 가져오기 결과 (applied가 0이면 미리보기)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MetaImport {
+    pub changes: Vec<NoteMetaChange>,
+    pub unchanged: usize,
+    pub unmatched: Vec<UnmatchedRecord>,
+    pub applied: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record() -> MetaRecord {
+        MetaRecord {
+            uuid: Uuid::new_v4(),
+            path: "wiki/rust, notes.md".to_string(),
+            title: "Rust \"notes\"".to_string(),
+            tags: vec!["rust".to_string(), "개발".to_string()],
+            folder: Some("work".to_string()),
+            created: Utc.with_ymd_and_hms(2024, 1, 2, 3, 4, 5).unwrap(),
+            updated: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_csv_and_jsonl() {
+        let record = record();
+        for format in [MetaFormat::Csv, MetaFormat::Jsonl] {
+            let text = to_string(std::slice::from_ref(&record), format).unwrap();
+            let updates = parse(&text, format).unwrap();
+            // CSV는 머리글 다음 줄
+            let line = if format == MetaFormat::Csv { 2 } else { 1 };
+            assert_eq!(
+                updates,
+                vec![MetaUpdate {
+                    line,
+                    uuid: Some(record.uuid),
+                    path: Some(record.path.clone()),
+                    tags: Some(record.tags.clone()),
+                    folder: Some(record.folder.clone()),
+                    created: Some(record.created),
+                    updated: Some(record.updated),
+                }]
+            );
+        }
+        assert_eq!(MetaFormat::from_path(Path::new("a.CSV")), MetaFormat::Csv);
+        assert_eq!(
+            MetaFormat::from_path(Path::new("a.jsonl")),
+            MetaFormat::Jsonl
+        );
+    }
+
+    #[test]
+    fn test_parse_missing_columns_and_errors() {
+        // 없는 열은 바꾸지 않고, 빈 folder는 폴더 해제
+        let updates = parse("path,folder\nnote.md,\n", MetaFormat::Csv).unwrap();
+        assert_eq!(updates[0].tags, None);
+        assert_eq!(updates[0].folder, Some(None));
+        assert_eq!(updates[0].created, None);
+
+        let updates = parse(
+            "\n{\"path\": \"a.md\", \"tags\": [\"x\"]}\n",
+            MetaFormat::Jsonl,
+        )
+        .unwrap();
+        assert_eq!(updates[0].line, 2);
+        assert_eq!(updates[0].tags, Some(vec!["x".to_string()]));
+        assert_eq!(updates[0].folder, None);
+
+        let error = |input: &str, format| match parse(input, format) {
+            Err(Error::InvalidMetaRecord { line, reason }) => (line, reason),
+            other => panic!("unexpected result: {:?}", other),
+        };
+        assert!(matches!(
+            error("title\nOnly title\n", MetaFormat::Csv),
+            (2, MetaRecordError::MissingKey)
+        ));
+        assert!(matches!(
+            error("path,tags\na.md,@work\n", MetaFormat::Csv),
+            (2, MetaRecordError::InvalidTag(_))
+        ));
+        assert!(matches!(
+            error("{\"uuid\": \"nope\"}", MetaFormat::Jsonl),
+            (1, MetaRecordError::InvalidUuid(_))
+        ));
+        assert!(matches!(
+            error(
+                "{\"path\": \"a.md\", \"created\": \"2024-01-01\"}",
+                MetaFormat::Jsonl
+            ),
+            (1, MetaRecordError::InvalidDate(_))
+        ));
+        assert!(matches!(
+            error("[1, 2]", MetaFormat::Jsonl),
+            (1, MetaRecordError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::error::{Error, Result};
+use crate::storage;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// 마이그레이션 단계: 파일 JSON을 한 버전 올림 (dir은 파일이 있는 노트 디렉토리)
+type Step = fn(&mut Value, &Path);
+
+// .index.json 버전. INDEX_MIGRATIONS[n]은 버전 n → n+1
+pub const INDEX_VERSION: u32 = 1;
+const INDEX_MIGRATIONS: [Step; INDEX_VERSION as usize] = [index_v0_to_v1];
+
+// .shortcuts.json 버전. SHORTCUTS_MIGRATIONS[n]은 버전 n → n+1
+pub const SHORTCUTS_VERSION: u32 = 1;
+const SHORTCUTS_MIGRATIONS: [Step; SHORTCUTS_VERSION as usize] = [shortcuts_v0_to_v1];
+
+// 이전 버전 파일을 현재 버전으로 옮긴 기록
+#[derive(Debug, Clone, PartialEq)]
+pub struct Migration {
+    pub path: PathBuf,
+    pub from: u32,
+    pub to: u32,
+    pub backup: PathBuf, // 마이그레이션 전 파일 복사본
+}
+
+pub(crate) fn load_index<T: DeserializeOwned>(path: &Path) -> Result<(T, Option<Migration>)> {
+    load(path, &INDEX_MIGRATIONS)
+}
+
+pub(crate) fn load_shortcuts<T: DeserializeOwned>(path: &Path) -> Result<(T, Option<Migration>)> {
+    load(path, &SHORTCUTS_MIGRATIONS)
+}
+
+// 파일을 읽어 버전을 확인하고, 오래된 버전이면 백업 후 현재 버전으로 옮겨 저장
+// version 필드가 없으면 버전 0, 현재보다 새 버전이면 읽지 않음
+fn load<T: DeserializeOwned>(path: &Path, steps: &[Step]) -> Result<(T, Option<Migration>)> {
+    let current = steps.len() as u32;
+    let content = fs::read_to_string(path).map_err(|source| Error::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let corrupt = |source| Error::Corrupt {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut value: Value = serde_json::from_str(&content).map_err(corrupt)?;
+
+    let version = value.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > current as u64 {
+        return Err(Error::UnsupportedVersion {
+            path: path.to_path_buf(),
+            version,
+            supported: current,
+        });
+    }
+    let version = version as u32;
+    if version == current {
+        return Ok((serde_json::from_value(value).map_err(corrupt)?, None));
+    }
+
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    for step in &steps[version as usize..] {
+        step(&mut value, dir);
+    }
+    if let Some(object) = value.as_object_mut() {
+        object.insert("version".to_string(), current.into());
+    }
+    // 옮긴 결과를 읽을 수 있을 때만 원본을 바꿈
+    let data = serde_json::from_value(value.clone()).map_err(corrupt)?;
+
+    let backup = backup_path(path, version);
+    fs::copy(path, &backup).map_err(|source| Error::Write {
+        path: backup.clone(),
+        source,
+    })?;
+    let migrated = serde_json::to_string_pretty(&value).map_err(Error::Serialize)?;
+    storage::write_atomic(path, migrated)?;
+
+    Ok((
+        data,
+        Some(Migration {
+            path: path.to_path_buf(),
+            from: version,
+            to: current,
+            backup,
+        }),
+    ))
+}
+
+// .index.json → .index.json.v0.bak
+fn backup_path(path: &Path, version: u32) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}.v{}.bak", name, version))
+}
+
+// v0 → v1: file_path가 없던 항목은 노트 디렉토리 바로 아래 파일로 간주
+fn index_v0_to_v1(value: &mut Value, dir: &Path) {
+    let Some(mappings) = value.get_mut("mappings").and_then(Value::as_object_mut) else {
+        return;
+    };
+
+    for entry in mappings.values_mut() {
+        let Some(entry) = entry.as_object_mut() else {
+            continue;
+        };
+        let has_path = entry
+            .get("file_path")
+            .and_then(Value::as_str)
+            .is_some_and(|path| !path.is_empty());
+        if has_path {
+            continue;
+        }
+        if let Some(filename) = entry.get("filename").and_then(Value::as_str) {
+            let file_path = dir.join(filename).to_string_lossy().to_string();
+            entry.insert("file_path".to_string(), file_path.into());
+        }
+    }
+}
+
+// v0 → v1: 구조는 같고 version 필드만 추가
+fn shortcuts_v0_to_v1(_value: &mut Value, _dir: &Path) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_index_v0_to_v1_fills_file_path() {
+        let mut value = json!({
+            "mappings": {
+                "a": { "filename": "old.md" },
+                "b": { "filename": "sub.md", "file_path": "/notes/dir/sub.md" },
+                "c": { "filename": "empty.md", "file_path": "" }
+            }
+        });
+        index_v0_to_v1(&mut value, Path::new("/notes"));
+
+        let path = |id: &str| {
+            value["mappings"][id]["file_path"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(path("a"), Path::new("/notes/old.md").to_string_lossy());
+        assert_eq!(path("b"), "/notes/dir/sub.md");
+        assert_eq!(path("c"), Path::new("/notes/empty.md").to_string_lossy());
+    }
+
+    #[test]
+    fn test_load_refuses_newer_version() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".shortcuts.json");
+        fs::write(&path, r#"{"version": 99, "shortcuts": {}}"#).unwrap();
+
+        let result = load_shortcuts::<Value>(&path);
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedVersion {
+                version: 99,
+                supported: SHORTCUTS_VERSION,
+                ..
+            })
+        ));
+    }
+}
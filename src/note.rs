@@ -1,6 +1,13 @@
+use crate::error::{Error, Result};
+use crate::index::{IndexEntry, content_hash};
+use crate::links::{self, RawLink};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::fs;
+use std::ops::Range;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +18,34 @@ pub enum LinkTarget {
     Note { id: Uuid },
 }
 
+impl LinkTarget {
+    // 문자열에서 대상 종류 추론: URL 스킴이 있으면 Url, note:<UUID>는 Note, 나머지는 File
+    pub fn parse(target: &str) -> Result<Self> {
+        let target = target.trim();
+        if target.is_empty() {
+            return Err(Error::EmptyLinkTarget);
+        }
+
+        if let Some(id) = target.strip_prefix("note:") {
+            let id = Uuid::parse_str(id).map_err(|_| Error::InvalidNoteId(id.to_string()))?;
+            return Ok(LinkTarget::Note { id });
+        }
+
+        let is_url = ["http://", "https://", "mailto:", "file://"]
+            .iter()
+            .any(|scheme| target.starts_with(scheme));
+        if is_url {
+            Ok(LinkTarget::Url {
+                url: target.to_string(),
+            })
+        } else {
+            Ok(LinkTarget::File {
+                path: PathBuf::from(target),
+            })
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Shortcut {
     pub alias: String,
@@ -34,8 +69,35 @@ pub struct Note {
     pub title: String,             // 실제 title (항상 존재)
     pub created_at: DateTime<Utc>, // 인덱스에서 관리
     pub updated_at: DateTime<Utc>, // 인덱스에서 관리
-    pub content: String,
-    pub tags: Vec<String>, // 인덱스에서 로드된 태그
+    pub tags: Vec<String>,         // 인덱스에서 로드된 태그
+    // 인덱스 항목으로 만든 노트는 본문이 처음 필요할 때 이 파일을 읽음
+    source: Option<PathBuf>,
+    body: OnceLock<NoteBody>,
+}
+
+// 파일에서 읽은 frontmatter와 본문
+#[derive(Debug, Clone, Default)]
+struct NoteBody {
+    frontmatter: Mapping, // 파일의 전체 frontmatter (알 수 없는 키 포함, 순서 유지)
+    content: String,
+    frontmatter_lines: usize, // 본문 앞 frontmatter 줄 수 (--- 줄 포함, 없으면 0)
+}
+
+impl NoteBody {
+    // 파일 내용을 frontmatter와 본문으로 나눔 (YAML이 잘못되었으면 빈 frontmatter)
+    fn parse(text: &str) -> Self {
+        match Note::split_frontmatter(text) {
+            Some((frontmatter, content)) => NoteBody {
+                frontmatter: Note::parse_frontmatter(&frontmatter).unwrap_or_default(),
+                content,
+                frontmatter_lines: Note::frontmatter_line_count(text),
+            },
+            None => NoteBody {
+                content: text.to_string(),
+                ..NoteBody::default()
+            },
+        }
+    }
 }
 
 impl Note {
@@ -47,10 +109,11 @@ impl Note {
         tags: Vec<String>,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
-    ) -> Result<Self, String> {
+    ) -> Result<Self> {
         if let Some((frontmatter, body)) = Self::split_frontmatter(&content) {
+            let mapping = Self::parse_frontmatter(&frontmatter)?;
             let mut meta: NoteMeta =
-                serde_yaml::from_str(&frontmatter).map_err(|e| format!("YAML 파싱 오류: {}", e))?;
+                serde_yaml::from_value(Value::Mapping(mapping.clone())).map_err(Error::Yaml)?;
 
             // 파일에 UUID가 있으면 사용, 없으면 매개변수의 UUID 사용
             let actual_id = meta.id.unwrap_or(id);
@@ -73,8 +136,13 @@ impl Note {
                 title,
                 created_at,
                 updated_at,
-                content: body,
                 tags,
+                source: None,
+                body: OnceLock::from(NoteBody {
+                    frontmatter: mapping,
+                    content: body,
+                    frontmatter_lines: Self::frontmatter_line_count(&content),
+                }),
             })
         } else {
             // frontmatter가 없는 경우 - 기본 메타데이터 생성
@@ -91,22 +159,176 @@ impl Note {
                 title,
                 created_at,
                 updated_at,
-                content,
                 tags,
+                source: None,
+                body: OnceLock::from(NoteBody {
+                    content,
+                    ..NoteBody::default()
+                }),
             })
         }
     }
 
-    fn split_frontmatter(content: &str) -> Option<(String, String)> {
-        if content.starts_with("---\n") {
-            let parts: Vec<&str> = content.splitn(3, "---\n").collect();
-            if parts.len() == 3 {
-                return Some((parts[1].to_string(), parts[2].to_string()));
+    // 바뀌지 않은 파일의 노트를 인덱스 항목으로 만듦 (파일은 본문이 필요할 때 읽음)
+    pub fn from_index(id: Uuid, entry: &IndexEntry) -> Self {
+        Note {
+            id,
+            filename: entry.filename.clone(),
+            meta: NoteMeta {
+                title: Some(entry.title.clone()),
+                id: Some(id),
+            },
+            title: entry.title.clone(),
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+            tags: entry.tags.clone(),
+            source: Some(PathBuf::from(&entry.file_path)),
+            body: OnceLock::new(),
+        }
+    }
+
+    fn body(&self) -> &NoteBody {
+        self.body.get_or_init(|| {
+            self.source
+                .as_ref()
+                .and_then(|path| fs::read_to_string(path).ok())
+                .map(|text| NoteBody::parse(&text))
+                .unwrap_or_default()
+        })
+    }
+
+    // 본문 (frontmatter 제외)
+    pub fn content(&self) -> &str {
+        &self.body().content
+    }
+
+    // 파일의 전체 frontmatter (알 수 없는 키 포함, 순서 유지)
+    pub fn frontmatter(&self) -> &Mapping {
+        &self.body().frontmatter
+    }
+
+    // 본문 앞 frontmatter 줄 수 (--- 줄 포함, 없으면 0)
+    pub fn frontmatter_lines(&self) -> usize {
+        self.body().frontmatter_lines
+    }
+
+    // 본문을 이미 읽었는지 (인덱스에서 만든 노트는 처음엔 읽지 않은 상태)
+    pub fn is_loaded(&self) -> bool {
+        self.body.get().is_some()
+    }
+
+    // 파일을 다시 쓴 뒤 (UUID 주입 등) 그 내용으로 frontmatter와 본문을 바꿈
+    pub fn set_file_content(&mut self, text: &str) {
+        self.body = OnceLock::from(NoteBody::parse(text));
+    }
+
+    // frontmatter 위치 찾기: (YAML 본문 범위, 본문 시작 위치)
+    // 여는 줄과 닫는 줄은 정확히 "---" 이어야 하며 CRLF 줄바꿈도 허용
+    fn frontmatter_bounds(content: &str) -> Option<(Range<usize>, usize)> {
+        let yaml_start = if content.starts_with("---\n") {
+            4
+        } else if content.starts_with("---\r\n") {
+            5
+        } else {
+            return None;
+        };
+
+        let mut pos = yaml_start;
+        while pos <= content.len() {
+            let line_end = content[pos..]
+                .find('\n')
+                .map(|i| pos + i + 1)
+                .unwrap_or(content.len());
+            let line = &content[pos..line_end];
+            if line.trim_end_matches(['\r', '\n']) == "---" {
+                return Some((yaml_start..pos, line_end));
             }
+            if line_end == content.len() {
+                break;
+            }
+            pos = line_end;
         }
         None
     }
 
+    // 본문 앞 frontmatter 줄 수 (여는/닫는 --- 줄 포함)
+    pub fn frontmatter_line_count(content: &str) -> usize {
+        Self::frontmatter_bounds(content).map_or(0, |(_, body_start)| {
+            content[..body_start].matches('\n').count()
+        })
+    }
+
+    fn split_frontmatter(content: &str) -> Option<(String, String)> {
+        Self::frontmatter_bounds(content).map(|(yaml, body_start)| {
+            (content[yaml].to_string(), content[body_start..].to_string())
+        })
+    }
+
+    // frontmatter YAML을 매핑으로 파싱 (빈 frontmatter는 빈 매핑)
+    fn parse_frontmatter(frontmatter: &str) -> Result<Mapping> {
+        match serde_yaml::from_str::<Value>(frontmatter).map_err(Error::Yaml)? {
+            Value::Null => Ok(Mapping::new()),
+            Value::Mapping(mapping) => Ok(mapping),
+            _ => Err(Error::FrontmatterNotMapping),
+        }
+    }
+
+    // 원본 텍스트를 직접 편집하여 frontmatter에 UUID 주입
+    // id 줄 외의 모든 내용(키 순서, 주석, 따옴표, 줄바꿈)은 그대로 유지됨
+    pub fn inject_id(content: &str, id: Uuid, title: &str) -> String {
+        Self::write_id(content, id, title, false)
+    }
+
+    // 이미 있는 id 값도 새 UUID로 교체 (중복 UUID 복구용)
+    pub fn replace_id(content: &str, id: Uuid, title: &str) -> String {
+        Self::write_id(content, id, title, true)
+    }
+
+    fn write_id(content: &str, id: Uuid, title: &str, replace: bool) -> String {
+        let Some((yaml, _)) = Self::frontmatter_bounds(content) else {
+            // frontmatter가 없으면 title과 id로 새로 만듦
+            let meta = NoteMeta {
+                title: Some(title.to_string()),
+                id: Some(id),
+            };
+            let frontmatter = serde_yaml::to_string(&meta).unwrap_or_default();
+            return format!("---\n{}---\n{}", frontmatter, content);
+        };
+
+        let eol = if content.starts_with("---\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let id_line = format!("id: {}", id);
+
+        // 값이 비어 있는 (replace면 아무 값이나) 최상위 id 키가 있으면 그 줄을 교체 (중복 키 방지)
+        let mut pos = yaml.start;
+        while pos < yaml.end {
+            let line_end = content[pos..yaml.end]
+                .find('\n')
+                .map(|i| pos + i + 1)
+                .unwrap_or(yaml.end);
+            let line = content[pos..line_end].trim_end_matches(['\r', '\n']);
+            if let Some(value) = line.strip_prefix("id:") {
+                let value = value.split('#').next().unwrap_or("").trim();
+                if replace || value.is_empty() || value == "~" || value == "null" {
+                    return format!(
+                        "{}{}{}",
+                        &content[..pos],
+                        id_line,
+                        &content[pos + line.len()..]
+                    );
+                }
+            }
+            pos = line_end;
+        }
+
+        // 닫는 "---" 줄 바로 앞에 id 줄 추가
+        let (head, tail) = content.split_at(yaml.end);
+        format!("{}{}{}{}", head, id_line, eol, tail)
+    }
+
     fn extract_title_from_content(content: &str) -> Option<String> {
         for line in content.lines() {
             if line.starts_with("# ") {
@@ -178,25 +400,70 @@ impl Note {
             .collect()
     }
 
+    // 전체 frontmatter를 다시 직렬화 (알 수 없는 키도 유지, 주석은 유실됨)
     pub fn to_markdown(&self) -> String {
-        let frontmatter = serde_yaml::to_string(&self.meta).unwrap_or_default();
-        format!("---\n{}---\n{}", frontmatter, self.content)
+        let mut mapping = self.frontmatter().clone();
+        if let Some(title) = &self.meta.title {
+            mapping.insert(Value::from("title"), Value::from(title.as_str()));
+        }
+        if let Some(id) = self.meta.id {
+            mapping.insert(Value::from("id"), Value::from(id.to_string()));
+        }
+        let frontmatter = serde_yaml::to_string(&mapping).unwrap_or_default();
+        format!("---\n{}---\n{}", frontmatter, self.content())
+    }
+
+    // 본문의 위키 링크와 마크다운 노트 링크 (줄 번호는 파일 기준)
+    pub fn links(&self) -> Vec<RawLink> {
+        let mut links = links::parse_links(self.content());
+        for link in &mut links {
+            link.line_number += self.frontmatter_lines();
+        }
+        links
     }
 
     // frontmatter가 있는지 확인
     #[allow(dead_code)]
     pub fn has_frontmatter(content: &str) -> bool {
-        content.starts_with("---\n")
+        Self::frontmatter_bounds(content).is_some()
     }
 
     // frontmatter에 UUID가 있는지 확인
     pub fn has_uuid_in_frontmatter(content: &str) -> bool {
-        if let Some((frontmatter, _)) = Self::split_frontmatter(content) {
-            if let Ok(meta) = serde_yaml::from_str::<NoteMeta>(&frontmatter) {
-                return meta.id.is_some();
-            }
+        Self::id_in_frontmatter(content).is_some()
+    }
+
+    // frontmatter에 저장된 UUID
+    pub fn id_in_frontmatter(content: &str) -> Option<Uuid> {
+        let (frontmatter, _) = Self::split_frontmatter(content)?;
+        let mapping = Self::parse_frontmatter(&frontmatter).ok()?;
+        serde_yaml::from_value::<NoteMeta>(Value::Mapping(mapping))
+            .ok()?
+            .id
+    }
+
+    // frontmatter와 본문의 해시. 제목/태그/폴더 등 frontmatter만 고친 경우도 변경으로 보지만
+    // 주입된 id와 본문 제목과 같은 title은 빼므로 UUID 주입에는 영향을 받지 않음
+    pub fn file_hash(content: &str) -> String {
+        let Some((yaml, body_start)) = Self::frontmatter_bounds(content) else {
+            return content_hash(content);
+        };
+        let Ok(mut mapping) = Self::parse_frontmatter(&content[yaml]) else {
+            return content_hash(content);
+        };
+
+        let body = &content[body_start..];
+        mapping.remove("id");
+        if mapping.get("title").and_then(Value::as_str)
+            == Self::extract_title_from_content(body).as_deref()
+        {
+            mapping.remove("title");
+        }
+        if mapping.is_empty() {
+            return content_hash(body);
         }
-        false
+        let frontmatter = serde_yaml::to_string(&mapping).unwrap_or_default();
+        content_hash(&format!("{}---\n{}", frontmatter, body))
     }
 }
 
@@ -230,7 +497,7 @@ This is a test note."#;
         assert_eq!(note.title, "Test Note");
         assert_eq!(note.filename, "test.md");
         assert_eq!(note.tags, tags);
-        assert!(note.content.contains("# Test Content"));
+        assert!(note.content().contains("# Test Content"));
     }
 
     #[test]
@@ -348,4 +615,95 @@ title: Test Note
         assert!(markdown.contains("title: Test Note"));
         assert!(markdown.contains("# Test Content"));
     }
+
+    #[test]
+    fn test_inject_id_appends_line_to_existing_frontmatter() {
+        let content = "---\ntitle: Keep Me # comment\ndraft: true\n---\nBody\n";
+        let id = Uuid::new_v4();
+
+        let injected = Note::inject_id(content, id, "ignored");
+
+        assert_eq!(
+            injected,
+            format!(
+                "---\ntitle: Keep Me # comment\ndraft: true\nid: {}\n---\nBody\n",
+                id
+            )
+        );
+    }
+
+    #[test]
+    fn test_replace_id_keeps_other_lines() {
+        let old = Uuid::new_v4();
+        let new = Uuid::new_v4();
+        let content = format!("---\ntitle: Copy\nid: {}\ndraft: true\n---\nBody\n", old);
+
+        assert_eq!(
+            Note::replace_id(&content, new, "ignored"),
+            format!("---\ntitle: Copy\nid: {}\ndraft: true\n---\nBody\n", new)
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_keeps_unknown_keys() {
+        let content = "---\ntitle: Test Note\naliases:\n  - Other\n---\nBody";
+        let now = Utc::now();
+        let note = Note::from_markdown(
+            Uuid::new_v4(),
+            "test.md".to_string(),
+            content.to_string(),
+            vec![],
+            now,
+            now,
+        )
+        .unwrap();
+
+        let markdown = note.to_markdown();
+        assert!(markdown.contains("aliases:"));
+        assert!(markdown.contains(&format!("id: {}", note.id)));
+        assert!(markdown.ends_with("---\nBody"));
+    }
+
+    #[test]
+    fn test_link_line_numbers_count_frontmatter() {
+        let now = Utc::now();
+        let parse = |content: &str| {
+            Note::from_markdown(
+                Uuid::new_v4(),
+                "test.md".to_string(),
+                content.to_string(),
+                vec![],
+                now,
+                now,
+            )
+            .unwrap()
+        };
+
+        let note = parse("---\ntitle: Hub\ndraft: true\n---\n\n[[Other]]\n");
+        assert_eq!(note.frontmatter_lines(), 4);
+        assert_eq!(note.links()[0].line_number, 6);
+
+        let note = parse("# Hub\n[[Other]]\n");
+        assert_eq!(note.frontmatter_lines(), 0);
+        assert_eq!(note.links()[0].line_number, 2);
+    }
+
+    #[test]
+    fn test_link_target_parse() {
+        assert!(matches!(
+            LinkTarget::parse("https://example.com").unwrap(),
+            LinkTarget::Url { .. }
+        ));
+        let id = Uuid::new_v4();
+        assert!(matches!(
+            LinkTarget::parse(&format!("note:{}", id)).unwrap(),
+            LinkTarget::Note { id: target } if target == id
+        ));
+        assert!(matches!(
+            LinkTarget::parse("docs/spec.md").unwrap(),
+            LinkTarget::File { path } if path == std::path::Path::new("docs/spec.md")
+        ));
+        assert!(LinkTarget::parse("note:xyz").is_err());
+        assert!(LinkTarget::parse("  ").is_err());
+    }
 }
@@ -0,0 +1,45 @@
+use crossterm::terminal;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+// 터미널에 출력할 때만 색을 씀 (NO_COLOR가 있으면 끔)
+pub fn use_color() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+// 터미널 높이보다 긴 출력은 $PAGER (기본값 less)로 보여주고, 아니면 그대로 출력
+pub fn page(output: &str) {
+    let fits = terminal::size().is_ok_and(|(_, rows)| output.lines().count() < rows as usize);
+    if !io::stdout().is_terminal() || fits || !run_pager(output) {
+        print!("{}", output);
+    }
+}
+
+// PAGER가 비어 있거나 cat이면 페이저를 쓰지 않음
+fn run_pager(output: &str) -> bool {
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    let mut words = pager.split_whitespace();
+    let Some(program) = words.next() else {
+        return false;
+    };
+    if program == "cat" {
+        return false;
+    }
+
+    let mut command = Command::new(program);
+    command.args(words).stdin(Stdio::piped());
+    // less: 색 유지(R), 한 화면이면 바로 종료(F), 종료 후 화면 유지(X)
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    let Ok(mut child) = command.spawn() else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // 페이저를 먼저 닫으면 파이프가 끊기므로 쓰기 오류는 무시
+        let _ = stdin.write_all(output.as_bytes());
+    }
+    let _ = child.wait();
+    true
+}
@@ -0,0 +1,456 @@
+use crate::error::{QueryError, Result};
+use crate::index::IndexEntry;
+use crate::note::Note;
+use crate::search::{self, QueryTerm};
+use chrono::{DateTime, NaiveDate, Utc};
+
+// 날짜 비교 연산자
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DateField {
+    Created,
+    Updated,
+}
+
+// 파싱된 검색 쿼리
+// 예: tag:rust folder:@work title:"meeting" updated:>2025-01-01 -draft
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    All, // 빈 쿼리 - 모든 노트
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+    Text(QueryTerm), // 제목/본문 전문 검색 (역색인)
+    Tag(String),     // tag:rust
+    Folder(String),  // folder:@work 또는 folder:work
+    Title(String),   // title:"meeting" (부분 일치)
+    Path(String),    // path:projects/ (파일 경로 부분 일치)
+    Date {
+        field: DateField,
+        comparison: Comparison,
+        date: NaiveDate,
+    }, // created:>2025-01-01, updated:<=2025-02-01, before:, after:
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word(String),
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query> {
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+
+        if parser.tokens.is_empty() {
+            return Ok(Query::All);
+        }
+
+        let query = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(QueryError::Unexpected(token.to_string()).into());
+        }
+        Ok(query)
+    }
+
+    // 노트가 쿼리 조건을 만족하는지 확인
+    // text_match: 전문 검색어가 이 노트에 포함되는지 판단 (보통 역색인 조회)
+    pub fn matches(
+        &self,
+        note: &Note,
+        entry: Option<&IndexEntry>,
+        text_match: &dyn Fn(&QueryTerm) -> bool,
+    ) -> bool {
+        match self {
+            Query::All => true,
+            Query::And(queries) => queries.iter().all(|q| q.matches(note, entry, text_match)),
+            Query::Or(queries) => queries.iter().any(|q| q.matches(note, entry, text_match)),
+            Query::Not(query) => !query.matches(note, entry, text_match),
+            Query::Text(term) => text_match(term),
+            Query::Tag(tag) => note.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+            Query::Folder(folder) => note
+                .get_folder_tag()
+                .is_some_and(|t| t.eq_ignore_ascii_case(folder)),
+            Query::Title(text) => note.title.to_lowercase().contains(&text.to_lowercase()),
+            Query::Path(text) => entry
+                .map(|e| e.file_path.as_str())
+                .unwrap_or(note.filename.as_str())
+                .to_lowercase()
+                .contains(&text.to_lowercase()),
+            Query::Date {
+                field,
+                comparison,
+                date,
+            } => {
+                let value: DateTime<Utc> = match field {
+                    DateField::Created => note.created_at,
+                    DateField::Updated => note.updated_at,
+                };
+                let value = value.date_naive();
+                match comparison {
+                    Comparison::Less => value < *date,
+                    Comparison::LessOrEqual => value <= *date,
+                    Comparison::Equal => value == *date,
+                    Comparison::GreaterOrEqual => value >= *date,
+                    Comparison::Greater => value > *date,
+                }
+            }
+        }
+    }
+
+    // 순위 매기기와 미리보기에 쓸 전문 검색어 (NOT 아래에 있는 것은 제외)
+    pub fn text_terms(&self) -> Vec<QueryTerm> {
+        let mut terms = Vec::new();
+        self.collect_text_terms(&mut terms, false);
+        terms
+    }
+
+    // 쿼리에 등장하는 모든 전문 검색어 (NOT 포함)
+    pub fn referenced_terms(&self) -> Vec<QueryTerm> {
+        let mut terms = Vec::new();
+        self.collect_text_terms(&mut terms, true);
+        terms
+    }
+
+    fn collect_text_terms(&self, terms: &mut Vec<QueryTerm>, include_negated: bool) {
+        match self {
+            Query::And(queries) | Query::Or(queries) => {
+                for query in queries {
+                    query.collect_text_terms(terms, include_negated);
+                }
+            }
+            Query::Not(query) if include_negated => {
+                query.collect_text_terms(terms, include_negated)
+            }
+            Query::Text(term) if !terms.contains(term) => terms.push(term.clone()),
+            _ => {}
+        }
+    }
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::Not => write!(f, "NOT"),
+            Token::Word(word) => write!(f, "{}", word),
+        }
+    }
+}
+
+// 쿼리 문자열을 토큰으로 분리 (따옴표 안의 공백과 괄호는 값의 일부)
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '(' {
+            chars.next();
+            tokens.push(Token::LParen);
+            continue;
+        }
+        if c == ')' {
+            chars.next();
+            tokens.push(Token::RParen);
+            continue;
+        }
+
+        let mut word = String::new();
+        let mut in_quotes = false;
+        while let Some(&c) = chars.peek() {
+            if !in_quotes && (c.is_whitespace() || c == '(' || c == ')') {
+                break;
+            }
+            if c == '"' {
+                in_quotes = !in_quotes;
+            }
+            word.push(c);
+            chars.next();
+        }
+        if in_quotes {
+            return Err(QueryError::UnclosedQuote.into());
+        }
+
+        match word.as_str() {
+            "AND" | "&&" => tokens.push(Token::And),
+            "OR" | "||" => tokens.push(Token::Or),
+            "NOT" | "-" => tokens.push(Token::Not),
+            _ => {
+                // -word 는 NOT word
+                if let Some(rest) = word.strip_prefix('-') {
+                    tokens.push(Token::Not);
+                    tokens.push(Token::Word(rest.to_string()));
+                } else {
+                    tokens.push(Token::Word(word));
+                }
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    // or := and ("OR" and)*
+    fn parse_or(&mut self) -> Result<Query> {
+        let mut queries = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            queries.push(self.parse_and()?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::Or(queries)
+        })
+    }
+
+    // and := unary (["AND"] unary)*  - AND는 생략 가능
+    fn parse_and(&mut self) -> Result<Query> {
+        let mut queries = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                Some(Token::Or) | Some(Token::RParen) | None => break,
+                _ => {}
+            }
+            queries.push(self.parse_unary()?);
+        }
+        Ok(if queries.len() == 1 {
+            queries.remove(0)
+        } else {
+            Query::And(queries)
+        })
+    }
+
+    // unary := ("NOT" | "-") unary | primary
+    fn parse_unary(&mut self) -> Result<Query> {
+        if self.peek() == Some(&Token::Not) {
+            self.next();
+            return Ok(Query::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    // primary := "(" or ")" | term
+    fn parse_primary(&mut self) -> Result<Query> {
+        match self.next() {
+            Some(Token::LParen) => {
+                let query = self.parse_or()?;
+                if self.next() != Some(Token::RParen) {
+                    return Err(QueryError::UnclosedParen.into());
+                }
+                Ok(query)
+            }
+            Some(Token::Word(word)) => parse_term(&word),
+            Some(token) => Err(QueryError::Unexpected(token.to_string()).into()),
+            None => Err(QueryError::UnexpectedEnd.into()),
+        }
+    }
+}
+
+fn unquote(value: &str) -> String {
+    value.replace('"', "")
+}
+
+// field:value 또는 일반 검색어 하나 해석
+fn parse_term(word: &str) -> Result<Query> {
+    if let Some((field, value)) = word.split_once(':') {
+        let value = unquote(value);
+        let query = match field.to_lowercase().as_str() {
+            "tag" => Some(Query::Tag(value)),
+            "folder" => Some(Query::Folder(if value.starts_with('@') {
+                value
+            } else {
+                format!("@{}", value)
+            })),
+            "title" => Some(Query::Title(value)),
+            "path" => Some(Query::Path(value)),
+            "created" => Some(parse_date_query(DateField::Created, &value)?),
+            "updated" => Some(parse_date_query(DateField::Updated, &value)?),
+            "before" => Some(Query::Date {
+                field: DateField::Updated,
+                comparison: Comparison::Less,
+                date: parse_date(&value)?,
+            }),
+            "after" => Some(Query::Date {
+                field: DateField::Updated,
+                comparison: Comparison::Greater,
+                date: parse_date(&value)?,
+            }),
+            // 알 수 없는 필드 (예: URL)는 일반 검색어로 취급
+            _ => None,
+        };
+
+        if let Some(query) = query {
+            if matches!(&query, Query::Tag(v) | Query::Title(v) | Query::Path(v) if v.is_empty()) {
+                return Err(QueryError::EmptyValue(field.to_string()).into());
+            }
+            return Ok(query);
+        }
+    }
+
+    // 일반 검색어는 전문 검색 규칙(구문, 접두어)으로 해석
+    let mut terms: Vec<Query> = search::parse_query(word)
+        .into_iter()
+        .map(Query::Text)
+        .collect();
+    Ok(match terms.len() {
+        0 => Query::All,
+        1 => terms.remove(0),
+        _ => Query::And(terms),
+    })
+}
+
+fn parse_date_query(field: DateField, value: &str) -> Result<Query> {
+    let (comparison, date) = if let Some(rest) = value.strip_prefix(">=") {
+        (Comparison::GreaterOrEqual, rest)
+    } else if let Some(rest) = value.strip_prefix("<=") {
+        (Comparison::LessOrEqual, rest)
+    } else if let Some(rest) = value.strip_prefix('>') {
+        (Comparison::Greater, rest)
+    } else if let Some(rest) = value.strip_prefix('<') {
+        (Comparison::Less, rest)
+    } else if let Some(rest) = value.strip_prefix('=') {
+        (Comparison::Equal, rest)
+    } else {
+        (Comparison::Equal, value)
+    };
+
+    Ok(Query::Date {
+        field,
+        comparison,
+        date: parse_date(date)?,
+    })
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| QueryError::InvalidDate(value.to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    fn make_note(title: &str, tags: &[&str], updated: &str) -> Note {
+        let updated = NaiveDate::parse_from_str(updated, "%Y-%m-%d").unwrap();
+        let updated = Utc.from_utc_datetime(&updated.and_hms_opt(12, 0, 0).unwrap());
+        Note::from_markdown(
+            Uuid::new_v4(),
+            "test.md".to_string(),
+            format!("# {}\n\nbody text", title),
+            tags.iter().map(|t| t.to_string()).collect(),
+            updated,
+            updated,
+        )
+        .unwrap()
+    }
+
+    fn no_text(_: &QueryTerm) -> bool {
+        false
+    }
+
+    #[test]
+    fn test_parse_fields_and_implicit_and() {
+        let query = Query::parse(r#"tag:rust folder:work title:"team meeting" -draft"#).unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Query::Tag("rust".to_string()),
+                Query::Folder("@work".to_string()),
+                Query::Title("team meeting".to_string()),
+                Query::Not(Box::new(Query::Text(QueryTerm::Word("draft".to_string())))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_boolean_operators_and_grouping() {
+        let query = Query::parse("(tag:a OR tag:b) AND NOT tag:c").unwrap();
+        assert_eq!(
+            query,
+            Query::And(vec![
+                Query::Or(vec![
+                    Query::Tag("a".to_string()),
+                    Query::Tag("b".to_string())
+                ]),
+                Query::Not(Box::new(Query::Tag("c".to_string()))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Query::parse("(tag:a").is_err());
+        assert!(Query::parse("tag:a OR").is_err());
+        assert!(Query::parse("updated:>2025-13-01").is_err());
+        assert!(Query::parse(r#"title:"open"#).is_err());
+        assert!(Query::parse("tag:").is_err());
+    }
+
+    #[test]
+    fn test_matches_tags_folders_and_dates() {
+        let note = make_note("Weekly Meeting", &["rust", "@work"], "2025-03-10");
+
+        let matches = |q: &str| Query::parse(q).unwrap().matches(&note, None, &no_text);
+        assert!(matches("tag:rust folder:@work"));
+        assert!(matches("title:meeting"));
+        assert!(matches("updated:>2025-01-01 updated:<=2025-03-10"));
+        assert!(matches("after:2025-03-09 before:2025-03-11"));
+        assert!(matches("tag:python OR tag:RUST"));
+        assert!(!matches("tag:rust -folder:work"));
+        assert!(!matches("created:2025-03-11"));
+    }
+
+    #[test]
+    fn test_text_terms_skip_negated_terms() {
+        let query = Query::parse(r#"rust "web dev" -python tag:x"#).unwrap();
+        assert_eq!(
+            query.text_terms(),
+            vec![
+                QueryTerm::Word("rust".to_string()),
+                QueryTerm::Phrase(vec!["web".to_string(), "dev".to_string()]),
+            ]
+        );
+    }
+}
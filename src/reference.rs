@@ -0,0 +1,130 @@
+use uuid::Uuid;
+
+// 목록에 표시하는 UUID 길이 (git의 짧은 해시처럼 앞부분만)
+pub const SHORT_ID_LEN: usize = 8;
+
+// UUID 앞부분으로 인정하는 최소 길이
+const MIN_PREFIX_LEN: usize = 4;
+
+// 제목 유사도가 이 값 이상일 때만 후보로 봄 (0.0 ~ 1.0)
+pub const FUZZY_THRESHOLD: f64 = 0.8;
+
+// 이보다 짧은 이름은 부분 문자열/유사도로 찾지 않음 ("a"가 아무 제목에나 맞지 않도록)
+pub const MIN_FUZZY_LEN: usize = 3;
+
+// 명령어에서 노트를 가리키는 방법
+#[derive(Debug, Clone, PartialEq)]
+pub enum NoteRef {
+    // #N: 마지막으로 표시한 목록/검색 결과의 N번째 (1부터)
+    Result(usize),
+    // # 없는 숫자: 결과 번호로 먼저 보고, 범위 밖이면 이름/UUID 앞부분으로 다시 찾음
+    Number(usize),
+    // 전체 UUID
+    Id(Uuid),
+    // UUID 앞부분일 수 있는 16진수 (같은 파일명/제목의 노트가 있으면 그 노트가 우선)
+    Prefix(String),
+    // 파일명 또는 제목
+    Name(String),
+}
+
+impl NoteRef {
+    pub fn parse(reference: &str) -> Self {
+        let reference = reference.trim();
+        if let Some(number) = reference.strip_prefix('#')
+            && let Ok(number) = number.parse::<usize>()
+        {
+            return NoteRef::Result(number);
+        }
+        if let Ok(id) = Uuid::parse_str(reference) {
+            return NoteRef::Id(id);
+        }
+        if reference.chars().all(|c| c.is_ascii_digit())
+            && let Ok(number) = reference.parse::<usize>()
+        {
+            return NoteRef::Number(number);
+        }
+
+        match as_prefix(reference) {
+            Some(prefix) => NoteRef::Prefix(prefix),
+            None => NoteRef::Name(reference.to_string()),
+        }
+    }
+}
+
+// UUID 앞부분으로 볼 수 있으면 소문자로 바꾼 값 (16진수와 하이픈, 16진수 4자 이상)
+pub fn as_prefix(reference: &str) -> Option<String> {
+    let is_hex = reference.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    let digits = reference.chars().filter(|c| *c != '-').count();
+    (is_hex && digits >= MIN_PREFIX_LEN).then(|| reference.to_ascii_lowercase())
+}
+
+// 목록 표시용 짧은 UUID
+pub fn short_id(id: &Uuid) -> String {
+    id.to_string()[..SHORT_ID_LEN].to_string()
+}
+
+// UUID가 앞부분과 맞는지 (하이픈은 있어도 없어도 됨)
+pub fn matches_prefix(id: &Uuid, prefix: &str) -> bool {
+    if prefix.contains('-') {
+        id.to_string().starts_with(prefix)
+    } else {
+        id.simple().to_string().starts_with(prefix)
+    }
+}
+
+// 제목 유사도 (대소문자 무시, MIN_FUZZY_LEN보다 짧으면 0)
+// 제목에 그대로 들어 있으면 0.95, 아니면 Jaro-Winkler
+pub fn fuzzy_score(query: &str, title: &str) -> f64 {
+    let query = query.trim().to_lowercase();
+    let title = title.to_lowercase();
+    if query.chars().count() < MIN_FUZZY_LEN {
+        return 0.0;
+    }
+    if title.contains(&query) {
+        return 0.95;
+    }
+    strsim::jaro_winkler(&query, &title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_note_ref() {
+        let id = Uuid::new_v4();
+        assert_eq!(NoteRef::parse("#3"), NoteRef::Result(3));
+        assert_eq!(NoteRef::parse("3"), NoteRef::Number(3));
+        assert_eq!(NoteRef::parse("1234"), NoteRef::Number(1234));
+        assert_eq!(NoteRef::parse(&id.to_string()), NoteRef::Id(id));
+        assert_eq!(
+            NoteRef::parse("3F2A9c"),
+            NoteRef::Prefix("3f2a9c".to_string())
+        );
+        // 너무 짧거나 16진수가 아니면 이름
+        assert_eq!(NoteRef::parse("abc"), NoteRef::Name("abc".to_string()));
+        assert_eq!(
+            NoteRef::parse("rust notes.md"),
+            NoteRef::Name("rust notes.md".to_string())
+        );
+    }
+
+    #[test]
+    fn test_matches_prefix_with_or_without_hyphens() {
+        let id = Uuid::parse_str("3f2a9c1d-1234-4abc-8def-0123456789ab").unwrap();
+        assert!(matches_prefix(&id, "3f2a9c1d-12"));
+        assert!(matches_prefix(&id, "3f2a9c1d12"));
+        assert!(!matches_prefix(&id, "3f2b"));
+        assert_eq!(short_id(&id), "3f2a9c1d");
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("rust", "Learning Rust"), 0.95);
+        assert!(fuzzy_score("ownrship", "Ownership") >= FUZZY_THRESHOLD);
+        // 너무 짧은 이름은 부분 문자열이어도 맞지 않음
+        assert_eq!(fuzzy_score("r", "Rust"), 0.0);
+        assert_eq!(fuzzy_score("ru", "Rust"), 0.0);
+        assert!(fuzzy_score("python", "Weekly meeting") < FUZZY_THRESHOLD);
+    }
+}
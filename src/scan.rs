@@ -0,0 +1,165 @@
+use crate::error::{Error, Result};
+use crate::index::FolderRules;
+use crate::storage;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// watched folder 하나에 적용되는 스캔 규칙 (패턴을 미리 컴파일해 둠)
+pub struct FolderScanner {
+    root: PathBuf,
+    max_depth: Option<usize>,
+    exclude: Gitignore,
+    include: Option<GlobSet>,
+}
+
+impl FolderScanner {
+    pub fn new(root: &Path, rules: &FolderRules) -> Result<Self> {
+        let mut exclude = GitignoreBuilder::new(root);
+        for pattern in &rules.exclude {
+            exclude
+                .add_line(None, pattern)
+                .map_err(|e| invalid_pattern(pattern, e))?;
+        }
+        let exclude = exclude
+            .build()
+            .map_err(|e| invalid_pattern(&rules.exclude.join(", "), e))?;
+
+        let include = if rules.include.is_empty() {
+            None
+        } else {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in &rules.include {
+                let glob = Glob::new(pattern).map_err(|e| invalid_pattern(pattern, e))?;
+                builder.add(glob);
+            }
+            Some(
+                builder
+                    .build()
+                    .map_err(|e| invalid_pattern(&rules.include.join(", "), e))?,
+            )
+        };
+
+        Ok(FolderScanner {
+            root: root.to_path_buf(),
+            max_depth: rules.max_depth,
+            exclude,
+            include,
+        })
+    }
+
+    // 폴더를 재귀적으로 스캔하여 .md 파일 목록 반환 (경로 순 정렬)
+    pub fn scan(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        self.scan_dir(&self.root, 0, &mut |path, hidden| {
+            if !hidden && self.is_included(&path) {
+                files.push(path);
+            }
+        })?;
+        files.sort();
+        Ok(files)
+    }
+
+    // 저장 도중 중단되어 남은 노트 임시 파일 (.a.md.tmp, a.md가 스캔 대상인 것만)
+    pub fn scan_temp_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        self.scan_dir(&self.root, 0, &mut |path, hidden| {
+            if hidden
+                && let Some(original) = storage::temp_original(&path)
+                && self.is_included(&original)
+                && !self.exclude.matched(&original, false).is_ignore()
+            {
+                files.push(path);
+            }
+        })?;
+        files.sort();
+        Ok(files)
+    }
+
+    // 규칙에 맞는 폴더를 돌며 파일마다 visit(경로, 숨김 파일인지) 호출
+    fn scan_dir(
+        &self,
+        dir: &Path,
+        depth: usize,
+        visit: &mut dyn FnMut(PathBuf, bool),
+    ) -> Result<()> {
+        let read_dir_error = |source| Error::ReadDir {
+            path: dir.to_path_buf(),
+            source,
+        };
+        let entries = fs::read_dir(dir).map_err(read_dir_error)?;
+
+        for entry in entries {
+            let entry = entry.map_err(read_dir_error)?;
+            let path = entry.path();
+            let file_type = entry.file_type().map_err(read_dir_error)?;
+            // 심볼릭 링크 폴더는 순환을 피하기 위해 따라가지 않음
+            let is_dir = file_type.is_dir();
+            let is_file = file_type.is_file() || (file_type.is_symlink() && path.is_file());
+
+            // 숨김 파일/폴더 (.git, .obsidian, .index.json 등)는 노트로 보지 않음
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                if is_file {
+                    visit(path, true);
+                }
+                continue;
+            }
+
+            if self.exclude.matched(&path, is_dir).is_ignore() {
+                continue;
+            }
+
+            if is_dir {
+                if self.max_depth.is_none_or(|max| depth < max) {
+                    self.scan_dir(&path, depth + 1, visit)?;
+                }
+            } else if is_file {
+                visit(path, false);
+            }
+        }
+
+        Ok(())
+    }
+
+    // 스캔했다면 이 파일이 포함되었을지 (파일 감시 이벤트 거르기용)
+    pub fn accepts(&self, path: &Path) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+
+        let hidden = relative
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'));
+        let depth = relative.components().count().saturating_sub(1);
+
+        !hidden
+            && self.max_depth.is_none_or(|max| depth <= max)
+            && !self
+                .exclude
+                .matched_path_or_any_parents(path, false)
+                .is_ignore()
+            && self.is_included(path)
+    }
+
+    pub fn is_included(&self, path: &Path) -> bool {
+        if path.extension().and_then(|s| s.to_str()) != Some("md") {
+            return false;
+        }
+
+        match &self.include {
+            Some(include) => {
+                let relative = path.strip_prefix(&self.root).unwrap_or(path);
+                include.is_match(relative)
+            }
+            None => true,
+        }
+    }
+}
+
+fn invalid_pattern(pattern: &str, source: impl std::error::Error + Send + Sync + 'static) -> Error {
+    Error::InvalidPattern {
+        pattern: pattern.to_string(),
+        source: Box::new(source),
+    }
+}
@@ -0,0 +1,474 @@
+use crate::error::{Error, Result};
+use crate::index::content_hash;
+use crate::storage;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+// BM25 파라미터
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+// 토큰 분리 규칙이 바뀌면 올려서 저장된 색인을 다시 만들게 함
+const TOKENIZER_VERSION: u32 = 1;
+
+// 색인된 문서 정보
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DocInfo {
+    pub length: u32,  // 토큰 위치 개수
+    pub hash: String, // 색인된 텍스트의 해시 (변경 감지용)
+}
+
+// 전문 검색용 역색인 (.search_index.json에 저장)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    #[serde(default)]
+    pub tokenizer: u32, // 색인을 만든 토큰 분리 규칙 버전
+    pub docs: HashMap<Uuid, DocInfo>,
+    // term -> (note_id -> 토큰 위치 목록)
+    pub postings: HashMap<String, HashMap<Uuid, Vec<u32>>>,
+}
+
+// 검색 결과에 표시할 본문 줄
+#[derive(Debug, Clone, Serialize)]
+pub struct Snippet {
+    pub line_number: usize, // 본문 기준 1부터 시작
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub id: Uuid,
+    pub score: f64,
+    pub snippet: Option<Snippet>,
+}
+
+// 검색어 하나 (단어, 접두어, 구문)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QueryTerm {
+    Word(String),
+    Prefix(String),      // rust* 형식
+    Phrase(Vec<String>), // "exact phrase" 형식
+}
+
+// 띄어쓰기 대신 조사/어미가 붙는 한글, 한자, 가나
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{1100}'..='\u{11FF}'
+        | '\u{3040}'..='\u{30FF}'
+        | '\u{3130}'..='\u{318F}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7A3}'
+        | '\u{F900}'..='\u{FAFF}')
+}
+
+// 텍스트를 (위치, 소문자 토큰)으로 분리 (문자/숫자가 아닌 문자가 구분자)
+// 한글 등 CJK 글자는 글자마다 한 위치를 차지하고, 다음 글자와 묶은 2글자 토큰으로 색인
+// ("러스트를" -> 러스, 스트, 트를). every_char면 각 위치에 1글자 토큰도 함께 넣음
+fn positioned_tokens(text: &str, every_char: bool) -> Vec<(u32, String)> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    for word in text.split(|c: char| !c.is_alphanumeric()) {
+        let chars: Vec<char> = word.chars().collect();
+        for run in chars.chunk_by(|a, b| is_cjk(*a) == is_cjk(*b)) {
+            if !is_cjk(run[0]) {
+                tokens.push((position, run.iter().collect::<String>().to_lowercase()));
+                position += 1;
+                continue;
+            }
+
+            for (i, c) in run.iter().enumerate() {
+                if let Some(next) = run.get(i + 1) {
+                    tokens.push((position, format!("{}{}", c, next)));
+                }
+                if every_char || i + 1 == run.len() {
+                    tokens.push((position, c.to_string()));
+                }
+                position += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+// 검색어를 소문자 토큰으로 분리 (위치마다 토큰 하나, CJK 글자는 2글자씩 묶고 마지막 글자만 단독)
+// 여러 토큰이 나오면 구문으로 찾으므로 "러스트"는 "러스트를"의 러스, 스트, 트와 맞음
+pub fn tokenize(text: &str) -> Vec<String> {
+    positioned_tokens(text, false)
+        .into_iter()
+        .map(|(_, token)| token)
+        .collect()
+}
+
+// 검색어 파싱: 따옴표로 묶인 부분은 구문, 끝이 *인 단어는 접두어
+pub fn parse_query(query: &str) -> Vec<QueryTerm> {
+    let mut terms = Vec::new();
+
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            let tokens = tokenize(part);
+            match tokens.len() {
+                0 => {}
+                1 => terms.push(QueryTerm::Word(tokens[0].clone())),
+                _ => terms.push(QueryTerm::Phrase(tokens)),
+            }
+            continue;
+        }
+
+        for word in part.split_whitespace() {
+            if let Some(prefix) = word.strip_suffix('*') {
+                let tokens = tokenize(prefix);
+                if tokens.len() == 1 {
+                    terms.push(QueryTerm::Prefix(tokens[0].clone()));
+                    continue;
+                }
+            }
+            match tokenize(word).as_slice() {
+                [] => {}
+                [token] => terms.push(QueryTerm::Word(token.clone())),
+                tokens => terms.push(QueryTerm::Phrase(tokens.to_vec())),
+            }
+        }
+    }
+
+    terms
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        SearchIndex {
+            tokenizer: TOKENIZER_VERSION,
+            ..SearchIndex::default()
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).map_err(|source| Error::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        let index: SearchIndex =
+            serde_json::from_str(&content).map_err(|source| Error::Corrupt {
+                path: path.to_path_buf(),
+                source,
+            })?;
+
+        // 다른 규칙으로 만든 색인은 토큰이 맞지 않으므로 버리고 새로 색인
+        if index.tokenizer != TOKENIZER_VERSION {
+            return Ok(SearchIndex::new());
+        }
+        Ok(index)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string(&self).map_err(Error::Serialize)?;
+
+        storage::write_atomic(path, content)
+    }
+
+    // 문서 색인 (내용이 바뀌지 않았으면 건너뜀). 변경되었으면 true 반환
+    pub fn update_document(&mut self, id: Uuid, text: &str) -> bool {
+        let hash = content_hash(text);
+        if self.docs.get(&id).is_some_and(|doc| doc.hash == hash) {
+            return false;
+        }
+
+        self.remove_document(&id);
+
+        let tokens = positioned_tokens(text, true);
+        let length = tokens.last().map(|(position, _)| position + 1).unwrap_or(0);
+        for (position, token) in tokens {
+            self.postings
+                .entry(token)
+                .or_default()
+                .entry(id)
+                .or_default()
+                .push(position);
+        }
+
+        self.docs.insert(id, DocInfo { length, hash });
+        true
+    }
+
+    pub fn remove_document(&mut self, id: &Uuid) -> bool {
+        if self.docs.remove(id).is_none() {
+            return false;
+        }
+
+        self.postings.retain(|_, docs| {
+            docs.remove(id);
+            !docs.is_empty()
+        });
+        true
+    }
+
+    // 주어진 노트 목록에 없는 문서 제거. 제거된 개수 반환
+    pub fn retain_documents(&mut self, ids: &HashSet<Uuid>) -> usize {
+        let stale: Vec<Uuid> = self
+            .docs
+            .keys()
+            .filter(|id| !ids.contains(id))
+            .copied()
+            .collect();
+
+        for id in &stale {
+            self.remove_document(id);
+        }
+        stale.len()
+    }
+
+    // BM25로 순위를 매긴 검색 (모든 검색어가 포함된 문서만 반환)
+    pub fn search(&self, terms: &[QueryTerm]) -> Vec<(Uuid, f64)> {
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut candidates: Option<HashSet<Uuid>> = None;
+        for term in terms {
+            let matched: HashSet<Uuid> = self.term_frequencies(term).into_keys().collect();
+            candidates = Some(match candidates {
+                Some(previous) => previous.intersection(&matched).copied().collect(),
+                None => matched,
+            });
+        }
+
+        let mut results: Vec<(Uuid, f64)> = self
+            .score(terms, &candidates.unwrap_or_default())
+            .into_iter()
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        results
+    }
+
+    // 주어진 문서들의 BM25 점수 (검색어가 없는 문서는 0점)
+    pub fn score(&self, terms: &[QueryTerm], ids: &HashSet<Uuid>) -> HashMap<Uuid, f64> {
+        let mut scores: HashMap<Uuid, f64> = ids.iter().map(|id| (*id, 0.0)).collect();
+        if self.docs.is_empty() {
+            return scores;
+        }
+
+        let doc_count = self.docs.len() as f64;
+        let avg_len = self.docs.values().map(|d| d.length as f64).sum::<f64>() / doc_count;
+
+        for term in terms {
+            // 이 검색어에 대한 문서별 빈도
+            let frequencies = self.term_frequencies(term);
+
+            let df = frequencies.len() as f64;
+            let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+
+            for (id, tf) in &frequencies {
+                let Some(score) = scores.get_mut(id) else {
+                    continue;
+                };
+                let length = self.docs.get(id).map(|d| d.length as f64).unwrap_or(0.0);
+                let tf = *tf as f64;
+                let norm = tf + BM25_K1 * (1.0 - BM25_B + BM25_B * length / avg_len.max(1.0));
+                *score += idf * tf * (BM25_K1 + 1.0) / norm;
+            }
+        }
+
+        scores
+    }
+
+    // 검색어가 포함된 문서와 문서별 등장 횟수
+    pub fn term_frequencies(&self, term: &QueryTerm) -> HashMap<Uuid, usize> {
+        let mut frequencies = HashMap::new();
+
+        match term {
+            QueryTerm::Word(word) => {
+                if let Some(docs) = self.postings.get(word) {
+                    for (id, positions) in docs {
+                        frequencies.insert(*id, positions.len());
+                    }
+                }
+            }
+            QueryTerm::Prefix(prefix) => {
+                for (token, docs) in &self.postings {
+                    if token.starts_with(prefix.as_str()) {
+                        for (id, positions) in docs {
+                            *frequencies.entry(*id).or_default() += positions.len();
+                        }
+                    }
+                }
+            }
+            QueryTerm::Phrase(words) => {
+                let Some(first) = self.postings.get(&words[0]) else {
+                    return frequencies;
+                };
+
+                for (id, starts) in first {
+                    let count = starts
+                        .iter()
+                        .filter(|&&start| {
+                            words.iter().enumerate().skip(1).all(|(offset, word)| {
+                                self.postings
+                                    .get(word)
+                                    .and_then(|docs| docs.get(id))
+                                    .is_some_and(|positions| {
+                                        positions.contains(&(start + offset as u32))
+                                    })
+                            })
+                        })
+                        .count();
+                    if count > 0 {
+                        frequencies.insert(*id, count);
+                    }
+                }
+            }
+        }
+
+        frequencies
+    }
+}
+
+// 검색어가 처음 등장하는 본문 줄 찾기
+// (색인과 같은 규칙으로 맞추도록 줄마다 작은 색인을 만들어 확인)
+pub fn find_snippet(content: &str, terms: &[QueryTerm]) -> Option<Snippet> {
+    let (idx, line) = content.lines().enumerate().find(|(_, line)| {
+        let mut index = SearchIndex::new();
+        index.update_document(Uuid::nil(), line);
+        terms
+            .iter()
+            .any(|term| !index.term_frequencies(term).is_empty())
+    })?;
+    Some(Snippet {
+        line_number: idx + 1,
+        text: line.trim().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_lowercases_and_splits() {
+        assert_eq!(
+            tokenize("Rust-lang: 빠른 언어, v2.0!"),
+            vec!["rust", "lang", "빠른", "른", "언어", "어", "v2", "0"]
+        );
+        assert_eq!(tokenize("러스트를"), vec!["러스", "스트", "트를", "를"]);
+        assert_eq!(tokenize("rust러스트"), vec!["rust", "러스", "스트", "트"]);
+    }
+
+    #[test]
+    fn test_korean_word_matches_with_particle_attached() {
+        let mut index = SearchIndex::new();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        index.update_document(a, "오늘은 러스트를 배웠다");
+        index.update_document(b, "스트레칭을 했다");
+
+        let ids: Vec<Uuid> = index
+            .search(&parse_query("러스트"))
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids, vec![a]);
+
+        // 한 글자 검색어도 단어 중간에서 찾음
+        assert_eq!(index.search(&parse_query("웠")).len(), 1);
+        assert_eq!(index.search(&parse_query("러스*")).len(), 1);
+        assert!(index.search(&parse_query(r#""러스트 배웠다""#)).is_empty());
+        assert_eq!(index.search(&parse_query(r#""러스트를 배웠다""#)).len(), 1);
+    }
+
+    #[test]
+    fn test_latin_words_match_whole_tokens_only() {
+        let mut index = SearchIndex::new();
+        let a = Uuid::new_v4();
+        index.update_document(a, "in rust we trust");
+        let b = Uuid::new_v4();
+        index.update_document(b, "we trust nothing");
+
+        assert_eq!(index.search(&parse_query("rust")).len(), 1);
+        assert_eq!(index.search(&parse_query("draft")).len(), 0);
+    }
+
+    #[test]
+    fn test_parse_query_phrases_and_prefixes() {
+        assert_eq!(
+            parse_query(r#"rust "team meeting" prog*"#),
+            vec![
+                QueryTerm::Word("rust".to_string()),
+                QueryTerm::Phrase(vec!["team".to_string(), "meeting".to_string()]),
+                QueryTerm::Prefix("prog".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bm25_ranks_more_relevant_document_first() {
+        let mut index = SearchIndex::new();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let c = Uuid::new_v4();
+        index.update_document(a, "rust is fast. rust is safe. rust rust");
+        index.update_document(
+            b,
+            "a long note that mentions rust once among many other words here",
+        );
+        index.update_document(c, "python only");
+
+        let results = index.search(&parse_query("rust"));
+        let ids: Vec<Uuid> = results.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![a, b]);
+    }
+
+    #[test]
+    fn test_phrase_query_requires_adjacent_words() {
+        let mut index = SearchIndex::new();
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        index.update_document(a, "weekly team meeting notes");
+        index.update_document(b, "meeting with the team");
+
+        let results = index.search(&parse_query(r#""team meeting""#));
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].0, a);
+    }
+
+    #[test]
+    fn test_update_and_remove_document() {
+        let mut index = SearchIndex::new();
+        let a = Uuid::new_v4();
+        assert!(index.update_document(a, "first version"));
+        assert!(!index.update_document(a, "first version"));
+        assert!(index.update_document(a, "second version"));
+
+        assert!(index.search(&parse_query("first")).is_empty());
+        assert_eq!(index.search(&parse_query("second")).len(), 1);
+
+        assert_eq!(index.retain_documents(&HashSet::new()), 1);
+        assert!(index.postings.is_empty());
+    }
+
+    #[test]
+    fn test_index_from_older_tokenizer_is_discarded() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("search_index.json");
+        let mut index = SearchIndex::new();
+        index.update_document(Uuid::new_v4(), "러스트를 배웠다");
+        index.tokenizer = 0;
+        index.save(&path).unwrap();
+
+        let loaded = SearchIndex::load(&path).unwrap();
+        assert_eq!(loaded.tokenizer, TOKENIZER_VERSION);
+        assert!(loaded.docs.is_empty());
+    }
+
+    #[test]
+    fn test_find_snippet_returns_matching_line() {
+        let content = "# Title\n\nnothing here\nWe use Rust daily.\n";
+        let snippet = find_snippet(content, &parse_query("rust")).unwrap();
+        assert_eq!(snippet.line_number, 4);
+        assert_eq!(snippet.text, "We use Rust daily.");
+    }
+}
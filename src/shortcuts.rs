@@ -1,64 +1,80 @@
+use crate::error::{Error, Result};
+use crate::migrate::{self, Migration, SHORTCUTS_VERSION};
 use crate::note::{LinkTarget, Shortcut};
+use crate::storage;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::fs;
+use std::fmt::Write;
 use std::path::Path;
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ShortcutsRegistry {
+    pub version: u32, // 파일 형식 버전 (migrate.rs 참고)
     // note_id -> shortcuts mapping
     pub shortcuts: HashMap<Uuid, HashMap<String, Shortcut>>,
 }
 
+impl Default for ShortcutsRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ShortcutsRegistry {
     pub fn new() -> Self {
         ShortcutsRegistry {
+            version: SHORTCUTS_VERSION,
             shortcuts: HashMap::new(),
         }
     }
 
-    pub fn load(path: &Path) -> Result<Self, String> {
-        let content =
-            fs::read_to_string(path).map_err(|e| format!("Shortcuts 파일 읽기 실패: {}", e))?;
-
-        serde_json::from_str(&content).map_err(|e| format!("Shortcuts 파일 파싱 실패: {}", e))
+    // 이전 버전 파일이면 백업 후 현재 버전으로 옮겨 저장하고 그 기록을 함께 반환
+    pub fn load(path: &Path) -> Result<(Self, Option<Migration>)> {
+        migrate::load_shortcuts(path)
     }
 
-    pub fn save(&self, path: &Path) -> Result<(), String> {
-        let content =
-            serde_json::to_string_pretty(&self).map_err(|e| format!("JSON 직렬화 실패: {}", e))?;
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(&self).map_err(Error::Serialize)?;
 
-        fs::write(path, content).map_err(|e| format!("Shortcuts 파일 저장 실패: {}", e))
+        storage::write_atomic(path, content)
     }
 
     pub fn get_shortcuts(&self, note_id: &Uuid) -> Option<&HashMap<String, Shortcut>> {
         self.shortcuts.get(note_id)
     }
 
-    #[allow(dead_code)]
     pub fn add_shortcut(&mut self, note_id: Uuid, alias: String, shortcut: Shortcut) {
         self.shortcuts
             .entry(note_id)
-            .or_insert_with(HashMap::new)
+            .or_default()
             .insert(alias, shortcut);
     }
 
+    // 단축어 하나 제거 (노트에 단축어가 남지 않으면 항목도 제거)
+    pub fn remove_shortcut(&mut self, note_id: &Uuid, alias: &str) -> Option<Shortcut> {
+        let shortcuts = self.shortcuts.get_mut(note_id)?;
+        let removed = shortcuts.remove(alias);
+        if shortcuts.is_empty() {
+            self.shortcuts.remove(note_id);
+        }
+        removed
+    }
+
     pub fn remove_shortcuts(&mut self, note_id: &Uuid) -> Option<HashMap<String, Shortcut>> {
         self.shortcuts.remove(note_id)
     }
 
     // 특정 노트를 참조하는 모든 shortcuts 찾기
-    #[allow(dead_code)]
     pub fn find_references_to_note(&self, target_id: &Uuid) -> Vec<(Uuid, String, &Shortcut)> {
         let mut references = Vec::new();
 
         for (note_id, shortcuts_map) in &self.shortcuts {
             for (alias, shortcut) in shortcuts_map {
-                if let LinkTarget::Note { id } = &shortcut.target {
-                    if id == target_id {
-                        references.push((*note_id, alias.clone(), shortcut));
-                    }
+                if let LinkTarget::Note { id } = &shortcut.target
+                    && id == target_id
+                {
+                    references.push((*note_id, alias.clone(), shortcut));
                 }
             }
         }
@@ -66,3 +82,192 @@ impl ShortcutsRegistry {
         references
     }
 }
+
+// 단축어 이름 검사: 문자, 숫자, '-', '_', '.'만 사용 가능
+pub fn validate_alias(alias: &str) -> Result<()> {
+    if alias.is_empty() {
+        return Err(Error::EmptyAlias);
+    }
+    if !alias.chars().all(is_alias_char) {
+        return Err(Error::InvalidAlias(alias.to_string()));
+    }
+    Ok(())
+}
+
+fn is_alias_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+// 본문에서 찾은 정의되지 않은 단축어
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnknownAlias {
+    pub alias: String,
+    pub line_number: usize, // 1부터 시작 (expand_aliases는 받은 텍스트 기준, NoteApp::expand_content는 파일 기준)
+}
+
+#[derive(Debug, Clone)]
+pub struct ExpandedContent {
+    pub content: String,
+    pub unknown: Vec<UnknownAlias>,
+}
+
+// 본문의 {alias}를 대상 링크로 바꿈 (코드 블록과 인라인 코드는 건드리지 않음)
+// resolve_note는 노트 대상의 (제목, 파일명)을 돌려주며, 없으면 알 수 없는 단축어로 처리
+pub fn expand_aliases(
+    content: &str,
+    shortcuts: Option<&HashMap<String, Shortcut>>,
+    resolve_note: impl Fn(&Uuid) -> Option<(String, String)>,
+) -> ExpandedContent {
+    let mut output = String::with_capacity(content.len());
+    let mut unknown = Vec::new();
+    let mut fence: Option<&str> = None;
+
+    for (idx, line) in content.split_inclusive('\n').enumerate() {
+        // 펜스 코드 블록 (``` 또는 ~~~)
+        let trimmed = line.trim_start();
+        let marker = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m));
+        match (fence, marker) {
+            (None, Some(m)) => fence = Some(m),
+            (Some(open), Some(m)) if open == m => fence = None,
+            _ => {}
+        }
+        if fence.is_some() || marker.is_some() {
+            output.push_str(line);
+            continue;
+        }
+
+        let mut in_code = false;
+        let mut rest = line;
+        while let Some(pos) = rest.find(['`', '{']) {
+            output.push_str(&rest[..pos]);
+            rest = &rest[pos..];
+
+            if rest.starts_with('`') {
+                in_code = !in_code;
+                output.push('`');
+                rest = &rest[1..];
+                continue;
+            }
+
+            // '{' 다음부터 '}'까지가 올바른 단축어 이름인지 확인
+            let alias = rest[1..]
+                .find('}')
+                .map(|end| &rest[1..end + 1])
+                .filter(|alias| !alias.is_empty() && alias.chars().all(is_alias_char));
+            let Some(alias) = alias.filter(|_| !in_code) else {
+                output.push('{');
+                rest = &rest[1..];
+                continue;
+            };
+
+            let expanded =
+                shortcuts
+                    .and_then(|s| s.get(alias))
+                    .and_then(|shortcut| match &shortcut.target {
+                        LinkTarget::Url { url } => Some(format!("[{}]({})", alias, url)),
+                        LinkTarget::File { path } => {
+                            Some(format!("[{}]({})", alias, path.display()))
+                        }
+                        LinkTarget::Note { id } => resolve_note(id)
+                            .map(|(title, filename)| format!("[{}]({})", title, filename)),
+                    });
+
+            match expanded {
+                Some(link) => output.push_str(&link),
+                None => {
+                    let _ = write!(output, "{{{}}}", alias);
+                    unknown.push(UnknownAlias {
+                        alias: alias.to_string(),
+                        line_number: idx + 1,
+                    });
+                }
+            }
+            rest = &rest[alias.len() + 2..];
+        }
+        output.push_str(rest);
+    }
+
+    ExpandedContent {
+        content: output,
+        unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registry() -> HashMap<String, Shortcut> {
+        let mut shortcuts = HashMap::new();
+        for (alias, target) in [
+            ("docs", "https://doc.rust-lang.org"),
+            ("plan", "~/work/plan.pdf"),
+        ] {
+            shortcuts.insert(
+                alias.to_string(),
+                Shortcut {
+                    alias: alias.to_string(),
+                    target: LinkTarget::parse(target).unwrap(),
+                    description: None,
+                },
+            );
+        }
+        shortcuts
+    }
+
+    #[test]
+    fn test_expand_aliases_replaces_known_and_reports_unknown() {
+        let shortcuts = registry();
+        let content = "See {docs} and {plan}.\nAlso {missing}.\nNot an alias: {a b}\n";
+        let expanded = expand_aliases(content, Some(&shortcuts), |_| None);
+
+        assert_eq!(
+            expanded.content,
+            "See [docs](https://doc.rust-lang.org) and [plan](~/work/plan.pdf).\nAlso {missing}.\nNot an alias: {a b}\n"
+        );
+        assert_eq!(
+            expanded.unknown,
+            vec![UnknownAlias {
+                alias: "missing".to_string(),
+                line_number: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_expand_aliases_skips_code() {
+        let shortcuts = registry();
+        let content = "`{docs}` {docs}\n```rust\nlet x = {docs};\n```\n{docs}";
+        let expanded = expand_aliases(content, Some(&shortcuts), |_| None);
+
+        assert_eq!(
+            expanded.content,
+            "`{docs}` [docs](https://doc.rust-lang.org)\n```rust\nlet x = {docs};\n```\n[docs](https://doc.rust-lang.org)"
+        );
+        assert!(expanded.unknown.is_empty());
+    }
+
+    #[test]
+    fn test_expand_note_alias_uses_resolver() {
+        let id = Uuid::new_v4();
+        let mut shortcuts = HashMap::new();
+        shortcuts.insert(
+            "prev".to_string(),
+            Shortcut {
+                alias: "prev".to_string(),
+                target: LinkTarget::Note { id },
+                description: None,
+            },
+        );
+
+        let expanded = expand_aliases("{prev}", Some(&shortcuts), |target| {
+            (*target == id).then(|| ("Previous".to_string(), "prev.md".to_string()))
+        });
+        assert_eq!(expanded.content, "[Previous](prev.md)");
+
+        // 대상 노트가 없으면 그대로 두고 보고
+        let expanded = expand_aliases("{prev}", Some(&shortcuts), |_| None);
+        assert_eq!(expanded.content, "{prev}");
+        assert_eq!(expanded.unknown.len(), 1);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::error::{Error, Result};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// 노트 디렉토리 잠금 파일 (숨김 파일이라 스캔/감시에서 제외됨)
+pub const LOCK_FILE: &str = ".md_filer.lock";
+
+// 같은 폴더의 숨김 임시 파일 (a.md → .a.md.tmp, .index.json → .index.json.tmp)
+pub fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let temp_name = if name.starts_with('.') {
+        format!("{}.tmp", name)
+    } else {
+        format!(".{}.tmp", name)
+    };
+    path.with_file_name(temp_name)
+}
+
+// 숨김 임시 파일의 원래 파일 (.a.md.tmp → a.md). 임시 파일 이름이 아니면 None
+pub fn temp_original(temp: &Path) -> Option<PathBuf> {
+    let name = temp.file_name()?.to_str()?;
+    let original = name.strip_suffix(".tmp")?.strip_prefix('.')?;
+    (!original.is_empty()).then(|| temp.with_file_name(original))
+}
+
+// 임시 파일에 쓰고 fsync한 뒤 rename으로 교체
+// 중간에 실패하거나 중단되어도 원본은 이전 내용 그대로 남음
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    let temp = temp_path(path);
+    write_and_rename(path, &temp, contents.as_ref()).map_err(|source| {
+        let _ = fs::remove_file(&temp);
+        Error::Write {
+            path: path.to_path_buf(),
+            source,
+        }
+    })
+}
+
+fn write_and_rename(path: &Path, temp: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = File::create(temp)?;
+    file.write_all(contents)?;
+    // 노트 파일의 권한은 유지
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(temp, path)?;
+    sync_parent(path)
+}
+
+// rename이 디스크에 기록되도록 폴더도 fsync (Windows에서는 폴더를 열 수 없음)
+fn sync_parent(path: &Path) -> io::Result<()> {
+    if cfg!(unix) {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+// 이전 실행이 남긴 임시 파일 정리
+// 원본이 없거나 깨졌고 임시 파일이 올바르면 임시 파일로 복구, 아니면 임시 파일 삭제
+// 반환값: 임시 파일이 없으면 None, 복구했으면 Some(true), 삭제했으면 Some(false)
+pub fn recover_temp_file(path: &Path, is_valid: impl Fn(&[u8]) -> bool) -> Result<Option<bool>> {
+    let temp = temp_path(path);
+    if !temp.exists() {
+        return Ok(None);
+    }
+
+    let temp_contents = fs::read(&temp).map_err(|source| Error::Read {
+        path: temp.clone(),
+        source,
+    })?;
+    let original_ok = fs::read(path).is_ok_and(|contents| is_valid(&contents));
+
+    if !original_ok && is_valid(&temp_contents) {
+        fs::rename(&temp, path)
+            .and_then(|_| sync_parent(path))
+            .map_err(|source| Error::Write {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        return Ok(Some(true));
+    }
+
+    fs::remove_file(&temp).map_err(|source| Error::Write { path: temp, source })?;
+    Ok(Some(false))
+}
+
+// 노트 디렉토리 단위의 advisory 잠금 (드롭되면 해제)
+// 같은 디렉토리를 두 프로세스가 동시에 고치지 못하게 함
+#[derive(Debug)]
+pub struct DirLock {
+    _file: File,
+}
+
+impl DirLock {
+    pub fn acquire(dir: &Path) -> Result<Self> {
+        let path = dir.join(LOCK_FILE);
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|source| Error::Lock {
+                path: path.clone(),
+                source,
+            })?;
+
+        match file.try_lock() {
+            Ok(()) => Ok(DirLock { _file: file }),
+            Err(TryLockError::WouldBlock) => Err(Error::Locked(dir.to_path_buf())),
+            Err(TryLockError::Error(source)) => Err(Error::Lock { path, source }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_temp_path_is_hidden() {
+        assert_eq!(
+            temp_path(Path::new("notes/a.md")),
+            PathBuf::from("notes/.a.md.tmp")
+        );
+        assert_eq!(
+            temp_path(Path::new("notes/.index.json")),
+            PathBuf::from("notes/.index.json.tmp")
+        );
+        assert_eq!(
+            temp_original(Path::new("notes/.a.md.tmp")),
+            Some(PathBuf::from("notes/a.md"))
+        );
+        assert_eq!(temp_original(Path::new("notes/a.md")), None);
+    }
+
+    #[test]
+    fn test_recover_temp_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".index.json");
+        let is_json = |bytes: &[u8]| serde_json::from_slice::<serde_json::Value>(bytes).is_ok();
+
+        // 원본이 깨졌으면 완성된 임시 파일로 복구
+        fs::write(&path, "{\"trunc").unwrap();
+        fs::write(temp_path(&path), "{\"ok\": true}").unwrap();
+        assert_eq!(recover_temp_file(&path, is_json).unwrap(), Some(true));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"ok\": true}");
+
+        // 원본이 멀쩡하면 반쯤 쓴 임시 파일은 버림
+        fs::write(temp_path(&path), "{\"half").unwrap();
+        assert_eq!(recover_temp_file(&path, is_json).unwrap(), Some(false));
+        assert!(!temp_path(&path).exists());
+        assert_eq!(recover_temp_file(&path, is_json).unwrap(), None);
+    }
+
+    #[test]
+    fn test_dir_lock_is_exclusive() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let lock = DirLock::acquire(dir.path()).unwrap();
+        assert!(matches!(
+            DirLock::acquire(dir.path()),
+            Err(Error::Locked(_))
+        ));
+        drop(lock);
+        assert!(DirLock::acquire(dir.path()).is_ok());
+    }
+}
@@ -0,0 +1,81 @@
+use chrono::{DateTime, Local};
+use uuid::Uuid;
+
+// 노트 템플릿 폴더 (숨김 폴더라 스캔/감시에서 제외됨)
+pub const TEMPLATES_DIR: &str = ".templates";
+
+// 파일명 길이 제한 (확장자 제외, 글자 수)
+const MAX_SLUG_CHARS: usize = 80;
+
+// 제목으로 파일명 만들기: 소문자, 공백은 '-', 글자/숫자 외의 문자는 제거
+// 한글 등 유니코드 글자는 그대로 유지하고, 남는 것이 없으면 "untitled"
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for ch in title.chars().flat_map(char::to_lowercase) {
+        if ch.is_alphanumeric() {
+            slug.push(ch);
+        } else if (ch.is_whitespace() || ch == '-' || ch == '_') && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    let slug: String = slug
+        .trim_matches('-')
+        .chars()
+        .take(MAX_SLUG_CHARS)
+        .collect();
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+// 템플릿 변수: {{title}}, {{date}} (YYYY-MM-DD), {{time}} (HH:MM), {{uuid}}
+pub struct TemplateVars<'a> {
+    pub title: &'a str,
+    pub id: Uuid,
+    pub now: DateTime<Local>,
+}
+
+// 알려진 변수만 바꾸고 나머지 {{...}}는 그대로 둠
+pub fn render(template: &str, vars: &TemplateVars) -> String {
+    template
+        .replace("{{title}}", vars.title)
+        .replace("{{date}}", &vars.now.format("%Y-%m-%d").to_string())
+        .replace("{{time}}", &vars.now.format("%H:%M").to_string())
+        .replace("{{uuid}}", &vars.id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello, World!"), "hello-world");
+        assert_eq!(
+            slugify("  Rust  ownership -- notes "),
+            "rust-ownership-notes"
+        );
+        assert_eq!(slugify("회의 노트 2024/05"), "회의-노트-202405");
+        assert_eq!(slugify("???"), "untitled");
+        assert_eq!(slugify(&"a".repeat(200)).len(), MAX_SLUG_CHARS);
+    }
+
+    #[test]
+    fn test_render_replaces_known_variables() {
+        let id = Uuid::new_v4();
+        let vars = TemplateVars {
+            title: "Weekly",
+            id,
+            now: Local.with_ymd_and_hms(2024, 5, 3, 9, 30, 0).unwrap(),
+        };
+        assert_eq!(
+            render("# {{title}}\n{{date}} {{time}} {{uuid}} {{other}}", &vars),
+            format!("# Weekly\n2024-05-03 09:30 {} {{{{other}}}}", id)
+        );
+    }
+}
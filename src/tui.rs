@@ -0,0 +1,847 @@
+use crate::{markdown, open_in_editor};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
+};
+use md_filer::app::NoteApp;
+use md_filer::links::Link;
+use md_filer::watch::NoteWatcher;
+use ratatui::DefaultTerminal;
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap};
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+use uuid::Uuid;
+
+// 키 입력을 받는 영역
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Sidebar,
+    List,
+    Filter,
+    Links,
+}
+
+// 사이드바 항목: 전체, 폴더(@태그), 일반 태그
+#[derive(Clone, PartialEq, Eq)]
+enum SidebarItem {
+    All,
+    Folder(String),
+    Tag(String),
+}
+
+struct Tui {
+    app: NoteApp,
+    watcher: Option<NoteWatcher>,
+    focus: Focus,
+    filter: String,
+    sidebar: Vec<(SidebarItem, usize)>,
+    sidebar_state: ListState,
+    // 필터와 사이드바 선택에 맞는 노트 (표시 순서)
+    notes: Vec<Uuid>,
+    list_state: ListState,
+    preview_scroll: u16,
+    // 선택한 노트에서 나가는 링크
+    links: Vec<Link>,
+    links_state: ListState,
+    // 링크를 따라가기 전에 보던 노트 (b로 돌아감)
+    history: Vec<Uuid>,
+    status: String,
+    quit: bool,
+}
+
+// 전체 화면 모드 실행 (종료하면 터미널을 원래대로 되돌림)
+pub fn run(notes_dir: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    // 화면이 깨지지 않도록 진행 메시지는 출력하지 않음
+    let app = NoteApp::new(notes_dir)?;
+    let watcher = NoteWatcher::new(app.list_watched_folders()).ok();
+    let mut tui = Tui::new(app, watcher);
+
+    let mut terminal = ratatui::init();
+    let result = tui.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+impl Tui {
+    fn new(app: NoteApp, watcher: Option<NoteWatcher>) -> Self {
+        let mut tui = Tui {
+            app,
+            watcher,
+            focus: Focus::List,
+            filter: String::new(),
+            sidebar: Vec::new(),
+            sidebar_state: ListState::default().with_selected(Some(0)),
+            notes: Vec::new(),
+            list_state: ListState::default(),
+            preview_scroll: 0,
+            links: Vec::new(),
+            links_state: ListState::default(),
+            history: Vec::new(),
+            status: String::new(),
+            quit: false,
+        };
+        tui.rebuild_sidebar();
+        tui.apply_filter();
+        tui
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<(), Box<dyn std::error::Error>> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            // 입력이 없으면 파일 변경을 반영
+            if !event::poll(Duration::from_millis(250))? {
+                self.apply_file_changes();
+                continue;
+            }
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+            {
+                self.handle_key(key, terminal)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_file_changes(&mut self) {
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+        let paths = watcher.pending();
+        if paths.is_empty() {
+            return;
+        }
+        match self.app.refresh_paths(&paths) {
+            Ok(changes) if !changes.is_empty() => {
+                self.rebuild_sidebar();
+                self.apply_filter();
+                self.status = format!("🔄 {} 개 변경 반영", changes.len());
+            }
+            Ok(_) => {}
+            Err(e) => self.status = format!("❌ {}", e),
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return Ok(());
+        }
+
+        match self.focus {
+            Focus::Filter => self.handle_filter_key(key),
+            Focus::Links => self.handle_links_key(key),
+            Focus::Sidebar | Focus::List => match key.code {
+                KeyCode::Char('q') => self.quit = true,
+                KeyCode::Char('/') => self.focus = Focus::Filter,
+                KeyCode::Esc if !self.filter.is_empty() => {
+                    self.filter.clear();
+                    self.apply_filter();
+                }
+                KeyCode::Esc => self.quit = true,
+                KeyCode::Tab | KeyCode::BackTab => {
+                    self.focus = match self.focus {
+                        Focus::Sidebar => Focus::List,
+                        _ => Focus::Sidebar,
+                    };
+                }
+                KeyCode::Char('e') => self.edit_selected(terminal)?,
+                KeyCode::Char('r') => self.refresh(),
+                KeyCode::Char('f') | KeyCode::Enter if !self.links.is_empty() => {
+                    self.links_state.select(Some(0));
+                    self.focus = Focus::Links;
+                }
+                KeyCode::Char('b') | KeyCode::Backspace => self.go_back(),
+                KeyCode::PageDown | KeyCode::Char('J') => {
+                    self.preview_scroll = self.preview_scroll.saturating_add(10)
+                }
+                KeyCode::PageUp | KeyCode::Char('K') => {
+                    self.preview_scroll = self.preview_scroll.saturating_sub(10)
+                }
+                code => self.move_selection(code),
+            },
+        }
+        Ok(())
+    }
+
+    // 입력할 때마다 바로 다시 검색
+    fn handle_filter_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter | KeyCode::Down | KeyCode::Tab => self.focus = Focus::List,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.focus = Focus::List;
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.filter.clear()
+            }
+            KeyCode::Char(c) => self.filter.push(c),
+            _ => return,
+        }
+        self.apply_filter();
+    }
+
+    fn handle_links_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('f') | KeyCode::Char('q') => self.focus = Focus::List,
+            KeyCode::Up | KeyCode::Char('k') => self.links_state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => self.links_state.select_next(),
+            KeyCode::Enter => {
+                let target = self
+                    .links_state
+                    .selected()
+                    .and_then(|index| self.links.get(index))
+                    .map(|link| (link.raw.target.clone(), link.resolved));
+                match target {
+                    Some((_, Some(id))) => {
+                        self.focus = Focus::List;
+                        self.follow(id);
+                    }
+                    Some((target, None)) => {
+                        self.status = format!("❌ 연결되지 않은 링크: {}", target)
+                    }
+                    None => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn move_selection(&mut self, code: KeyCode) {
+        let (state, len) = match self.focus {
+            Focus::Sidebar => (&mut self.sidebar_state, self.sidebar.len()),
+            _ => (&mut self.list_state, self.notes.len()),
+        };
+        if len == 0 {
+            return;
+        }
+        let current = state.selected().unwrap_or(0);
+        let next = match code {
+            KeyCode::Up | KeyCode::Char('k') => current.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => (current + 1).min(len - 1),
+            KeyCode::Home | KeyCode::Char('g') => 0,
+            KeyCode::End | KeyCode::Char('G') => len - 1,
+            _ => return,
+        };
+
+        if self.focus == Focus::Sidebar {
+            self.sidebar_state.select(Some(next));
+            self.apply_filter();
+        } else {
+            self.select(next);
+        }
+    }
+
+    fn selected_note(&self) -> Option<Uuid> {
+        self.list_state
+            .selected()
+            .and_then(|index| self.notes.get(index))
+            .copied()
+    }
+
+    fn select(&mut self, index: usize) {
+        self.list_state
+            .select((!self.notes.is_empty()).then(|| index.min(self.notes.len() - 1)));
+        self.preview_scroll = 0;
+        self.links = self
+            .selected_note()
+            .map(|id| self.app.outgoing_links(&id))
+            .unwrap_or_default();
+    }
+
+    // 사이드바 항목과 노트 수 (전체 → 폴더 → 태그)
+    fn rebuild_sidebar(&mut self) {
+        let selected = self
+            .sidebar_state
+            .selected()
+            .and_then(|index| self.sidebar.get(index))
+            .map(|(item, _)| item.clone());
+
+        let count = |tag: &str| {
+            self.app
+                .notes
+                .values()
+                .filter(|note| note.tags.iter().any(|t| t == tag))
+                .count()
+        };
+        let mut sidebar = vec![(SidebarItem::All, self.app.notes.len())];
+        for folder in self.app.get_folders() {
+            let n = count(&folder);
+            sidebar.push((SidebarItem::Folder(folder), n));
+        }
+        for tag in self.app.get_all_tags() {
+            if !tag.starts_with('@') {
+                let n = count(&tag);
+                sidebar.push((SidebarItem::Tag(tag), n));
+            }
+        }
+        self.sidebar = sidebar;
+
+        let index = selected
+            .and_then(|item| self.sidebar.iter().position(|(i, _)| *i == item))
+            .unwrap_or(0);
+        self.sidebar_state.select(Some(index));
+    }
+
+    // 검색어와 사이드바 선택으로 목록을 다시 만듦 (선택한 노트는 가능하면 유지)
+    fn apply_filter(&mut self) {
+        let selected = self.selected_note();
+        let query = self.filter.trim();
+
+        let ids: Vec<Uuid> = if query.is_empty() {
+            self.app.list_notes().iter().map(|(id, _)| **id).collect()
+        } else {
+            match self.search(query) {
+                Ok(ids) => ids,
+                // 입력 중인 불완전한 검색어는 이전 결과 유지
+                Err(e) => {
+                    self.status = format!("⚠️  {}", e);
+                    return;
+                }
+            }
+        };
+        self.status.clear();
+
+        let tag = match self
+            .sidebar_state
+            .selected()
+            .and_then(|index| self.sidebar.get(index))
+        {
+            Some((SidebarItem::Folder(tag) | SidebarItem::Tag(tag), _)) => Some(tag.clone()),
+            _ => None,
+        };
+        self.notes = ids
+            .into_iter()
+            .filter(|id| {
+                tag.as_ref().is_none_or(|tag| {
+                    self.app
+                        .get_note(id)
+                        .is_some_and(|note| note.tags.contains(tag))
+                })
+            })
+            .collect();
+
+        let index = selected
+            .and_then(|id| self.notes.iter().position(|n| *n == id))
+            .unwrap_or(0);
+        self.select(index);
+    }
+
+    // 마지막 단어는 입력 중일 수 있으므로 먼저 접두어 검색으로 시도
+    fn search(&self, query: &str) -> md_filer::error::Result<Vec<Uuid>> {
+        let last_word = query.rsplit(' ').next().unwrap_or_default();
+        let typing =
+            query.ends_with(|c: char| c.is_alphanumeric()) && !last_word.contains([':', '"', '*']);
+        let hits = if typing {
+            self.app
+                .search(&format!("{}*", query))
+                .or_else(|_| self.app.search(query))?
+        } else {
+            self.app.search(query)?
+        };
+        Ok(hits.into_iter().map(|hit| hit.id).collect())
+    }
+
+    fn refresh(&mut self) {
+        match self.app.load_notes() {
+            Ok(()) => {
+                self.rebuild_sidebar();
+                self.apply_filter();
+                self.status = format!("✅ 새로고침 완료! ({} 개 노트)", self.app.notes.len());
+            }
+            Err(e) => self.status = format!("❌ {}", e),
+        }
+    }
+
+    // 화면을 잠시 내려놓고 편집기를 연 뒤, 그 노트만 다시 읽음
+    fn edit_selected(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        let Some(id) = self.selected_note() else {
+            return Ok(());
+        };
+        let Some(path) = self
+            .app
+            .index
+            .get(&id)
+            .map(|entry| PathBuf::from(&entry.file_path))
+        else {
+            return Ok(());
+        };
+
+        disable_raw_mode()?;
+        execute!(io::stdout(), LeaveAlternateScreen)?;
+        let edited = open_in_editor(&path);
+        execute!(io::stdout(), EnterAlternateScreen)?;
+        enable_raw_mode()?;
+        terminal.clear()?;
+
+        if let Err(e) = edited {
+            self.status = format!("❌ {}", e);
+            return Ok(());
+        }
+        let reloaded = self.app.reload_note(&id);
+        self.rebuild_sidebar();
+        self.apply_filter();
+        self.status = match reloaded {
+            Ok(changes) if changes.is_empty() => "✅ 변경 없음".to_string(),
+            Ok(_) => "✅ 노트를 다시 읽었습니다".to_string(),
+            Err(e) => format!("❌ {}", e),
+        };
+        Ok(())
+    }
+
+    // 링크 대상 노트로 이동 (목록에 없으면 필터를 풀고 찾음)
+    fn follow(&mut self, target: Uuid) {
+        if let Some(current) = self.selected_note() {
+            self.history.push(current);
+        }
+        self.show_note(target);
+    }
+
+    fn go_back(&mut self) {
+        if let Some(previous) = self.history.pop() {
+            self.show_note(previous);
+        }
+    }
+
+    fn show_note(&mut self, id: Uuid) {
+        if !self.notes.contains(&id) {
+            self.filter.clear();
+            self.sidebar_state.select(Some(0));
+            self.apply_filter();
+        }
+        if let Some(index) = self.notes.iter().position(|n| *n == id) {
+            self.select(index);
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [sidebar, center, preview] = Layout::horizontal([
+            Constraint::Length(24),
+            Constraint::Percentage(35),
+            Constraint::Min(20),
+        ])
+        .areas(main);
+        let [filter, list] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(center);
+
+        self.draw_sidebar(frame, sidebar);
+        self.draw_filter(frame, filter);
+        self.draw_list(frame, list);
+        self.draw_preview(frame, preview);
+        self.draw_status(frame, status);
+        if self.focus == Focus::Links {
+            self.draw_links(frame, preview);
+        }
+    }
+
+    fn block(&self, title: String, focus: Focus) -> Block<'static> {
+        let border = if self.focus == focus {
+            Style::new().fg(Color::Cyan)
+        } else {
+            Style::new().fg(Color::DarkGray)
+        };
+        Block::bordered().title(title).border_style(border)
+    }
+
+    fn draw_sidebar(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .sidebar
+            .iter()
+            .map(|(item, count)| {
+                let label = match item {
+                    SidebarItem::All => "📚 전체".to_string(),
+                    SidebarItem::Folder(tag) => format!("📁 {}", tag.trim_start_matches('@')),
+                    SidebarItem::Tag(tag) => format!("🏷️ {}", tag),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(label),
+                    Span::styled(format!(" {}", count), Style::new().fg(Color::DarkGray)),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.block(" 폴더/태그 ".to_string(), Focus::Sidebar))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.sidebar_state);
+    }
+
+    fn draw_filter(&self, frame: &mut Frame, area: Rect) {
+        let text = if self.filter.is_empty() && self.focus != Focus::Filter {
+            Span::styled(
+                "/ 를 눌러 검색 (tag:, folder:, AND/OR/NOT)",
+                Style::new().fg(Color::DarkGray),
+            )
+        } else {
+            Span::raw(self.filter.clone())
+        };
+        let width = Span::raw(self.filter.as_str()).width() as u16;
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![Span::raw("🔍 "), text]))
+                .block(self.block(" 검색 ".to_string(), Focus::Filter)),
+            area,
+        );
+        if self.focus == Focus::Filter {
+            frame.set_cursor_position(Position::new(
+                (area.x + 4 + width).min(area.right().saturating_sub(2)),
+                area.y + 1,
+            ));
+        }
+    }
+
+    fn draw_list(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .notes
+            .iter()
+            .filter_map(|id| {
+                let note = self.app.get_note(id)?;
+                let mut spans = vec![
+                    Span::raw(note.title.clone()),
+                    Span::styled(
+                        format!(" {}", note.updated_at.format("%Y-%m-%d")),
+                        Style::new().fg(Color::DarkGray),
+                    ),
+                ];
+                if let Some(folder) = note.get_folder_name() {
+                    spans.push(Span::styled(
+                        format!(" @{}", folder),
+                        Style::new().fg(Color::Yellow),
+                    ));
+                }
+                Some(ListItem::new(Line::from(spans)))
+            })
+            .collect();
+        let title = format!(" 노트 ({}) ", self.notes.len());
+        let list = List::new(items)
+            .block(self.block(title, Focus::List))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    fn draw_preview(&self, frame: &mut Frame, area: Rect) {
+        let block = self.block(" 미리보기 ".to_string(), Focus::Links);
+        let Some((id, note)) = self
+            .selected_note()
+            .and_then(|id| Some((id, self.app.get_note(&id)?)))
+        else {
+            frame.render_widget(Paragraph::new("노트가 없습니다.").block(block), area);
+            return;
+        };
+
+        let dim = Style::new().fg(Color::DarkGray);
+        let mut lines = vec![
+            Line::styled(
+                note.title.clone(),
+                Style::new().add_modifier(Modifier::BOLD),
+            ),
+            Line::styled(self.app.display_path(&id), dim),
+        ];
+        let tags = note.get_regular_tags();
+        if !tags.is_empty() {
+            lines.push(Line::styled(format!("🏷️ {}", tags.join(", ")), dim));
+        }
+        if !self.links.is_empty() {
+            lines.push(Line::styled(
+                format!("🔗 링크 {} 개 (f로 따라가기)", self.links.len()),
+                dim,
+            ));
+        }
+        lines.push(Line::default());
+
+        // 단축어는 링크로 펼쳐서 표시
+        let content = self
+            .app
+            .expand_content(&id)
+            .map(|expanded| expanded.content)
+            .unwrap_or_else(|| note.content().to_string());
+        let mut text = Text::from(lines);
+        text.extend(markdown::render(&content));
+
+        frame.render_widget(
+            Paragraph::new(text)
+                .block(block.title_bottom(format!(" {} ", note.filename)))
+                .wrap(Wrap { trim: false })
+                .scroll((self.preview_scroll, 0)),
+            area,
+        );
+    }
+
+    fn draw_links(&mut self, frame: &mut Frame, area: Rect) {
+        let height = (self.links.len() as u16 + 2).min(area.height.saturating_sub(2));
+        let popup = Rect {
+            x: area.x + 2,
+            y: area.y + 2,
+            width: area.width.saturating_sub(4),
+            height,
+        };
+        let items: Vec<ListItem> = self
+            .links
+            .iter()
+            .map(
+                |link| match link.resolved.and_then(|id| self.app.get_note(&id)) {
+                    Some(note) => ListItem::new(format!("→ {}", note.title)),
+                    None => ListItem::new(Line::styled(
+                        format!("✗ {}", link.raw.target),
+                        Style::new().fg(Color::Red),
+                    )),
+                },
+            )
+            .collect();
+        let list = List::new(items)
+            .block(
+                Block::bordered()
+                    .title(" 링크 (Enter 이동, Esc 닫기) ")
+                    .border_style(Style::new().fg(Color::Cyan)),
+            )
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_widget(Clear, popup);
+        frame.render_stateful_widget(list, popup, &mut self.links_state);
+    }
+
+    fn draw_status(&self, frame: &mut Frame, area: Rect) {
+        let line = if self.status.is_empty() {
+            Line::styled(
+                "/ 검색  Tab 폴더/태그  ↑↓ 이동  PgUp/PgDn 미리보기  e 편집  f 링크  b 뒤로  r 새로고침  q 종료",
+                Style::new().fg(Color::DarkGray),
+            )
+        } else {
+            Line::raw(self.status.clone())
+        };
+        frame.render_widget(Paragraph::new(line), area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    // 노트 세 개: alpha(@work, rust), beta(@home), gamma(rust)
+    fn test_tui() -> (TempDir, Tui) {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let dir = temp_dir.path();
+        let notes = [
+            (
+                "alpha.md",
+                "Alpha",
+                "Alpha explains ownership. See [[Beta]].",
+            ),
+            ("beta.md", "Beta", "Beta is about cooking."),
+            ("gamma.md", "Gamma", "Gamma mentions ownership too."),
+        ];
+        for (filename, title, body) in notes {
+            let content = format!("---\ntitle: {}\n---\n\n{}\n", title, body);
+            fs::write(dir.join(filename), content).expect("Failed to write note");
+        }
+
+        let mut app = NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+        let tags = [
+            ("alpha.md", vec!["@work", "rust"]),
+            ("beta.md", vec!["@home"]),
+            ("gamma.md", vec!["rust"]),
+        ];
+        for (filename, tags) in tags {
+            let id = app.resolve_note(filename).unwrap();
+            let tags: Vec<String> = tags.into_iter().map(String::from).collect();
+            app.add_tags(&id, &tags).unwrap();
+        }
+        (temp_dir, Tui::new(app, None))
+    }
+
+    fn id(tui: &Tui, filename: &str) -> Uuid {
+        tui.app.resolve_note(filename).unwrap()
+    }
+
+    fn shown(tui: &Tui) -> Vec<Uuid> {
+        let mut ids = tui.notes.clone();
+        ids.sort();
+        ids
+    }
+
+    fn ids(tui: &Tui, filenames: &[&str]) -> Vec<Uuid> {
+        let mut ids: Vec<Uuid> = filenames.iter().map(|f| id(tui, f)).collect();
+        ids.sort();
+        ids
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_filter(tui: &mut Tui, text: &str) {
+        tui.focus = Focus::Filter;
+        for c in text.chars() {
+            tui.handle_filter_key(key(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn test_filter_narrows_list_and_keeps_selection() {
+        let (_temp_dir, mut tui) = test_tui();
+        assert_eq!(tui.notes.len(), 3);
+        let gamma = id(&tui, "gamma.md");
+        tui.select(tui.notes.iter().position(|n| *n == gamma).unwrap());
+
+        // 입력 중인 마지막 단어는 접두어로 검색
+        type_filter(&mut tui, "owner");
+        assert_eq!(shown(&tui), ids(&tui, &["alpha.md", "gamma.md"]));
+        assert_eq!(tui.selected_note(), Some(gamma));
+
+        for _ in 0..5 {
+            tui.handle_filter_key(key(KeyCode::Backspace));
+        }
+        assert!(tui.filter.is_empty());
+        assert_eq!(tui.notes.len(), 3);
+        assert_eq!(tui.selected_note(), Some(gamma));
+
+        // Ctrl-U는 검색어를 지우고, Esc는 지운 뒤 목록으로
+        type_filter(&mut tui, "cooking");
+        assert_eq!(shown(&tui), ids(&tui, &["beta.md"]));
+        tui.handle_filter_key(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::CONTROL));
+        assert!(tui.filter.is_empty());
+        assert_eq!(tui.notes.len(), 3);
+
+        type_filter(&mut tui, "cooking");
+        tui.handle_filter_key(key(KeyCode::Esc));
+        assert!(tui.filter.is_empty());
+        assert!(tui.focus == Focus::List);
+        assert_eq!(tui.notes.len(), 3);
+    }
+
+    #[test]
+    fn test_incomplete_query_keeps_previous_results() {
+        let (_temp_dir, mut tui) = test_tui();
+
+        type_filter(&mut tui, "tag:rust");
+        assert_eq!(shown(&tui), ids(&tui, &["alpha.md", "gamma.md"]));
+        assert!(tui.status.is_empty());
+
+        // 따옴표를 닫기 전에는 오류를 상태 줄에만 표시
+        tui.filter.push_str(" title:\"Al");
+        tui.apply_filter();
+        assert!(tui.status.starts_with("⚠️"), "{}", tui.status);
+        assert_eq!(shown(&tui), ids(&tui, &["alpha.md", "gamma.md"]));
+
+        type_filter(&mut tui, "pha\"");
+        assert_eq!(shown(&tui), ids(&tui, &["alpha.md"]));
+        assert!(tui.status.is_empty());
+    }
+
+    #[test]
+    fn test_search_prefixes_last_word_while_typing() {
+        let (_temp_dir, tui) = test_tui();
+
+        let mut found = tui.search("own").unwrap();
+        found.sort();
+        assert_eq!(found, ids(&tui, &["alpha.md", "gamma.md"]));
+        // 접두어로 찾을 수 없는 검색어는 그대로 검색
+        assert_eq!(tui.search("cooking").unwrap(), ids(&tui, &["beta.md"]));
+        assert_eq!(
+            tui.search("ownership -alpha").unwrap(),
+            ids(&tui, &["gamma.md"])
+        );
+        assert!(tui.search("title:\"open").is_err());
+    }
+
+    #[test]
+    fn test_move_selection_clamps_to_list() {
+        let (_temp_dir, mut tui) = test_tui();
+        tui.select(0);
+
+        tui.move_selection(KeyCode::Up);
+        assert_eq!(tui.list_state.selected(), Some(0));
+        tui.move_selection(KeyCode::End);
+        assert_eq!(tui.list_state.selected(), Some(2));
+        tui.move_selection(KeyCode::Char('j'));
+        assert_eq!(tui.list_state.selected(), Some(2));
+        tui.move_selection(KeyCode::Char('g'));
+        assert_eq!(tui.list_state.selected(), Some(0));
+        tui.move_selection(KeyCode::Down);
+        assert_eq!(tui.list_state.selected(), Some(1));
+
+        tui.select(10);
+        assert_eq!(tui.list_state.selected(), Some(2));
+        assert_eq!(tui.selected_note(), Some(tui.notes[2]));
+
+        // 빈 목록에서는 선택 없음
+        type_filter(&mut tui, "zzzzzz");
+        assert!(tui.notes.is_empty());
+        tui.move_selection(KeyCode::Down);
+        assert_eq!(tui.list_state.selected(), None);
+        assert_eq!(tui.selected_note(), None);
+        assert!(tui.links.is_empty());
+    }
+
+    #[test]
+    fn test_sidebar_counts_and_filters_by_tag() {
+        let (_temp_dir, mut tui) = test_tui();
+        let items: Vec<(SidebarItem, usize)> = vec![
+            (SidebarItem::All, 3),
+            (SidebarItem::Folder("@home".to_string()), 1),
+            (SidebarItem::Folder("@work".to_string()), 1),
+            (SidebarItem::Tag("rust".to_string()), 2),
+        ];
+        assert!(tui.sidebar == items);
+
+        // 사이드바에서 항목을 고르면 목록이 그 태그로 좁혀짐
+        tui.focus = Focus::Sidebar;
+        tui.move_selection(KeyCode::End);
+        assert_eq!(shown(&tui), ids(&tui, &["alpha.md", "gamma.md"]));
+        tui.move_selection(KeyCode::Up);
+        assert_eq!(shown(&tui), ids(&tui, &["alpha.md"]));
+
+        // 다시 만들어도 고른 항목 유지, 사라지면 전체로
+        let gamma = id(&tui, "gamma.md");
+        tui.app.add_tags(&gamma, &["@work".to_string()]).unwrap();
+        tui.rebuild_sidebar();
+        tui.apply_filter();
+        assert!(tui.sidebar[2] == (SidebarItem::Folder("@work".to_string()), 2));
+        assert_eq!(tui.sidebar_state.selected(), Some(2));
+        assert_eq!(shown(&tui), ids(&tui, &["alpha.md", "gamma.md"]));
+
+        let alpha = id(&tui, "alpha.md");
+        tui.app.remove_tags(&alpha, &["@work".to_string()]).unwrap();
+        tui.app.remove_tags(&gamma, &["@work".to_string()]).unwrap();
+        tui.rebuild_sidebar();
+        assert_eq!(tui.sidebar_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_follow_link_and_go_back() {
+        let (_temp_dir, mut tui) = test_tui();
+        let alpha = id(&tui, "alpha.md");
+        let beta = id(&tui, "beta.md");
+
+        // 대상이 목록에 없으면 필터를 풀고 찾음
+        type_filter(&mut tui, "ownership");
+        tui.focus = Focus::List;
+        tui.select(tui.notes.iter().position(|n| *n == alpha).unwrap());
+        assert_eq!(tui.links.len(), 1);
+        assert_eq!(tui.links[0].resolved, Some(beta));
+
+        tui.follow(beta);
+        assert_eq!(tui.selected_note(), Some(beta));
+        assert!(tui.filter.is_empty());
+        assert_eq!(tui.history, vec![alpha]);
+
+        tui.go_back();
+        assert_eq!(tui.selected_note(), Some(alpha));
+        assert!(tui.history.is_empty());
+
+        // 돌아갈 곳이 없으면 그대로
+        tui.go_back();
+        assert_eq!(tui.selected_note(), Some(alpha));
+    }
+}
@@ -0,0 +1,103 @@
+use crate::error::{Error, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use uuid::Uuid;
+
+// 파일 변경을 반영한 결과
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum NoteChange {
+    Added { id: Uuid, path: String },
+    Updated { id: Uuid, path: String },
+    Moved { id: Uuid, from: String, to: String },
+    Removed { id: Uuid, path: String },
+}
+
+// watched_folders의 파일 시스템 이벤트 수신기 (Linux에서는 inotify)
+pub struct NoteWatcher {
+    // 드롭되면 감시가 끝나므로 보관
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+}
+
+impl NoteWatcher {
+    pub fn new(folders: &[String]) -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).map_err(Error::Watch)?;
+
+        for folder in folders {
+            let path = Path::new(folder);
+            if !path.exists() {
+                continue;
+            }
+            watcher
+                .watch(path, RecursiveMode::Recursive)
+                .map_err(|source| Error::WatchFolder {
+                    path: path.to_path_buf(),
+                    source,
+                })?;
+        }
+
+        Ok(NoteWatcher {
+            _watcher: watcher,
+            events,
+        })
+    }
+
+    // 이벤트가 올 때까지 기다린 뒤, debounce 동안 조용해질 때까지 모은 경로 반환
+    pub fn next_batch(&self, debounce: Duration) -> Result<Vec<PathBuf>> {
+        let mut paths = BTreeSet::new();
+
+        while paths.is_empty() {
+            let event = self.events.recv().map_err(|_| Error::WatchStopped)?;
+            collect_paths(event, &mut paths);
+        }
+
+        loop {
+            match self.events.recv_timeout(debounce) {
+                Ok(event) => collect_paths(event, &mut paths),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(Error::WatchStopped);
+                }
+            }
+        }
+
+        Ok(paths.into_iter().collect())
+    }
+
+    // 기다리지 않고 지금까지 쌓인 이벤트의 경로 반환 (REPL 명령 사이에 사용)
+    pub fn pending(&self) -> Vec<PathBuf> {
+        let mut paths = BTreeSet::new();
+
+        while let Ok(event) = self.events.try_recv() {
+            collect_paths(event, &mut paths);
+        }
+
+        paths.into_iter().collect()
+    }
+}
+
+fn collect_paths(event: notify::Result<Event>, paths: &mut BTreeSet<PathBuf>) {
+    let Ok(event) = event else {
+        return;
+    };
+    // 읽기/열기 이벤트는 내용이 바뀌지 않음
+    if matches!(event.kind, EventKind::Access(_)) {
+        return;
+    }
+
+    for path in event.paths {
+        // .index.json 같은 숨김 파일은 앱이 직접 쓰는 파일이므로 무시
+        let hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if !hidden {
+            paths.insert(path);
+        }
+    }
+}
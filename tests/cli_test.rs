@@ -0,0 +1,408 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn md_filer(notes_dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_md_filer"))
+        .arg("--notes-dir")
+        .arg(notes_dir)
+        .args(args)
+        .env_remove("NOTES_DIR")
+        .output()
+        .expect("Failed to run md_filer")
+}
+
+fn create_test_dir() -> TempDir {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    fs::write(
+        temp_dir.path().join("rust.md"),
+        "---\ntitle: Rust Note\n---\n\nAbout Rust.",
+    )
+    .expect("Failed to write test file");
+    temp_dir
+}
+
+#[test]
+fn test_cli_list_json() {
+    // 인덱스가 없는 새 폴더: 로드 중 진행 메시지가 JSON 출력에 섞이면 안 됨
+    let temp_dir = create_test_dir();
+    assert!(!temp_dir.path().join(".index.json").exists());
+
+    let output = md_filer(temp_dir.path(), &["list", "--format", "json"]);
+    assert!(output.status.success());
+
+    let notes: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is not JSON");
+    assert_eq!(notes.as_array().map(|a| a.len()), Some(1));
+    assert_eq!(notes[0]["title"], "Rust Note");
+    assert!(notes[0]["file_path"].as_str().unwrap().ends_with("rust.md"));
+}
+
+#[test]
+fn test_cli_show_and_exit_codes() {
+    let temp_dir = create_test_dir();
+
+    let output = md_filer(temp_dir.path(), &["list", "--format", "json"]);
+    let notes: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is not JSON");
+    let id = notes[0]["id"].as_str().unwrap().to_string();
+
+    let output = md_filer(temp_dir.path(), &["show", &id, "--format", "json"]);
+    assert!(output.status.success());
+    let note: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is not JSON");
+    assert!(note["content"].as_str().unwrap().contains("About Rust."));
+
+    // 없는 노트
+    let output = md_filer(
+        temp_dir.path(),
+        &["show", "00000000-0000-0000-0000-000000000000"],
+    );
+    assert_eq!(output.status.code(), Some(3));
+
+    // UUID가 아니면 파일명/제목으로 찾음
+    let output = md_filer(temp_dir.path(), &["show", "not-a-uuid"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    // 검색 결과 없음
+    let output = md_filer(temp_dir.path(), &["search", "python", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "[]");
+}
+
+#[test]
+fn test_cli_folders_add_and_remove() {
+    let temp_dir = create_test_dir();
+    let extra = TempDir::new().expect("Failed to create temp dir");
+    let extra_path = extra.path().to_string_lossy().to_string();
+
+    let output = md_filer(
+        temp_dir.path(),
+        &["folders", "add", &extra_path, "--format", "json"],
+    );
+    assert!(output.status.success());
+    let folders: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is not JSON");
+    assert_eq!(folders.as_array().map(|a| a.len()), Some(2));
+
+    let output = md_filer(temp_dir.path(), &["folders", "remove", &extra_path]);
+    assert!(output.status.success());
+
+    let output = md_filer(temp_dir.path(), &["folders", "remove", &extra_path]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cli_check_reports_broken_links() {
+    let temp_dir = create_test_dir();
+
+    // 고립된 노트만 있으면 성공
+    let output = md_filer(temp_dir.path(), &["check", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    fs::write(
+        temp_dir.path().join("index.md"),
+        "---\ntitle: Index\n---\n[[Rust Note]]\n[[Nowhere]]\n",
+    )
+    .expect("Failed to write test file");
+
+    let output = md_filer(temp_dir.path(), &["check", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(4));
+    let report: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is not JSON");
+    let notes = report["notes"].as_array().unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0]["title"], "Index");
+    assert_eq!(notes[0]["issues"][0]["type"], "unresolved_link");
+    assert_eq!(notes[0]["issues"][0]["target"], "Nowhere");
+
+    // 줄 번호는 frontmatter를 포함한 파일 기준 (로드할 때 id 줄이 추가됨)
+    let content = fs::read_to_string(temp_dir.path().join("index.md")).unwrap();
+    let line = content.lines().position(|l| l == "[[Nowhere]]").unwrap() + 1;
+    assert_eq!(line, 6);
+    assert_eq!(notes[0]["issues"][0]["line_number"], line);
+}
+
+#[test]
+fn test_cli_new_with_template() {
+    let temp_dir = create_test_dir();
+    let templates = temp_dir.path().join(".templates");
+    fs::create_dir(&templates).unwrap();
+    fs::write(
+        templates.join("meeting.md"),
+        "---\ntitle: \"{{title}}\"\n---\n# {{title}}\n\n날짜: {{date}}\nID: {{uuid}}\n",
+    )
+    .unwrap();
+
+    let output = md_filer(
+        temp_dir.path(),
+        &[
+            "new",
+            "Weekly",
+            "Sync",
+            "--template",
+            "meeting",
+            "-t",
+            "@work",
+            "-t",
+            "회의",
+            "--format",
+            "json",
+        ],
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let note: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is not JSON");
+    assert_eq!(note["title"], "Weekly Sync");
+    assert_eq!(note["folder"], "work");
+    assert_eq!(note["tags"], serde_json::json!(["회의"]));
+
+    let id = note["id"].as_str().unwrap();
+    let content = fs::read_to_string(temp_dir.path().join("weekly-sync.md")).unwrap();
+    assert!(content.contains(&format!("id: {}", id)));
+    assert!(content.contains(&format!("ID: {}", id)));
+    assert!(!content.contains("{{date}}"));
+
+    // 템플릿은 노트로 취급하지 않음
+    let output = md_filer(temp_dir.path(), &["list", "--format", "json"]);
+    let notes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(notes.as_array().map(|a| a.len()), Some(2));
+
+    // 없는 템플릿
+    let output = md_filer(temp_dir.path(), &["new", "X", "--template", "nope"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(!temp_dir.path().join("x.md").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_edit_reindexes_note() {
+    let temp_dir = create_test_dir();
+    let output = md_filer(temp_dir.path(), &["list", "--format", "json"]);
+    let notes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = notes[0]["id"].as_str().unwrap().to_string();
+
+    // 편집기 대신 제목을 바꾸는 스크립트
+    let script = temp_dir.path().join("edit.sh");
+    fs::write(&script, "sed -i.bak 's/Rust Note/Edited Note/' \"$1\"\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_md_filer"))
+        .arg("--notes-dir")
+        .arg(temp_dir.path())
+        .args(["edit", &id, "--format", "json"])
+        .env_remove("VISUAL")
+        .env("EDITOR", format!("sh {}", script.display()))
+        .output()
+        .expect("Failed to run md_filer");
+    assert!(output.status.success(), "{:?}", output);
+    let changes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(changes[0]["change"], "updated");
+
+    let output = md_filer(temp_dir.path(), &["show", &id, "--format", "json"]);
+    let note: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(note["title"], "Edited Note");
+
+    // 편집기가 실패하면 종료 코드 1
+    let output = Command::new(env!("CARGO_BIN_EXE_md_filer"))
+        .arg("--notes-dir")
+        .arg(temp_dir.path())
+        .args(["edit", &id])
+        .env("VISUAL", "false")
+        .env("MD_FILER_LANG", "en")
+        .output()
+        .expect("Failed to run md_filer");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("editor exited with an error"), "{}", stderr);
+
+    // 편집기를 실행하지 못해도 종료 코드 1
+    let output = Command::new(env!("CARGO_BIN_EXE_md_filer"))
+        .arg("--notes-dir")
+        .arg(temp_dir.path())
+        .args(["edit", &id])
+        .env("VISUAL", "md-filer-no-such-editor")
+        .env("MD_FILER_LANG", "en")
+        .output()
+        .expect("Failed to run md_filer");
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("failed to launch editor 'md-filer-no-such-editor'"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_cli_result_numbers_follow_last_search() {
+    let temp_dir = create_test_dir();
+    fs::write(
+        temp_dir.path().join("web.md"),
+        "---\ntitle: Web Note\n---\n\nAbout the web.",
+    )
+    .unwrap();
+
+    // 검색 결과의 #1은 목록 순서와 관계없이 검색된 노트
+    let output = md_filer(temp_dir.path(), &["search", "web"]);
+    assert!(output.status.success());
+    let output = md_filer(temp_dir.path(), &["show", "#1", "--format", "json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let note: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(note["title"], "Web Note");
+
+    // 파일명과 제목으로도 찾을 수 있음
+    let output = md_filer(temp_dir.path(), &["show", "rust.md", "--format", "json"]);
+    let note: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(note["title"], "Rust Note");
+
+    let output = md_filer(temp_dir.path(), &["show", "#9"]);
+    assert_eq!(output.status.code(), Some(3));
+    let output = md_filer(temp_dir.path(), &["show", "Note"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_cli_show_renders_markdown_unless_raw() {
+    let temp_dir = create_test_dir();
+    fs::write(
+        temp_dir.path().join("rich.md"),
+        "---\ntitle: Rich\n---\n# Heading\n\nSome **bold** text.\n\n- one\n\n```rust\nfn main() {}\n```\n",
+    )
+    .unwrap();
+
+    // 터미널이 아니면 색과 페이저 없이 렌더링만
+    let output = md_filer(temp_dir.path(), &["show", "Rich"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains('\x1b'));
+    assert!(stdout.contains("Some bold text."));
+    assert!(stdout.contains("• one"));
+    assert!(stdout.contains("  fn main() {}"));
+    assert!(!stdout.contains("```"));
+
+    let output = md_filer(temp_dir.path(), &["show", "Rich", "--raw"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Some **bold** text."));
+    assert!(stdout.contains("```rust"));
+}
+
+#[test]
+fn test_cli_import_meta_dry_run_then_apply() {
+    let temp_dir = create_test_dir();
+    let csv = temp_dir.path().join("meta.csv");
+    let output = md_filer(temp_dir.path(), &["export-meta", csv.to_str().unwrap()]);
+    assert!(output.status.success());
+    let exported = fs::read_to_string(&csv).unwrap();
+    assert!(exported.starts_with("uuid,path,title,tags,folder,created,updated\n"));
+
+    fs::write(&csv, "path,tags\nrust.md,rust 학습\n").unwrap();
+    let import = |extra: &[&str]| {
+        let mut args = vec!["import-meta", csv.to_str().unwrap(), "--format", "json"];
+        args.extend(extra);
+        let output = md_filer(temp_dir.path(), &args);
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    // 기본은 미리보기
+    let plan = import(&[]);
+    assert_eq!(plan["changes"][0]["changes"][0]["field"], "tags");
+    assert_eq!(plan["applied"], 0);
+    assert_eq!(import(&["--apply"])["applied"], 1);
+    assert_eq!(import(&[])["unchanged"], 1);
+
+    let output = md_filer(temp_dir.path(), &["list", "--format", "json"]);
+    let notes: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(notes[0]["tags"], serde_json::json!(["rust", "학습"]));
+
+    // 잘못된 줄은 종료 코드 2
+    fs::write(&csv, "title\nNo key\n").unwrap();
+    let output = md_filer(temp_dir.path(), &["import-meta", csv.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_cli_read_commands_work_while_dir_is_locked() {
+    let temp_dir = create_test_dir();
+    // watch처럼 디렉토리를 잠그고 있는 다른 세션
+    let session =
+        md_filer::app::NoteApp::new(temp_dir.path().to_path_buf()).expect("Failed to open app");
+
+    let output = md_filer(temp_dir.path(), &["list", "--format", "json"]);
+    assert!(output.status.success());
+    let notes: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is not JSON");
+    assert_eq!(notes[0]["title"], "Rust Note");
+
+    let output = md_filer(temp_dir.path(), &["search", "rust"]);
+    assert!(output.status.success());
+
+    // 바꾸는 명령은 잠금 오류
+    let output = md_filer(temp_dir.path(), &["new", "Another"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(!temp_dir.path().join("another.md").exists());
+
+    drop(session);
+}
+
+#[test]
+fn test_cli_tag_exit_codes() {
+    let temp_dir = create_test_dir();
+
+    let output = md_filer(temp_dir.path(), &["tag", "add", "rust.md", "lang"]);
+    assert!(output.status.success());
+    // 잘못된 태그 이름은 2, 노트에 없는 태그는 3
+    let output = md_filer(temp_dir.path(), &["tag", "add", "rust.md", "a,b"]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = md_filer(temp_dir.path(), &["tag", "remove", "rust.md", "missing"]);
+    assert_eq!(output.status.code(), Some(3));
+    let output = md_filer(temp_dir.path(), &["tag", "rename", "missing", "other"]);
+    assert_eq!(output.status.code(), Some(3));
+    let output = md_filer(temp_dir.path(), &["move", "rust.md", "@a@b"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_cli_shortcut_exit_codes() {
+    let temp_dir = create_test_dir();
+
+    let output = md_filer(
+        temp_dir.path(),
+        &[
+            "shortcut",
+            "add",
+            "rust.md",
+            "docs",
+            "https://doc.rust-lang.org",
+        ],
+    );
+    assert!(output.status.success());
+
+    // 없는 단축어는 3, 잘못된 대상은 2
+    let output = md_filer(
+        temp_dir.path(),
+        &[
+            "shortcut",
+            "edit",
+            "rust.md",
+            "missing",
+            "-t",
+            "https://a.io",
+        ],
+    );
+    assert_eq!(output.status.code(), Some(3));
+    let output = md_filer(
+        temp_dir.path(),
+        &["shortcut", "edit", "rust.md", "docs", "-t", ""],
+    );
+    assert_eq!(output.status.code(), Some(2));
+    let output = md_filer(
+        temp_dir.path(),
+        &["shortcut", "remove", "rust.md", "missing"],
+    );
+    assert_eq!(output.status.code(), Some(3));
+    let output = md_filer(temp_dir.path(), &["shortcut", "remove", "rust.md", "docs"]);
+    assert!(output.status.success());
+}
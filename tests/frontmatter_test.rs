@@ -0,0 +1,104 @@
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const FIXTURES_DIR: &str = "tests/fixtures/frontmatter";
+
+// fixture 하나만 담긴 임시 노트 폴더로 앱을 열고, UUID 주입 후 파일 내용과 노트 반환
+fn load_fixture(name: &str) -> (String, String, md_filer::note::Note) {
+    let original =
+        fs::read_to_string(Path::new(FIXTURES_DIR).join(name)).expect("Failed to read fixture");
+
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let notes_dir = temp_dir.path().to_path_buf();
+    fs::write(notes_dir.join(name), &original).expect("Failed to write fixture");
+
+    let app = md_filer::app::NoteApp::new(notes_dir.clone()).expect("Failed to create app");
+    assert_eq!(app.list_notes().len(), 1);
+    let note = app.list_notes()[0].1.clone();
+
+    let rewritten = fs::read_to_string(notes_dir.join(name)).expect("Failed to read file");
+
+    // 두 번째 로드에서는 파일이 바뀌지 않아야 함
+    drop(app);
+    let app = md_filer::app::NoteApp::new(notes_dir.clone()).expect("Failed to create app");
+    assert_eq!(app.list_notes()[0].1.id, note.id);
+    let reloaded = fs::read_to_string(notes_dir.join(name)).expect("Failed to read file");
+    assert_eq!(
+        reloaded, rewritten,
+        "{}: second load modified the file",
+        name
+    );
+
+    (original, rewritten, note)
+}
+
+#[test]
+fn test_uuid_injection_preserves_everything_else() {
+    let fixtures = [
+        "obsidian-aliases.md",
+        "hugo-post.md",
+        "no-title.md",
+        "crlf.md",
+        "no-trailing-newline.md",
+        "horizontal-rule.md",
+    ];
+
+    for name in fixtures {
+        let (original, rewritten, note) = load_fixture(name);
+        let eol = if original.starts_with("---\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+
+        // 주입된 id 줄을 빼면 원본과 바이트 단위로 같아야 함
+        let id_line = format!("id: {}{}", note.id, eol);
+        assert_eq!(rewritten.matches(&id_line).count(), 1, "{}", name);
+        assert_eq!(rewritten.replacen(&id_line, "", 1), original, "{}", name);
+        assert!(md_filer::note::Note::has_uuid_in_frontmatter(&rewritten));
+    }
+}
+
+#[test]
+fn test_uuid_injection_fills_empty_id_key() {
+    let (original, rewritten, note) = load_fixture("empty-id.md");
+
+    assert_eq!(
+        rewritten,
+        original.replace("id:\n", &format!("id: {}\n", note.id))
+    );
+}
+
+#[test]
+fn test_uuid_injection_without_frontmatter_keeps_body() {
+    let (original, rewritten, note) = load_fixture("no-frontmatter.md");
+
+    assert!(rewritten.starts_with("---\n"));
+    assert!(rewritten.ends_with(&original));
+    assert!(rewritten.contains(&format!("id: {}", note.id)));
+    assert!(rewritten.contains("title: Plain Markdown"));
+}
+
+#[test]
+fn test_unknown_frontmatter_keys_are_kept_on_note() {
+    let (_, _, note) = load_fixture("obsidian-aliases.md");
+    let keys: Vec<_> = note
+        .frontmatter()
+        .keys()
+        .filter_map(|k| k.as_str())
+        .collect();
+    // 주입된 id는 파일과 같이 닫는 --- 바로 앞 (마지막 키)
+    assert_eq!(keys, vec!["title", "aliases", "tags", "cssclass", "id"]);
+
+    let (_, _, note) = load_fixture("hugo-post.md");
+    assert_eq!(note.title, "Hugo: A Post");
+    assert_eq!(
+        note.frontmatter().get("draft").and_then(|v| v.as_bool()),
+        Some(true)
+    );
+    assert!(note.content().starts_with("Content starts"));
+
+    let (_, _, note) = load_fixture("horizontal-rule.md");
+    assert!(note.content().contains("Below the rule."));
+}
@@ -1,10 +1,25 @@
+use md_filer::check::Issue;
+use md_filer::error::{Error, MetaRecordError, QueryError};
+use md_filer::events::NoteEvent;
+use md_filer::fsck::{FsckCategory, FsckIssue};
+use md_filer::links::{BacklinkSource, LinkKind};
+use md_filer::meta::{self, FieldChange, MetaFormat};
+use md_filer::note::{LinkTarget, Note};
+use md_filer::watch::{NoteChange, NoteWatcher};
+use std::cell::RefCell;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, UNIX_EPOCH};
 use tempfile::TempDir;
+use uuid::Uuid;
 
 // 테스트용 헬퍼 함수들
-fn create_test_note(dir: &PathBuf, filename: &str, content: &str) {
+fn create_test_note(dir: &Path, filename: &str, content: &str) {
     let path = dir.join(filename);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).expect("Failed to create test dir");
+    }
     fs::write(path, content).expect("Failed to write test file");
 }
 
@@ -102,7 +117,7 @@ fn test_app_handles_new_files() {
 
     // 새 파일 추가
     create_test_note(
-        &temp_dir.path().to_path_buf(),
+        temp_dir.path(),
         "note4.md",
         r#"# New Note
 
@@ -162,7 +177,7 @@ This is a test note."#,
     // 인덱스에 태그 수동 추가 (실제로는 별도 명령어로 추가)
     if let Some(note) = app.list_notes().first() {
         let id = *note.0;
-        if let Some(entry) = app.index.mappings.get_mut(&id) {
+        if let Some(entry) = app.index.get_mut(&id) {
             entry.tags = vec![
                 "rust".to_string(),
                 "@projects".to_string(),
@@ -337,3 +352,1520 @@ Just some plain text without any heading."#,
     // 파일명에서 제목이 생성되었는지 확인
     assert_eq!(note.title, "empty-note");
 }
+
+#[test]
+fn test_recursive_scan_finds_notes_in_subdirectories() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let notes_dir = temp_dir.path().to_path_buf();
+
+    create_test_note(&notes_dir, "top.md", "# Top");
+    create_test_note(&notes_dir, "projects/2025/plan.md", "# Plan");
+    create_test_note(&notes_dir, ".obsidian/cache.md", "# Hidden");
+
+    let app = md_filer::app::NoteApp::new(notes_dir).expect("Failed to create app");
+
+    let mut titles: Vec<_> = app
+        .list_notes()
+        .iter()
+        .map(|(_, n)| n.title.clone())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, vec!["Plan", "Top"]);
+}
+
+#[test]
+fn test_folder_matching_does_not_cover_sibling_with_same_prefix() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let notes_dir = temp_dir.path().join("notes");
+    let archive = temp_dir.path().join("notes-archive");
+    create_test_note(&notes_dir, "current.md", "# Current");
+    create_test_note(&archive, "old.md", "# Old");
+
+    let mut app = md_filer::app::NoteApp::new(notes_dir.clone()).expect("Failed to create app");
+    let archive_path = archive.to_string_lossy().to_string();
+    app.add_watched_folder(archive_path.clone()).unwrap();
+    assert_eq!(app.list_notes().len(), 2);
+
+    // notes 폴더는 notes-archive의 노트를 세지 않음
+    let notes_path = notes_dir.to_string_lossy().to_string();
+    assert_eq!(app.count_notes_in_folder(&notes_path), 1);
+    assert_eq!(app.count_notes_in_folder(&archive_path), 1);
+
+    // 빠지는 폴더의 노트에 단축어를 달아 둠
+    let current = app.resolve_note("current.md").unwrap();
+    let old = app.resolve_note("old.md").unwrap();
+    app.add_shortcut(
+        &current,
+        "web",
+        LinkTarget::Url {
+            url: "https://example.com".to_string(),
+        },
+        None,
+    )
+    .unwrap();
+    app.add_shortcut(
+        &old,
+        "docs",
+        LinkTarget::Url {
+            url: "https://docs.rs".to_string(),
+        },
+        None,
+    )
+    .unwrap();
+
+    // notes 폴더를 빼도 notes-archive의 노트는 그대로
+    app.remove_watched_folder(&notes_path).unwrap();
+    let titles: Vec<_> = app
+        .list_notes()
+        .iter()
+        .map(|(_, n)| n.title.clone())
+        .collect();
+    assert_eq!(titles, vec!["Old"]);
+
+    // 빠진 노트의 단축어는 함께 지워져 다시 열어도 fsck가 깨끗함
+    drop(app);
+    let app = md_filer::app::NoteApp::new(notes_dir).expect("Failed to reopen app");
+    assert!(app.list_shortcuts(&current).is_empty());
+    assert_eq!(app.list_shortcuts(&old).len(), 1);
+    assert!(app.fsck().issues.is_empty(), "{:?}", app.fsck().issues);
+}
+
+#[test]
+fn test_folder_rules_depth_exclude_include() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let notes_dir = temp_dir.path().to_path_buf();
+    let folder = notes_dir.to_string_lossy().to_string();
+
+    create_test_note(&notes_dir, "top.md", "# Top");
+    create_test_note(&notes_dir, "projects/plan.md", "# Plan");
+    create_test_note(&notes_dir, "projects/deep/idea.md", "# Idea");
+    create_test_note(&notes_dir, "archive/old.md", "# Old");
+    create_test_note(&notes_dir, "node_modules/pkg/readme.md", "# Pkg");
+
+    let mut app = md_filer::app::NoteApp::new(notes_dir.clone()).expect("Failed to create app");
+    assert_eq!(app.list_notes().len(), 5);
+
+    // 제외 패턴
+    let mut rules = app.get_folder_rules(&folder);
+    rules.exclude = vec!["node_modules".to_string(), "archive/".to_string()];
+    app.set_folder_rules(&folder, rules.clone())
+        .expect("Failed to set rules");
+    assert_eq!(app.list_notes().len(), 3);
+
+    // 깊이 제한
+    rules.max_depth = Some(1);
+    app.set_folder_rules(&folder, rules.clone())
+        .expect("Failed to set rules");
+    assert_eq!(app.list_notes().len(), 2);
+
+    // 포함 glob
+    rules.max_depth = None;
+    rules.include = vec!["projects/**".to_string()];
+    app.set_folder_rules(&folder, rules)
+        .expect("Failed to set rules");
+    let mut titles: Vec<_> = app
+        .list_notes()
+        .iter()
+        .map(|(_, n)| n.title.clone())
+        .collect();
+    titles.sort();
+    assert_eq!(titles, vec!["Idea", "Plan"]);
+
+    // 규칙은 인덱스에 저장되어 다시 열어도 유지됨
+    drop(app);
+    let app = md_filer::app::NoteApp::new(notes_dir).expect("Failed to create app");
+    assert_eq!(app.list_notes().len(), 2);
+    assert_eq!(app.get_folder_rules(&folder).exclude.len(), 2);
+}
+
+#[test]
+fn test_folder_rules_reject_invalid_pattern() {
+    let (temp_dir, mut app) = create_test_app();
+    let folder = temp_dir.path().to_string_lossy().to_string();
+
+    let mut rules = app.get_folder_rules(&folder);
+    rules.include = vec!["[".to_string()];
+    assert!(app.set_folder_rules(&folder, rules).is_err());
+    assert!(
+        app.set_folder_rules("/no/such/folder", Default::default())
+            .is_err()
+    );
+}
+
+#[test]
+fn test_ranked_search_with_snippets() {
+    let (temp_dir, mut app) = create_test_app();
+
+    let results = app.search("rust").unwrap();
+    assert_eq!(results.len(), 1);
+    let note = app.get_note(&results[0].id).unwrap();
+    assert_eq!(note.title, "First Note");
+    let snippet = results[0].snippet.as_ref().expect("snippet missing");
+    assert!(snippet.text.contains("Rust programming"));
+
+    // 구문 검색
+    assert_eq!(app.search("\"web development\"").unwrap().len(), 1);
+    assert!(app.search("\"development web\"").unwrap().is_empty());
+
+    // 검색 인덱스 파일이 저장되고, 새 파일은 증분 색인됨
+    assert!(temp_dir.path().join(".search_index.json").exists());
+    create_test_note(temp_dir.path(), "note4.md", "# Rust Again\n\nMore rust.");
+    app.load_notes().expect("Failed to reload notes");
+    let results = app.search("rust").unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(app.get_note(&results[0].id).unwrap().title, "Rust Again");
+
+    // 삭제된 파일은 색인에서 제거됨
+    fs::remove_file(temp_dir.path().join("note4.md")).expect("Failed to delete file");
+    app.load_notes().expect("Failed to reload notes");
+    assert_eq!(app.search("rust").unwrap().len(), 1);
+    assert_eq!(app.search_index.docs.len(), 3);
+}
+
+#[test]
+fn test_search_matches_korean_words_with_particles() {
+    let (temp_dir, mut app) = create_test_app();
+    create_test_note(
+        temp_dir.path(),
+        "korean.md",
+        "# 공부 기록\n\n오늘은 러스트를 배웠다.\n#프로그래밍언어",
+    );
+    app.load_notes().expect("Failed to reload notes");
+
+    // 조사가 붙은 단어도 찾고, 일치한 줄을 보여줌
+    let results = app.search("러스트").unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(app.get_note(&results[0].id).unwrap().title, "공부 기록");
+    assert!(results[0].score > 0.0);
+    assert_eq!(
+        results[0].snippet.as_ref().map(|s| s.text.as_str()),
+        Some("오늘은 러스트를 배웠다.")
+    );
+
+    // 붙여 쓴 태그의 일부로도 찾음
+    assert_eq!(app.search("프로그래밍").unwrap().len(), 1);
+
+    // 영어 단어는 단어 단위로만 맞음: rust가 trust에 맞지 않고, -draft가 redraft를 빼지 않음
+    create_test_note(temp_dir.path(), "trust.md", "# Trust\n\nredraft the plan");
+    app.load_notes().expect("Failed to reload notes");
+    let titles = |query: &str| -> Vec<String> {
+        app.search(query)
+            .unwrap()
+            .iter()
+            .map(|hit| app.get_note(&hit.id).unwrap().title.clone())
+            .collect()
+    };
+    assert!(!titles("rust").contains(&"Trust".to_string()));
+    assert_eq!(titles("plan -draft"), vec!["Trust".to_string()]);
+}
+
+#[test]
+fn test_structured_search_query() {
+    let (_temp_dir, mut app) = create_test_app();
+
+    // 태그와 폴더 수동 지정
+    let ids: Vec<_> = app.list_notes().iter().map(|(id, _)| **id).collect();
+    for id in &ids {
+        let title = app.get_note(id).unwrap().title.clone();
+        let tags = match title.as_str() {
+            "First Note" => vec!["rust", "@work"],
+            "Second Note" => vec!["web", "@work", "draft"],
+            _ => vec!["@personal"],
+        };
+        app.index.get_mut(id).unwrap().tags = tags.into_iter().map(String::from).collect();
+    }
+    app.load_notes().expect("Failed to reload");
+
+    let titles = |query: &str| -> Vec<String> {
+        let mut titles: Vec<String> = app
+            .search(query)
+            .expect("Failed to search")
+            .iter()
+            .map(|hit| app.get_note(&hit.id).unwrap().title.clone())
+            .collect();
+        titles.sort();
+        titles
+    };
+
+    assert_eq!(titles("folder:@work -tag:draft"), vec!["First Note"]);
+    assert_eq!(
+        titles("tag:web OR folder:personal"),
+        vec!["Second Note", "Simple Note"]
+    );
+    assert_eq!(titles("title:\"second\" development"), vec!["Second Note"]);
+    assert_eq!(titles("folder:work (rust OR web)").len(), 2);
+    assert_eq!(titles("NOT folder:work"), vec!["Simple Note"]);
+    assert_eq!(titles("updated:>2000-01-01").len(), 3);
+    assert!(titles("created:<2000-01-01").is_empty());
+    assert!(app.search("(tag:rust").is_err());
+}
+
+#[test]
+fn test_tag_management() {
+    let (temp_dir, mut app) = create_test_app();
+
+    let id_of = |app: &md_filer::app::NoteApp, title: &str| {
+        *app.list_notes()
+            .iter()
+            .find(|(_, note)| note.title == title)
+            .unwrap()
+            .0
+    };
+    let first = id_of(&app, "First Note");
+    let second = id_of(&app, "Second Note");
+
+    // 추가 (중복은 무시)와 문법 검사
+    app.add_tags(&first, &["rust".to_string(), "lang".to_string()])
+        .expect("Failed to add tags");
+    app.add_tags(&first, &["rust".to_string()])
+        .expect("Failed to add tags");
+    app.add_tags(&second, &["rustlang".to_string(), "web".to_string()])
+        .expect("Failed to add tags");
+    assert_eq!(app.get_note(&first).unwrap().tags, vec!["rust", "lang"]);
+    assert!(app.add_tags(&first, &["two words".to_string()]).is_err());
+    assert!(app.add_tags(&first, &["a@b".to_string()]).is_err());
+    assert!(app.add_tags(&first, &["@".to_string()]).is_err());
+
+    // 제거
+    assert!(app.remove_tags(&first, &["missing".to_string()]).is_err());
+    app.remove_tags(&first, &["lang".to_string()])
+        .expect("Failed to remove tag");
+    assert_eq!(app.get_note(&first).unwrap().tags, vec!["rust"]);
+
+    // 이름 변경과 합치기
+    assert_eq!(app.rename_tag("web", "frontend").unwrap(), 1);
+    assert_eq!(
+        app.merge_tags(&["rustlang".to_string(), "frontend".to_string()], "rust")
+            .unwrap(),
+        1
+    );
+    assert_eq!(app.get_note(&second).unwrap().tags, vec!["rust"]);
+    assert_eq!(app.index.find_by_tag("rust").len(), 2);
+    assert!(app.rename_tag("nothing", "other").is_err());
+    assert!(app.rename_tag("rust", "@work").is_err());
+
+    // 재시작 후에도 유지되어야 함
+    drop(app);
+    let app =
+        md_filer::app::NoteApp::new(temp_dir.path().to_path_buf()).expect("Failed to reopen app");
+    assert_eq!(app.get_note(&second).unwrap().tags, vec!["rust"]);
+}
+
+#[test]
+fn test_folder_tag_is_unique() {
+    let (_temp_dir, mut app) = create_test_app();
+    let id = *app.list_notes()[0].0;
+
+    app.set_folder(&id, Some("work"))
+        .expect("Failed to set folder");
+    app.add_tags(&id, &["rust".to_string()])
+        .expect("Failed to add tag");
+    assert_eq!(
+        app.get_note(&id).unwrap().get_folder_name().as_deref(),
+        Some("work")
+    );
+
+    // 두 번째 폴더 태그는 거부
+    let err = app.add_tags(&id, &["@personal".to_string()]).unwrap_err();
+    assert!(matches!(err, Error::MultipleFolders(_)));
+    assert!(err.to_string().contains("move"));
+
+    // 이동하면 기존 폴더가 대체됨
+    app.set_folder(&id, Some("@personal"))
+        .expect("Failed to move folder");
+    assert_eq!(app.get_note(&id).unwrap().tags, vec!["@personal", "rust"]);
+    assert_eq!(app.get_notes_by_folder("@personal").len(), 1);
+    assert!(app.get_notes_by_folder("@work").is_empty());
+
+    // 폴더 해제
+    app.set_folder(&id, None).expect("Failed to clear folder");
+    assert_eq!(app.get_note(&id).unwrap().tags, vec!["rust"]);
+}
+
+#[test]
+fn test_shortcut_management_and_expansion() {
+    let (temp_dir, mut app) = create_test_app();
+    create_test_note(
+        temp_dir.path(),
+        "links.md",
+        "---\ntitle: Links\n---\nRead {docs}, then {first}.\n\n`{docs}` stays, {nope} is unknown.\n",
+    );
+    app.load_notes().expect("Failed to reload");
+
+    let id_of = |app: &md_filer::app::NoteApp, title: &str| {
+        *app.list_notes()
+            .iter()
+            .find(|(_, note)| note.title == title)
+            .unwrap()
+            .0
+    };
+    let links = id_of(&app, "Links");
+    let first = id_of(&app, "First Note");
+
+    app.add_shortcut(
+        &links,
+        "docs",
+        LinkTarget::parse("https://docs.rs").unwrap(),
+        Some("Rust docs".to_string()),
+    )
+    .expect("Failed to add shortcut");
+    app.add_shortcut(&links, "first", LinkTarget::Note { id: first }, None)
+        .expect("Failed to add shortcut");
+
+    // 중복 이름, 잘못된 이름, 없는 노트 대상은 거부
+    assert!(
+        app.add_shortcut(&links, "docs", LinkTarget::parse("x.md").unwrap(), None)
+            .is_err()
+    );
+    assert!(
+        app.add_shortcut(&links, "bad name", LinkTarget::parse("x.md").unwrap(), None)
+            .is_err()
+    );
+    assert!(
+        app.add_shortcut(
+            &links,
+            "ghost",
+            LinkTarget::Note { id: Uuid::new_v4() },
+            None
+        )
+        .is_err()
+    );
+
+    let expanded = app.expand_content(&links).unwrap();
+    assert!(
+        expanded
+            .content
+            .contains("Read [docs](https://docs.rs), then [First Note](note1.md).")
+    );
+    assert!(expanded.content.contains("`{docs}` stays"));
+    assert_eq!(expanded.unknown.len(), 1);
+    assert_eq!(expanded.unknown[0].alias, "nope");
+    // 파일 기준 줄 번호 (frontmatter: ---, title, 주입된 id, ---)
+    assert_eq!(expanded.unknown[0].line_number, 7);
+
+    // 수정과 삭제
+    app.edit_shortcut(
+        &links,
+        "docs",
+        Some(LinkTarget::parse("https://doc.rust-lang.org").unwrap()),
+        Some(String::new()),
+    )
+    .expect("Failed to edit shortcut");
+    app.remove_shortcut(&links, "first")
+        .expect("Failed to remove shortcut");
+    assert!(app.remove_shortcut(&links, "first").is_err());
+
+    // 재시작 후에도 유지되어야 함
+    drop(app);
+    let app =
+        md_filer::app::NoteApp::new(temp_dir.path().to_path_buf()).expect("Failed to reopen app");
+    let shortcuts = app.list_shortcuts(&links);
+    assert_eq!(shortcuts.len(), 1);
+    assert!(shortcuts[0].description.is_none());
+    assert!(matches!(
+        &shortcuts[0].target,
+        LinkTarget::Url { url } if url == "https://doc.rust-lang.org"
+    ));
+}
+
+#[test]
+fn test_links_and_backlinks() {
+    let (temp_dir, mut app) = create_test_app();
+    let dir = temp_dir.path();
+    create_test_note(
+        dir,
+        "hub.md",
+        "---\ntitle: Hub\n---\nSee [[First Note]] and [[note2.md|the second]].\n\
+         Also [third](sub/../note3.md) and [[Missing Page]].\n`[[First Note]]` is code.\n",
+    );
+    create_test_note(
+        dir,
+        "projects/detail.md",
+        "---\ntitle: Detail\n---\nBack to [hub](../hub.md) or [[hub]].\n",
+    );
+    app.load_notes().expect("Failed to reload");
+
+    let id_of = |app: &md_filer::app::NoteApp, title: &str| {
+        *app.list_notes()
+            .iter()
+            .find(|(_, note)| note.title == title)
+            .unwrap()
+            .0
+    };
+    let hub = id_of(&app, "Hub");
+    let first = id_of(&app, "First Note");
+    let detail = id_of(&app, "Detail");
+
+    let links = app.outgoing_links(&hub);
+    let resolved: Vec<Option<String>> = links
+        .iter()
+        .map(|link| {
+            link.resolved
+                .map(|id| app.get_note(&id).unwrap().title.clone())
+        })
+        .collect();
+    assert_eq!(
+        resolved,
+        vec![
+            Some("First Note".to_string()),
+            Some("Second Note".to_string()),
+            Some("Simple Note".to_string()),
+            None,
+        ]
+    );
+    // 파일 기준 줄 번호 (frontmatter: ---, title, 주입된 id, ---)
+    assert_eq!(links[3].raw.line_number, 6);
+
+    // UUID 링크
+    assert_eq!(app.resolve_link(&hub, &first.to_string()), Some(first));
+
+    // 본문 링크와 노트 단축어 모두 백링크로 보여야 함
+    app.add_shortcut(&detail, "start", LinkTarget::Note { id: first }, None)
+        .expect("Failed to add shortcut");
+    let backlinks = app.backlinks(&first);
+    assert_eq!(backlinks.len(), 2);
+    assert_eq!(backlinks[0].source, detail);
+    assert!(matches!(
+        &backlinks[0].via,
+        BacklinkSource::Shortcut { alias } if alias == "start"
+    ));
+    assert_eq!(backlinks[1].source, hub);
+    assert!(matches!(
+        backlinks[1].via,
+        BacklinkSource::Link { line_number: 5, .. }
+    ));
+
+    // 하위 폴더에서의 상대 경로와 파일명 링크
+    assert_eq!(app.backlinks(&hub).len(), 2);
+
+    let graph = app.link_graph();
+    assert_eq!(graph[&hub].len(), 3);
+    assert!(graph[&detail].contains(&first));
+
+    // 제목이 겹치면 파일명으로, 파일명도 겹치면 해석하지 않음
+    create_test_note(dir, "meeting.md", "---\ntitle: Meeting\n---\n");
+    create_test_note(dir, "weekly.md", "---\ntitle: Meeting\n---\n");
+    create_test_note(dir, "a/dup.md", "text");
+    create_test_note(dir, "b/dup.md", "text");
+    app.load_notes().expect("Failed to reload");
+    let meeting = app
+        .index
+        .find_by_path(&dir.join("meeting.md").to_string_lossy())
+        .map(|(id, _)| id);
+    assert_eq!(app.resolve_link(&hub, "meeting"), meeting);
+    assert_eq!(app.resolve_link(&hub, "dup"), None);
+    assert_eq!(app.resolve_link(&hub, "dup.md"), None);
+    assert!(app.resolve_link(&hub, "a/dup.md").is_some());
+}
+
+#[test]
+fn test_check_report() {
+    let (temp_dir, mut app) = create_test_app();
+    create_test_note(
+        temp_dir.path(),
+        "hub.md",
+        "---\ntitle: Hub\n---\n[[First Note]]\n\n[[Nowhere]]\n",
+    );
+    fs::write(temp_dir.path().join("exists.txt"), "x").expect("Failed to write file");
+    app.load_notes().expect("Failed to reload");
+
+    let id_of = |app: &md_filer::app::NoteApp, title: &str| {
+        *app.list_notes()
+            .iter()
+            .find(|(_, note)| note.title == title)
+            .unwrap()
+            .0
+    };
+    let hub = id_of(&app, "Hub");
+    let second = id_of(&app, "Second Note");
+
+    app.add_shortcut(&hub, "ok", LinkTarget::parse("exists.txt").unwrap(), None)
+        .expect("Failed to add shortcut");
+    app.add_shortcut(&hub, "gone", LinkTarget::parse("gone.pdf").unwrap(), None)
+        .expect("Failed to add shortcut");
+    app.add_shortcut(&hub, "second", LinkTarget::Note { id: second }, None)
+        .expect("Failed to add shortcut");
+
+    // 대상 노트 삭제
+    fs::remove_file(temp_dir.path().join("note2.md")).expect("Failed to remove file");
+    app.load_notes().expect("Failed to reload");
+
+    let report = app.check();
+    assert!(report.has_errors());
+
+    let titles: Vec<&str> = report.notes.iter().map(|n| n.title.as_str()).collect();
+    assert_eq!(titles, vec!["Hub", "Simple Note"]);
+    assert_eq!(
+        report.notes[0].issues,
+        vec![
+            Issue::UnresolvedLink {
+                line_number: 7,
+                target: "Nowhere".to_string(),
+                kind: LinkKind::Wiki,
+            },
+            Issue::MissingFile {
+                alias: "gone".to_string(),
+                path: "gone.pdf".into(),
+            },
+            Issue::MissingNote {
+                alias: "second".to_string(),
+                id: second,
+            },
+        ]
+    );
+    assert_eq!(report.notes[1].issues, vec![Issue::Orphan]);
+}
+
+#[test]
+fn test_rename_keeps_uuid_tags_and_shortcuts() {
+    let (temp_dir, mut app) = create_test_app();
+    let dir = temp_dir.path();
+
+    let id = *app
+        .list_notes()
+        .iter()
+        .find(|(_, note)| note.title == "First Note")
+        .unwrap()
+        .0;
+    app.add_tags(&id, &["rust".to_string(), "@work".to_string()])
+        .expect("Failed to add tags");
+    app.add_shortcut(
+        &id,
+        "docs",
+        LinkTarget::parse("https://docs.rs").unwrap(),
+        None,
+    )
+    .expect("Failed to add shortcut");
+    let created_at = app.index.get(&id).unwrap().created_at;
+
+    // 하위 폴더로 이동하면서 이름 변경
+    fs::create_dir_all(dir.join("archive")).expect("Failed to create dir");
+    fs::rename(dir.join("note1.md"), dir.join("archive/rust-intro.md")).expect("Failed to rename");
+    app.load_notes().expect("Failed to reload");
+
+    assert_eq!(app.notes.len(), 3);
+    let entry = app.index.get(&id).expect("entry was dropped");
+    assert_eq!(entry.filename, "rust-intro.md");
+    assert!(entry.file_path.ends_with("archive/rust-intro.md"));
+    assert_eq!(entry.tags, vec!["rust", "@work"]);
+    assert_eq!(entry.created_at, created_at);
+    assert_eq!(app.list_shortcuts(&id).len(), 1);
+}
+
+#[test]
+fn test_rename_without_uuid_matches_by_content() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    create_test_note(dir, "draft.md", "# Draft\n\nSome unique body text.");
+    create_test_note(dir, "other.md", "# Other\n\nDifferent text.");
+
+    let mut app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    let id = *app
+        .list_notes()
+        .iter()
+        .find(|(_, note)| note.title == "Draft")
+        .unwrap()
+        .0;
+    app.add_tags(&id, &["idea".to_string()])
+        .expect("Failed to add tag");
+
+    // 다른 도구가 UUID 없이 다시 쓴 파일 (본문은 같음)
+    fs::remove_file(dir.join("draft.md")).expect("Failed to remove");
+    create_test_note(dir, "final.md", "# Draft\n\nSome unique body text.");
+    app.load_notes().expect("Failed to reload");
+
+    assert_eq!(app.notes.len(), 2);
+    let entry = app.index.get(&id).expect("entry was dropped");
+    assert_eq!(entry.filename, "final.md");
+    assert_eq!(entry.tags, vec!["idea"]);
+}
+
+#[test]
+fn test_same_filename_in_different_folders() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    create_test_note(dir, "work/todo.md", "# Work Todo\n\nShip it.");
+    create_test_note(dir, "home/todo.md", "# Home Todo\n\nWater plants.");
+
+    let mut app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    assert_eq!(app.notes.len(), 2);
+
+    let ids: Vec<Uuid> = app.list_notes().iter().map(|(id, _)| **id).collect();
+    assert!(ids.iter().all(|id| app.has_duplicate_filename(id)));
+    let mut paths: Vec<String> = ids.iter().map(|id| app.display_path(id)).collect();
+    paths.sort();
+    assert_eq!(paths, vec!["home/todo.md", "work/todo.md"]);
+
+    // 다시 로드해도 각자의 UUID 유지
+    app.load_notes().expect("Failed to reload");
+    let mut reloaded: Vec<Uuid> = app.list_notes().iter().map(|(id, _)| **id).collect();
+    let mut ids = ids;
+    reloaded.sort();
+    ids.sort();
+    assert_eq!(reloaded, ids);
+}
+
+#[test]
+fn test_frontmatter_uuid_is_used_as_identity() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    let id = Uuid::new_v4();
+    create_test_note(
+        dir,
+        "imported.md",
+        &format!("---\ntitle: Imported\nid: {}\n---\nBody", id),
+    );
+
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    assert!(app.get_note(&id).is_some());
+    assert_eq!(app.get_note(&id).unwrap().id, id);
+    assert!(app.index.get(&id).is_some());
+
+    // 복사된 파일은 같은 UUID를 갖지만 별도의 노트로 취급
+    drop(app);
+    fs::copy(dir.join("imported.md"), dir.join("copy.md")).expect("Failed to copy");
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    assert_eq!(app.notes.len(), 2);
+    assert!(
+        app.index
+            .get(&id)
+            .unwrap()
+            .file_path
+            .ends_with("imported.md")
+    );
+}
+
+#[test]
+fn test_updated_at_uses_file_mtime() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    create_test_note(dir, "old.md", "# Old\n\nWritten long ago.");
+    create_test_note(dir, "new.md", "# New\n\nWritten recently.");
+
+    let set_mtime = |name: &str, secs: u64| {
+        let file = fs::File::options()
+            .write(true)
+            .open(dir.join(name))
+            .expect("Failed to open file");
+        file.set_modified(UNIX_EPOCH + Duration::from_secs(secs))
+            .expect("Failed to set mtime");
+    };
+
+    // 처음 발견한 노트의 수정일은 파일의 수정 시각 (UUID 주입으로 바뀌기 전)
+    set_mtime("old.md", 1_000_000_000);
+    set_mtime("new.md", 1_700_000_000);
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    let notes = app.list_notes();
+    assert_eq!(notes[0].1.title, "New");
+    assert_eq!(notes[1].1.title, "Old");
+    assert_eq!(notes[1].1.updated_at.timestamp(), 1_000_000_000);
+
+    // 아무것도 바뀌지 않았으면 인덱스 파일을 다시 쓰지 않고, 파일도 읽지 않음
+    let index_path = dir.join(".index.json");
+    let before = fs::metadata(&index_path).unwrap().modified().unwrap();
+    drop(app);
+    std::thread::sleep(Duration::from_millis(20));
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    assert_eq!(
+        fs::metadata(&index_path).unwrap().modified().unwrap(),
+        before
+    );
+    let (_, old) = app.list_notes()[1];
+    assert_eq!(old.updated_at.timestamp(), 1_000_000_000);
+    assert!(!old.is_loaded());
+    assert!(old.content().contains("Written long ago."));
+
+    // 본문이 같으면 수정 시각만 바뀌어도 수정일 유지, 본문이 바뀌면 수정 시각으로
+    drop(app);
+    set_mtime("old.md", 1_100_000_000);
+    set_mtime("new.md", 1_750_000_000);
+    let content = fs::read_to_string(dir.join("new.md")).unwrap();
+    fs::write(dir.join("new.md"), content + "\nMore.").unwrap();
+    set_mtime("new.md", 1_750_000_000);
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    let notes = app.list_notes();
+    assert_eq!(notes[0].1.updated_at.timestamp(), 1_750_000_000);
+    assert_eq!(notes[1].1.updated_at.timestamp(), 1_000_000_000);
+
+    // frontmatter만 고쳐도 (제목 변경) 수정일은 수정 시각으로
+    drop(app);
+    let content = fs::read_to_string(dir.join("old.md")).unwrap();
+    assert!(content.contains("title: Old\n"));
+    fs::write(
+        dir.join("old.md"),
+        content.replace("title: Old\n", "title: Older\n"),
+    )
+    .unwrap();
+    set_mtime("old.md", 1_200_000_000);
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    let notes = app.list_notes();
+    assert_eq!(notes[1].1.title, "Older");
+    assert_eq!(notes[1].1.updated_at.timestamp(), 1_200_000_000);
+}
+
+#[test]
+fn test_refresh_only_rereads_changed_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    create_test_note(dir, "a.md", "# A\n\nalpha");
+    create_test_note(dir, "b.md", "# B\n\nbravo");
+
+    let mut app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    let id_of = |app: &md_filer::app::NoteApp, title: &str| {
+        *app.list_notes()
+            .iter()
+            .find(|(_, note)| note.title == title)
+            .unwrap()
+            .0
+    };
+    let a = id_of(&app, "A");
+    let b = id_of(&app, "B");
+
+    // 크기와 수정 시각이 같으면 다시 읽지 않음
+    let path = dir.join("a.md");
+    let mtime = fs::metadata(&path).unwrap().modified().unwrap();
+    let same_size = fs::read_to_string(&path).unwrap().replace("alpha", "ALPHA");
+    fs::write(&path, same_size).unwrap();
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+
+    // 다른 파일은 실제로 수정
+    let path = dir.join("b.md");
+    let changed = fs::read_to_string(&path)
+        .unwrap()
+        .replace("bravo", "bravo charlie");
+    fs::write(&path, changed).unwrap();
+
+    app.load_notes().expect("Failed to reload");
+    assert!(app.get_note(&a).unwrap().content().contains("alpha"));
+    assert!(
+        app.get_note(&b)
+            .unwrap()
+            .content()
+            .contains("bravo charlie")
+    );
+    assert_eq!(app.search("charlie").unwrap().len(), 1);
+}
+
+#[test]
+fn test_refresh_paths_applies_incremental_changes() {
+    let (temp_dir, mut app) = create_test_app();
+    let dir = temp_dir.path();
+    let id_of = |app: &md_filer::app::NoteApp, title: &str| {
+        *app.list_notes()
+            .iter()
+            .find(|(_, note)| note.title == title)
+            .unwrap()
+            .0
+    };
+    let first = id_of(&app, "First Note");
+    let second = id_of(&app, "Second Note");
+    app.add_tags(&first, &["rust".to_string()])
+        .expect("Failed to add tag");
+
+    // 생성
+    create_test_note(dir, "sub/new.md", "# New Note\n\nfresh content");
+    let changes = app
+        .refresh_paths(&[dir.join("sub/new.md")])
+        .expect("Failed to refresh");
+    assert!(matches!(changes.as_slice(), [NoteChange::Added { .. }]));
+    assert_eq!(app.search("fresh").unwrap().len(), 1);
+
+    // 이동 (태그 유지)
+    fs::rename(dir.join("note1.md"), dir.join("sub/renamed.md")).expect("Failed to rename");
+    let changes = app
+        .refresh_paths(&[dir.join("note1.md"), dir.join("sub/renamed.md")])
+        .expect("Failed to refresh");
+    assert!(matches!(
+        changes.as_slice(),
+        [NoteChange::Moved { id, to, .. }] if *id == first && to.ends_with("renamed.md")
+    ));
+    assert_eq!(app.get_note(&first).unwrap().filename, "renamed.md");
+    assert_eq!(app.get_note(&first).unwrap().tags, vec!["rust"]);
+
+    // 삭제
+    fs::remove_file(dir.join("note2.md")).expect("Failed to remove");
+    let changes = app
+        .refresh_paths(&[dir.join("note2.md")])
+        .expect("Failed to refresh");
+    assert_eq!(
+        changes,
+        vec![NoteChange::Removed {
+            id: second,
+            path: dir.join("note2.md").to_string_lossy().to_string(),
+        }]
+    );
+    assert!(app.get_note(&second).is_none());
+    assert!(app.search("development").unwrap().is_empty());
+
+    // 폴더째 삭제
+    fs::remove_dir_all(dir.join("sub")).expect("Failed to remove dir");
+    let changes = app
+        .refresh_paths(&[dir.join("sub")])
+        .expect("Failed to refresh");
+    assert_eq!(changes.len(), 2);
+    assert_eq!(app.notes.len(), 1);
+
+    // 인덱스 파일도 최신 상태여야 함
+    let (index, _) = md_filer::index::NoteIndex::load(&dir.join(".index.json")).unwrap();
+    assert_eq!(index.len(), 1);
+}
+
+#[test]
+fn test_watcher_reports_changed_files() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    let watcher = NoteWatcher::new(app.list_watched_folders()).expect("Failed to start watcher");
+
+    create_test_note(dir, "watched.md", "# Watched");
+    fs::write(dir.join(".hidden.json"), "{}").expect("Failed to write file");
+
+    let mut paths = Vec::new();
+    for _ in 0..50 {
+        std::thread::sleep(Duration::from_millis(20));
+        paths.extend(watcher.pending());
+        if !paths.is_empty() {
+            break;
+        }
+    }
+    assert!(paths.iter().any(|p| p.ends_with("watched.md")));
+    assert!(!paths.iter().any(|p| p.ends_with(".hidden.json")));
+}
+
+#[test]
+fn test_errors_are_typed() {
+    let (temp_dir, mut app) = create_test_app();
+
+    let missing = temp_dir.path().join("missing");
+    let err = app
+        .add_watched_folder(missing.to_string_lossy().to_string())
+        .unwrap_err();
+    assert!(matches!(err, Error::FolderMissing(path) if path == missing));
+
+    let err = app.search("title:\"open").unwrap_err();
+    assert!(matches!(err, Error::Query(QueryError::UnclosedQuote)));
+
+    // 손상된 인덱스는 경로와 원인 오류를 함께 전달
+    drop(app);
+    let index_path = temp_dir.path().join(".index.json");
+    fs::write(&index_path, "{ not json").unwrap();
+    match md_filer::app::NoteApp::new(temp_dir.path().to_path_buf()) {
+        Err(err @ Error::Corrupt { .. }) => {
+            assert!(std::error::Error::source(&err).is_some());
+            assert!(matches!(err, Error::Corrupt { path, .. } if path == index_path));
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("corrupt index was accepted"),
+    }
+}
+
+#[test]
+fn test_reporter_receives_events() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    create_test_note(dir, "plain.md", "# Plain\n\nno frontmatter");
+    create_test_note(dir, "broken.md", "---\ntitle: [unclosed\n---\nbody");
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let sink = Rc::clone(&events);
+    let app = md_filer::app::NoteApp::with_reporter(dir.to_path_buf(), move |event| {
+        sink.borrow_mut().push(event)
+    })
+    .expect("Failed to create app");
+    let id = *app.list_notes()[0].0;
+
+    let plain = dir.join("plain.md").to_string_lossy().to_string();
+    let broken = dir.join("broken.md").to_string_lossy().to_string();
+    {
+        let events = events.borrow();
+        assert!(events.iter().any(|e| matches!(
+            e,
+            NoteEvent::NoteDiscovered { id: found, path } if *found == id && *path == plain
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            NoteEvent::UuidInjected { id: found, .. } if *found == id
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            NoteEvent::ParseFailed { path, error: Error::Yaml(_) } if *path == broken
+        )));
+    }
+    drop(app);
+
+    // 파일을 지우고 다시 열면 삭제 이벤트
+    events.borrow_mut().clear();
+    fs::remove_file(dir.join("plain.md")).unwrap();
+    let sink = Rc::clone(&events);
+    md_filer::app::NoteApp::with_reporter(dir.to_path_buf(), move |event| {
+        sink.borrow_mut().push(event)
+    })
+    .expect("Failed to reopen app");
+    let events = events.borrow();
+    assert!(events.iter().any(|e| matches!(
+        e,
+        NoteEvent::NoteDeleted { id: found, path } if *found == id && *path == plain
+    )));
+    assert!(
+        events
+            .iter()
+            .any(|e| matches!(e, NoteEvent::IndexCleaned { removed: 1 }))
+    );
+}
+
+#[test]
+fn test_notes_dir_is_locked_while_app_is_open() {
+    let (temp_dir, app) = create_test_app();
+
+    match md_filer::app::NoteApp::new(temp_dir.path().to_path_buf()) {
+        Err(Error::Locked(path)) => assert_eq!(path, temp_dir.path()),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("second session opened a locked directory"),
+    }
+
+    drop(app);
+    md_filer::app::NoteApp::new(temp_dir.path().to_path_buf()).expect("Failed to reopen app");
+}
+
+#[test]
+fn test_read_only_app_opens_locked_dir_without_writing() {
+    let (temp_dir, app) = create_test_app();
+    let dir = temp_dir.path();
+
+    // watch 등이 잠근 상태에서 새로 생긴 UUID 없는 노트
+    create_test_note(dir, "fresh.md", "# Fresh\n\nnot indexed yet");
+    let index_before = fs::read(dir.join(".index.json")).unwrap();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let sink = events.clone();
+    let mut reader =
+        md_filer::app::NoteApp::for_reading(dir.to_path_buf(), move |e| sink.borrow_mut().push(e))
+            .expect("Failed to open locked dir for reading");
+
+    assert!(reader.is_read_only());
+    assert_eq!(reader.list_notes().len(), app.notes.len() + 1);
+    assert!(
+        events
+            .borrow()
+            .iter()
+            .any(|e| matches!(e, NoteEvent::OpenedReadOnly { .. }))
+    );
+
+    // 노트 파일과 인덱스는 그대로
+    assert_eq!(
+        fs::read_to_string(dir.join("fresh.md")).unwrap(),
+        "# Fresh\n\nnot indexed yet"
+    );
+    assert_eq!(fs::read(dir.join(".index.json")).unwrap(), index_before);
+
+    // 표시한 결과 목록은 메모리에만 기억
+    let ids: Vec<Uuid> = reader.list_notes().iter().map(|(id, _)| **id).collect();
+    reader.remember_results(ids.clone());
+    assert_eq!(reader.last_results(), ids.as_slice());
+    assert!(!dir.join(".last_results.json").exists());
+
+    // 바꾸는 명령은 잠금 오류
+    let id = *reader.list_notes()[0].0;
+    match reader.add_tags(&id, &["late".to_string()]) {
+        Err(Error::Locked(path)) => assert_eq!(path, dir),
+        other => panic!("expected Locked, got {:?}", other.map(|_| ())),
+    }
+
+    // 잠금을 얻을 수 있으면 평소처럼 씀
+    drop(reader);
+    drop(app);
+    let app =
+        md_filer::app::NoteApp::for_reading(dir.to_path_buf(), |_| {}).expect("Failed to open app");
+    assert!(!app.is_read_only());
+    assert!(Note::has_uuid_in_frontmatter(
+        &fs::read_to_string(dir.join("fresh.md")).unwrap()
+    ));
+}
+
+#[test]
+fn test_leftover_note_temp_files_are_recovered() {
+    let (temp_dir, app) = create_test_app();
+    let dir = temp_dir.path();
+    drop(app);
+
+    // 새 노트를 처음 쓰다 중단: 원본 없이 임시 파일만 남음
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("sub/.draft.md.tmp"), "# Draft\n\nsaved").unwrap();
+    // 기존 노트를 고쳐 쓰다 중단: 원본이 이전 내용 그대로 남아 있음
+    let original = fs::read_to_string(dir.join("note1.md")).unwrap();
+    fs::write(dir.join(".note1.md.tmp"), "half writ").unwrap();
+    // 관리 대상이 아닌 임시 파일은 건드리지 않음
+    fs::write(dir.join(".other.txt.tmp"), "keep").unwrap();
+
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to recover app");
+    assert!(!dir.join("sub/.draft.md.tmp").exists());
+    // 복구된 노트는 이어서 UUID가 주입됨
+    assert!(
+        fs::read_to_string(dir.join("sub/draft.md"))
+            .unwrap()
+            .ends_with("# Draft\n\nsaved")
+    );
+    assert!(app.list_notes().iter().any(|(_, n)| n.title == "Draft"));
+    assert!(!dir.join(".note1.md.tmp").exists());
+    assert_eq!(fs::read_to_string(dir.join("note1.md")).unwrap(), original);
+    assert!(dir.join(".other.txt.tmp").exists());
+}
+
+#[test]
+fn test_leftover_temp_files_are_recovered() {
+    let (temp_dir, app) = create_test_app();
+    let dir = temp_dir.path();
+    let count = app.notes.len();
+    drop(app);
+
+    // 인덱스 저장 도중 중단: 원본은 잘렸고 완성된 임시 파일이 남음
+    let index_path = dir.join(".index.json");
+    let saved = fs::read(&index_path).unwrap();
+    fs::write(dir.join(".index.json.tmp"), &saved).unwrap();
+    fs::write(&index_path, &saved[..saved.len() / 2]).unwrap();
+    // 단축어 저장 도중 중단: 반쯤 쓴 임시 파일만 남음
+    fs::write(dir.join(".shortcuts.json.tmp"), "{\"shortcuts\": {").unwrap();
+
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to recover app");
+    assert_eq!(app.notes.len(), count);
+    assert!(!dir.join(".index.json.tmp").exists());
+    assert!(!dir.join(".shortcuts.json.tmp").exists());
+}
+
+#[test]
+fn test_legacy_index_is_migrated() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    let id = Uuid::new_v4();
+    create_test_note(dir, "legacy.md", &format!("---\nid: {}\n---\n# Legacy", id));
+
+    // version과 file_path가 없던 시절의 인덱스
+    let legacy = format!(
+        r#"{{"mappings": {{"{}": {{"filename": "legacy.md", "title": "Legacy",
+            "created_at": "2024-01-01T00:00:00Z", "updated_at": "2024-01-01T00:00:00Z",
+            "tags": ["@work", "old"]}}}}}}"#,
+        id
+    );
+    fs::write(dir.join(".index.json"), &legacy).unwrap();
+    fs::write(dir.join(".shortcuts.json"), r#"{"shortcuts": {}}"#).unwrap();
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let sink = Rc::clone(&events);
+    let app = md_filer::app::NoteApp::with_reporter(dir.to_path_buf(), move |event| {
+        sink.borrow_mut().push(event)
+    })
+    .expect("Failed to migrate");
+
+    // 태그와 생성일이 그대로 유지되고 경로가 채워짐
+    let entry = app.index.get(&id).expect("entry kept");
+    assert_eq!(entry.tags, vec!["@work", "old"]);
+    assert_eq!(entry.created_at.to_rfc3339(), "2024-01-01T00:00:00+00:00");
+    assert_eq!(Path::new(&entry.file_path), dir.join("legacy.md"));
+    assert_eq!(app.index.version, md_filer::migrate::INDEX_VERSION);
+
+    // 이전 파일은 백업되고 이벤트로 알림
+    assert_eq!(
+        fs::read_to_string(dir.join(".index.json.v0.bak")).unwrap(),
+        legacy
+    );
+    assert!(dir.join(".shortcuts.json.v0.bak").exists());
+    assert_eq!(
+        events
+            .borrow()
+            .iter()
+            .filter(|e| matches!(e, NoteEvent::Migrated(m) if m.from == 0 && m.to == 1))
+            .count(),
+        2
+    );
+    drop(app);
+
+    // 더 새로운 버전의 파일은 열지 않음
+    fs::write(
+        dir.join(".index.json"),
+        r#"{"version": 999, "mappings": {}}"#,
+    )
+    .unwrap();
+    match md_filer::app::NoteApp::new(dir.to_path_buf()) {
+        Err(Error::UnsupportedVersion { version, .. }) => assert_eq!(version, 999),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("newer index was opened"),
+    }
+}
+
+#[test]
+fn test_fsck_repairs_duplicate_uuid() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let dir = temp_dir.path();
+    let id = Uuid::new_v4();
+    create_test_note(
+        dir,
+        "original.md",
+        &format!("---\ntitle: Original\nid: {}\n---\nBody", id),
+    );
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    drop(app);
+
+    // 파일을 복사하면 두 파일이 같은 UUID를 가짐
+    fs::copy(dir.join("original.md"), dir.join("copy.md")).expect("Failed to copy");
+    let mut app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+
+    let report = app.fsck();
+    assert_eq!(report.issues.len(), 1);
+    let FsckIssue::DuplicateId {
+        id: shared,
+        path,
+        kept,
+        assigned,
+    } = &report.issues[0]
+    else {
+        panic!("unexpected issue: {:?}", report.issues[0]);
+    };
+    assert_eq!(*shared, id);
+    assert!(path.ends_with("copy.md"));
+    assert!(kept.ends_with("original.md"));
+
+    // 원본은 UUID를 유지하고 복사본에는 인덱스에 등록된 새 UUID가 기록됨
+    assert_eq!(app.repair(&report.issues).unwrap(), 1);
+    let copy = fs::read_to_string(dir.join("copy.md")).unwrap();
+    assert_eq!(Note::id_in_frontmatter(&copy), Some(*assigned));
+    let original = fs::read_to_string(dir.join("original.md")).unwrap();
+    assert_eq!(Note::id_in_frontmatter(&original), Some(id));
+    assert!(app.fsck().is_clean());
+
+    // 다시 열어도 두 노트가 각자의 UUID를 가짐
+    drop(app);
+    let app = md_filer::app::NoteApp::new(dir.to_path_buf()).expect("Failed to create app");
+    assert!(app.get_note(&id).is_some());
+    assert!(app.get_note(assigned).is_some());
+    assert!(app.fsck().is_clean());
+}
+
+#[test]
+fn test_fsck_repairs_index_and_shortcuts() {
+    let (_temp_dir, mut app) = create_test_app();
+    assert!(app.fsck().is_clean());
+
+    let (id, _) = app
+        .index
+        .find_by_path(&app.notes_dir.join("note1.md").to_string_lossy())
+        .expect("note1 indexed");
+    let ghost = Uuid::new_v4();
+    app.index.get_mut(&id).unwrap().title = "Old Title".to_string();
+    app.add_shortcut(&id, "docs", LinkTarget::Note { id }, None)
+        .unwrap();
+    app.shortcuts
+        .shortcuts
+        .insert(ghost, app.shortcuts.shortcuts[&id].clone());
+    fs::remove_file(app.notes_dir.join("note2.md")).unwrap();
+
+    let report = app.fsck();
+    assert_eq!(
+        report.in_category(FsckCategory::File).count(),
+        1,
+        "{:?}",
+        report.issues
+    );
+    assert!(report.issues.contains(&FsckIssue::StaleTitle {
+        id,
+        path: app.index.get(&id).unwrap().file_path.clone(),
+        indexed: "Old Title".to_string(),
+        actual: "First Note".to_string(),
+    }));
+    assert!(report.issues.contains(&FsckIssue::OrphanShortcuts {
+        id: ghost,
+        aliases: vec!["docs".to_string()],
+    }));
+
+    assert_eq!(app.repair(&report.issues).unwrap(), 3);
+    assert!(app.fsck().is_clean());
+    assert_eq!(app.index.get(&id).unwrap().title, "First Note");
+    assert_eq!(app.notes.len(), 2);
+    assert_eq!(app.list_shortcuts(&id).len(), 1);
+}
+
+#[test]
+fn test_create_note() {
+    let (_temp_dir, mut app) = create_test_app();
+    let wiki = app.notes_dir.join("wiki");
+    fs::create_dir(&wiki).unwrap();
+    app.add_watched_folder(wiki.to_string_lossy().to_string())
+        .unwrap();
+
+    let id = app
+        .create_note("First Note", Some(&wiki.to_string_lossy()), &[], None)
+        .expect("Failed to create note");
+    let entry = app.index.get(&id).unwrap();
+    assert_eq!(Path::new(&entry.file_path), wiki.join("first-note.md"));
+    assert_eq!(entry.title, "First Note");
+    let content = fs::read_to_string(wiki.join("first-note.md")).unwrap();
+    assert_eq!(Note::id_in_frontmatter(&content), Some(id));
+
+    // 같은 제목이면 번호를 붙이고, 새 노트는 바로 검색됨
+    let second = app
+        .create_note("First Note", Some(&wiki.to_string_lossy()), &[], None)
+        .unwrap();
+    assert!(
+        app.index
+            .get(&second)
+            .unwrap()
+            .file_path
+            .ends_with("first-note-2.md")
+    );
+    assert!(app.fsck().is_clean());
+
+    assert!(matches!(
+        app.create_note("  ", None, &[], None),
+        Err(Error::EmptyTitle)
+    ));
+    assert!(matches!(
+        app.create_note("Nowhere", Some("/not/watched"), &[], None),
+        Err(Error::FolderNotWatched(_))
+    ));
+    assert!(matches!(
+        app.create_note("Two", None, &["@a".to_string(), "@b".to_string()], None),
+        Err(Error::MultipleFolders(_))
+    ));
+    assert!(!app.notes_dir.join("two.md").exists());
+}
+
+#[test]
+fn test_resolve_note_references() {
+    let (_temp_dir, mut app) = create_test_app();
+    let id_of = |app: &md_filer::app::NoteApp, title: &str| {
+        *app.notes
+            .iter()
+            .find(|(_, note)| note.title == title)
+            .unwrap()
+            .0
+    };
+    let first = id_of(&app, "First Note");
+    let second = id_of(&app, "Second Note");
+
+    // 전체 UUID, UUID 앞부분 (하이픈 없이도), 파일명, 제목
+    assert_eq!(app.resolve_note(&first.to_string()).unwrap(), first);
+    assert_eq!(app.resolve_note(&first.to_string()[..8]).unwrap(), first);
+    assert_eq!(
+        app.resolve_note(&first.simple().to_string()[..12]).unwrap(),
+        first
+    );
+    assert_eq!(app.resolve_note("note2.md").unwrap(), second);
+    assert_eq!(app.resolve_note("note2").unwrap(), second);
+    assert_eq!(app.resolve_note("second note").unwrap(), second);
+
+    // 비슷한 제목은 하나뿐일 때만 선택
+    assert_eq!(app.resolve_note("Secnd Note").unwrap(), second);
+    assert!(matches!(
+        app.resolve_note("note"),
+        Err(Error::AmbiguousNoteRef { candidates, .. }) if candidates.len() == 3
+    ));
+    assert!(matches!(
+        app.resolve_note("zzzz-nothing"),
+        Err(Error::NoteRefNotFound(_))
+    ));
+    // 너무 짧은 이름은 부분 문자열/유사도로 찾지 않음
+    assert!(matches!(
+        app.resolve_note("n"),
+        Err(Error::NoteRefNotFound(_))
+    ));
+
+    // #N은 마지막으로 표시한 결과를 가리키고 다시 열어도 유지됨
+    assert!(matches!(app.resolve_note("#1"), Err(Error::NoResultSet)));
+    assert!(matches!(app.resolve_note("1"), Err(Error::NoResultSet)));
+    app.remember_results(vec![second, first]);
+    assert_eq!(app.resolve_note("#1").unwrap(), second);
+    assert_eq!(app.resolve_note("1").unwrap(), second);
+    assert!(matches!(
+        app.resolve_note("#3"),
+        Err(Error::ResultOutOfRange { number: 3, len: 2 })
+    ));
+    assert!(matches!(
+        app.resolve_note("3"),
+        Err(Error::ResultOutOfRange { number: 3, len: 2 })
+    ));
+
+    let dir = app.notes_dir.clone();
+    drop(app);
+    create_test_note(&dir, "2024.md", "# Plans for 2024");
+    let app = md_filer::app::NoteApp::new(dir).expect("Failed to create app");
+    assert_eq!(app.resolve_note("#2").unwrap(), first);
+
+    // # 없는 숫자가 결과 번호 범위 밖이면 파일명/UUID 앞부분으로 찾음
+    let plans = id_of(&app, "Plans for 2024");
+    assert_eq!(app.resolve_note("2024").unwrap(), plans);
+    assert!(matches!(
+        app.resolve_note("#2024"),
+        Err(Error::ResultOutOfRange {
+            number: 2024,
+            len: 2
+        })
+    ));
+}
+
+#[test]
+fn test_exact_name_wins_over_uuid_prefix() {
+    let (temp_dir, mut app) = create_test_app();
+    let dir = temp_dir.path();
+    create_test_note(dir, "cafe.md", "# Coffee\n\nbeans");
+    create_test_note(
+        dir,
+        "menu.md",
+        "---\nid: cafe0000-0000-4000-8000-000000000001\n---\n# Menu\n",
+    );
+    create_test_note(
+        dir,
+        "cow.md",
+        "---\nid: beef0000-0000-4000-8000-000000000002\ntitle: dead-beef\n---\nmoo\n",
+    );
+    app.load_notes().expect("Failed to reload notes");
+
+    let id_of = |title: &str| {
+        *app.notes
+            .iter()
+            .find(|(_, note)| note.title == title)
+            .unwrap()
+            .0
+    };
+
+    // 파일명이나 제목이 정확히 같으면 UUID 앞부분보다 우선
+    assert_eq!(app.resolve_note("cafe").unwrap(), id_of("Coffee"));
+    assert_eq!(app.resolve_note("cafe.md").unwrap(), id_of("Coffee"));
+    assert_eq!(app.resolve_note("dead-beef").unwrap(), id_of("dead-beef"));
+
+    // 이름이 맞지 않으면 UUID 앞부분으로 찾음
+    assert_eq!(app.resolve_note("cafe0000").unwrap(), id_of("Menu"));
+    assert_eq!(app.resolve_note("beef").unwrap(), id_of("dead-beef"));
+}
+
+#[test]
+fn test_export_html_site() {
+    let (_temp_dir, mut app) = create_test_app();
+    create_test_note(
+        &app.notes_dir,
+        "links.md",
+        "---\ntitle: Links\n---\n[[First Note]] [둘째](note2.md) [[Nowhere]]\n",
+    );
+    app.load_notes().expect("Failed to reload notes");
+
+    let links = app.resolve_note("links.md").unwrap();
+    let first = app.resolve_note("note1.md").unwrap();
+    let second = app.resolve_note("note2.md").unwrap();
+    app.add_tags(&links, &["rust".to_string(), "@pub".to_string()])
+        .unwrap();
+
+    // 두 번째 노트는 내보내지 않음
+    let out = TempDir::new().expect("Failed to create temp dir");
+    let report = app.export_html(out.path(), &[links, first]).unwrap();
+    assert_eq!(report.notes, 2);
+    assert_eq!((report.tags, report.folders), (1, 1));
+    assert_eq!(report.unresolved_links, 2);
+    assert!(!out.path().join("notes/note2.html").exists());
+
+    let page = fs::read_to_string(out.path().join("notes/links.html")).unwrap();
+    assert!(page.contains("<a href=\"note1.html\">First Note</a>"));
+    assert!(page.contains("<span class=\"broken-link\" title=\"note2.md\">둘째</span>"));
+    assert!(page.contains("href=\"../tags/rust.html\""));
+    assert!(page.contains("href=\"../folders/pub.html\""));
+
+    // 링크한 노트는 대상 페이지의 백링크에 표시
+    let page = fs::read_to_string(out.path().join("notes/note1.html")).unwrap();
+    assert!(page.contains("<a href=\"../notes/links.html\">Links</a>"));
+
+    let tag_page = fs::read_to_string(out.path().join("tags/rust.html")).unwrap();
+    assert!(tag_page.contains("Links"));
+    assert!(out.path().join("index.html").exists());
+    assert!(out.path().join("style.css").exists());
+
+    let search: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(out.path().join("search.json")).unwrap()).unwrap();
+    let entries = search.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert!(
+        entries
+            .iter()
+            .all(|entry| entry["url"] != "notes/note2.html")
+    );
+    assert!(app.get_note(&second).is_some());
+}
+
+#[test]
+fn test_meta_import_plan_and_apply() {
+    let (_temp_dir, mut app) = create_test_app();
+    let first = app.resolve_note("note1.md").unwrap();
+    app.add_tags(&first, &["rust".to_string(), "@work".to_string()])
+        .unwrap();
+
+    // UUID로 찾는 줄, 경로로 찾는 줄, 못 찾는 줄
+    let input = "uuid,path,tags,folder,created,updated\n\
+                 ,note2.md,web 개발,,2023-05-01T09:00:00Z,2023-06-01T12:00:00Z\n\
+                 00000000-0000-0000-0000-000000000000,nowhere.md,,,,\n"
+        .to_string()
+        + &format!("{},,rust,work,,\n", first);
+    let updates = meta::parse(&input, MetaFormat::Csv).unwrap();
+    let plan = app.plan_meta_import(&updates).unwrap();
+
+    assert_eq!(plan.unchanged, 1);
+    assert_eq!(plan.unmatched.len(), 1);
+    assert_eq!(plan.unmatched[0].line, 3);
+    assert_eq!(plan.changes.len(), 1);
+    assert_eq!(plan.changes[0].path, "note2.md");
+    assert!(matches!(
+        &plan.changes[0].changes[..],
+        [
+            FieldChange::Tags { .. },
+            FieldChange::Created { .. },
+            FieldChange::Updated { .. }
+        ]
+    ));
+
+    // 미리보기는 인덱스를 바꾸지 않음
+    let second = plan.changes[0].id;
+    assert!(app.get_note(&second).unwrap().tags.is_empty());
+
+    assert_eq!(app.apply_meta_import(&plan.changes).unwrap(), 1);
+    let dir = app.notes_dir.clone();
+    drop(app);
+    let app = md_filer::app::NoteApp::new(dir.clone()).expect("Failed to create app");
+    let note = app.get_note(&second).unwrap();
+    assert_eq!(note.tags, vec!["web", "개발"]);
+    assert_eq!(note.created_at.to_rfc3339(), "2023-05-01T09:00:00+00:00");
+    // 가져온 수정일은 파일 수정 시각보다 우선 (본문이 바뀌기 전까지)
+    assert_eq!(note.updated_at.to_rfc3339(), "2023-06-01T12:00:00+00:00");
+
+    // 파일을 다시 써도 본문이 같으면 가져온 수정일 유지
+    drop(app);
+    let path = dir.join("note2.md");
+    let content = fs::read_to_string(&path).unwrap();
+    fs::write(&path, &content).unwrap();
+    let file = fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(UNIX_EPOCH + Duration::from_secs(1_800_000_000))
+        .unwrap();
+    drop(file);
+    let app = md_filer::app::NoteApp::new(dir).expect("Failed to create app");
+    assert_eq!(
+        app.get_note(&second).unwrap().updated_at.to_rfc3339(),
+        "2023-06-01T12:00:00+00:00"
+    );
+
+    // 내보낸 메타데이터를 다시 가져오면 바뀌는 것이 없음
+    let exported = meta::to_string(&app.export_meta(), MetaFormat::Jsonl).unwrap();
+    let plan = app
+        .plan_meta_import(&meta::parse(&exported, MetaFormat::Jsonl).unwrap())
+        .unwrap();
+    assert!(plan.changes.is_empty());
+    assert_eq!(plan.unchanged, app.notes.len());
+
+    // 같은 노트를 두 번 가리키면 오류
+    let input = format!("uuid,path\n{},\n,note1.md\n", first);
+    assert!(matches!(
+        app.plan_meta_import(&meta::parse(&input, MetaFormat::Csv).unwrap()),
+        Err(Error::InvalidMetaRecord {
+            line: 3,
+            reason: MetaRecordError::DuplicateNote { first_line: 2 }
+        })
+    ));
+}